@@ -0,0 +1,392 @@
+//! Batch module
+//!
+//! This module contains the code to find the mapfiles of many product variants (ex: board variants built from the
+//! same tree) and to build a combined matrix of their region, segment and library sizes, one column per variant
+
+use crate::{input::Compression, trend::escape_csv, Parser};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Extensions of files found in batch directories, after removing the compression extension
+const MAPFILE_EXTENSIONS: [&str; 2] = ["map", "xml"];
+
+/// Enum containing the matrix row types
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum BatchKind {
+    /// Region used size
+    Region,
+    /// Segment size
+    Segment,
+    /// Sum of library entry sizes
+    Library,
+}
+
+impl BatchKind {
+    /// All kinds, in output order
+    pub const ALL: [BatchKind; 3] = [BatchKind::Region, BatchKind::Segment, BatchKind::Library];
+
+    /// Returns kind name as used in outputs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BatchKind::Region => "region",
+            BatchKind::Segment => "segment",
+            BatchKind::Library => "library",
+        }
+    }
+}
+
+/// Structure containing the sizes of a region, segment or library in every variant
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BatchRow {
+    /// Row type
+    kind: BatchKind,
+    /// Region, segment or library name
+    name: String,
+    /// Size in each variant, in variant order, or [None](Option::None) if missing from the variant
+    sizes: Vec<Option<u64>>,
+}
+
+impl BatchRow {
+    /// Get row [kind](#structfield.kind)
+    pub fn get_kind(&self) -> BatchKind {
+        self.kind
+    }
+
+    /// Get row [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get [sizes](#structfield.sizes) per variant
+    pub fn get_sizes(&self) -> &[Option<u64>] {
+        &self.sizes
+    }
+
+    /// Returns the number of variants containing the item
+    pub fn get_variant_count(&self) -> usize {
+        self.sizes.iter().filter(|s| s.is_some()).count()
+    }
+
+    /// Returns [true] if the item is contained by all variants (ex: library shared by all variants)
+    pub fn is_shared(&self) -> bool {
+        self.get_variant_count() == self.sizes.len()
+    }
+}
+
+/// Structure containing the combined size matrix of several variants
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct BatchReport {
+    /// Variant names, in column order
+    variants: Vec<String>,
+    /// Regions, then segments, then libraries, each sorted by name
+    rows: Vec<BatchRow>,
+}
+
+impl BatchReport {
+    /// Creates a new [BatchReport] from pairs of variant name and [Parser] results
+    ///
+    /// Only [Parser::get_allocated_segments] are reported, and only their entries are counted in library sizes
+    pub fn new<'a, I>(variants: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a Parser)>,
+    {
+        let variants = variants.into_iter().collect::<Vec<_>>();
+        let mut sizes: BTreeMap<(BatchKind, String), Vec<Option<u64>>> = BTreeMap::new();
+        let mut add = |kind, name: &str, index: usize, size: u64| {
+            let row = sizes
+                .entry((kind, name.to_string()))
+                .or_insert_with(|| vec![None; variants.len()]);
+            *row[index].get_or_insert(0) += size;
+        };
+
+        for (index, (_, parser)) in variants.iter().enumerate() {
+            for region in parser.get_memory_regions() {
+                add(
+                    BatchKind::Region,
+                    region.get_name(),
+                    index,
+                    region.get_used_size(),
+                );
+            }
+
+            for segment in parser.get_allocated_segments() {
+                if let Some(size) = segment.get_size() {
+                    add(BatchKind::Segment, segment.get_name(), index, size);
+                }
+                for entry in segment.get_entries() {
+                    if let Some(library_name) = entry.get_library_name() {
+                        add(BatchKind::Library, library_name, index, entry.get_size());
+                    }
+                }
+            }
+        }
+
+        Self {
+            variants: variants.iter().map(|(name, _)| name.to_string()).collect(),
+            rows: sizes
+                .into_iter()
+                .map(|((kind, name), sizes)| BatchRow { kind, name, sizes })
+                .collect(),
+        }
+    }
+
+    /// Get [variants](#structfield.variants) names
+    pub fn get_variants(&self) -> &[String] {
+        &self.variants
+    }
+
+    /// Get all [rows](#structfield.rows)
+    pub fn get_rows(&self) -> &[BatchRow] {
+        &self.rows
+    }
+
+    /// Get rows of `kind`
+    pub fn get_rows_of_kind(&self, kind: BatchKind) -> Vec<&BatchRow> {
+        self.rows.iter().filter(|r| r.kind == kind).collect()
+    }
+
+    /// Get row of `kind` named `name` or [None](Option::None)
+    pub fn get_row(&self, kind: BatchKind, name: &str) -> Option<&BatchRow> {
+        self.rows.iter().find(|r| r.kind == kind && r.name == name)
+    }
+
+    /// Writes the matrix as CSV: kind, name and one size column per variant, missing sizes are empty
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let variants = self
+            .variants
+            .iter()
+            .map(|v| escape_csv(v))
+            .collect::<Vec<_>>();
+        writeln!(writer, "kind,name,{}", variants.join(","))?;
+
+        for row in &self.rows {
+            let sizes = row
+                .sizes
+                .iter()
+                .map(|s| s.map(|s| s.to_string()).unwrap_or_default())
+                .collect::<Vec<_>>();
+            writeln!(
+                writer,
+                "{},{},{}",
+                row.kind.as_str(),
+                escape_csv(&row.name),
+                sizes.join(",")
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the matrix as a text table, missing sizes are written as `-`
+    pub fn write_table<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let names = self
+            .rows
+            .iter()
+            .map(|r| format!("{} {}", r.kind.as_str(), r.name))
+            .collect::<Vec<_>>();
+        let name_width = names.iter().map(|n| n.chars().count()).max().unwrap_or(0);
+
+        let cells = self
+            .rows
+            .iter()
+            .map(|r| {
+                r.sizes
+                    .iter()
+                    .map(|s| s.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let widths = self
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(index, variant)| {
+                cells
+                    .iter()
+                    .map(|row| row[index].len())
+                    .chain([variant.chars().count()])
+                    .max()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let line = |name: &str, values: &[String]| {
+            let mut line = format!("{name:<name_width$}");
+            for (value, width) in values.iter().zip(&widths) {
+                line.push_str(&format!("  {value:>width$}"));
+            }
+            line
+        };
+
+        writeln!(writer, "{}", line("", &self.variants).trim_end())?;
+        for (name, row) in names.iter().zip(&cells) {
+            writeln!(writer, "{}", line(name, row))?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns `name` without compression extension (if any) and mapfile extension (if any)
+fn strip_extensions(name: &str) -> &str {
+    let mut name = name;
+    for extensions in [&["gz", "xz", "zst", "zip"][..], &MAPFILE_EXTENSIONS[..]] {
+        if let Some((stem, extension)) = name.rsplit_once('.') {
+            if !stem.is_empty() && extensions.iter().any(|e| extension.eq_ignore_ascii_case(e)) {
+                name = stem;
+            }
+        }
+    }
+    name
+}
+
+/// Returns [true] if `path` file name has a mapfile extension, optionally followed by a compression extension
+fn is_mapfile(path: &Path) -> bool {
+    let name = match path.file_name().and_then(|n| n.to_str()) {
+        Some(name) => name,
+        None => return false,
+    };
+
+    let name = match name.rsplit_once('.') {
+        Some((stem, extension))
+            if [
+                Compression::Gzip,
+                Compression::Xz,
+                Compression::Zstd,
+                Compression::Zip,
+            ]
+            .iter()
+            .any(|c| c.get_extension() == Some(&extension.to_lowercase())) =>
+        {
+            stem
+        }
+        _ => name,
+    };
+    Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| MAPFILE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Returns the mapfiles selected by `patterns`, sorted by path and without duplicates
+///
+/// Each pattern is a file path, a glob pattern (ex: `build/*/zephyr/zephyr.map`) or a directory, searched
+/// recursively for `.map` and `.xml` files (optionally compressed)
+pub fn find_mapfiles<S: AsRef<str>>(patterns: &[S]) -> std::io::Result<Vec<PathBuf>> {
+    let mut paths = BTreeSet::new();
+
+    for pattern in patterns {
+        let pattern = pattern.as_ref();
+        let path = Path::new(pattern);
+
+        if path.is_dir() {
+            let pattern = format!("{}/**/*", glob::Pattern::escape(pattern));
+            for entry in glob::glob(&pattern).map_err(std::io::Error::other)? {
+                let entry = entry.map_err(std::io::Error::other)?;
+                if entry.is_file() && is_mapfile(&entry) {
+                    paths.insert(entry);
+                }
+            }
+        } else if path.is_file() {
+            paths.insert(path.to_path_buf());
+        } else {
+            let entries = glob::glob(pattern)
+                .map_err(|e| std::io::Error::other(format!("Invalid pattern '{pattern}': {e}")))?;
+            let count = paths.len();
+            for entry in entries {
+                let entry = entry.map_err(std::io::Error::other)?;
+                if entry.is_file() {
+                    paths.insert(entry);
+                }
+            }
+            if paths.len() == count {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("No mapfile found for '{pattern}'"),
+                ));
+            }
+        }
+    }
+
+    Ok(paths.into_iter().collect())
+}
+
+/// Returns short variant names of `paths`: path components shared by all paths, at start and at end, are removed,
+/// together with the mapfile and compression extensions (ex: `build/nrf52dk/zephyr/zephyr.map` and
+/// `build/nrf5340dk/zephyr/zephyr.map` give `nrf52dk` and `nrf5340dk`). A single path gives its file name
+///
+/// Paths that only differ by extensions (ex: `a.map` and `a.xml`, or `a.map` and `a.map.gz`) keep their extensions,
+/// so every variant has its own name
+pub fn get_variant_names<P: AsRef<Path>>(paths: &[P]) -> Vec<String> {
+    let mut names = get_short_names(paths, true);
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for name in &names {
+        *counts.entry(name).or_insert(0) += 1;
+    }
+    let duplicates = names
+        .iter()
+        .map(|n| counts[n.as_str()] > 1)
+        .collect::<Vec<_>>();
+
+    if duplicates.contains(&true) {
+        let full_names = get_short_names(paths, false);
+        for ((name, full_name), duplicate) in names.iter_mut().zip(full_names).zip(duplicates) {
+            if duplicate {
+                *name = full_name;
+            }
+        }
+    }
+    names
+}
+
+/// Returns `paths` without the components shared by all paths, at start and at end, and without the extensions of
+/// the last component if `strip` is [true]
+fn get_short_names<P: AsRef<Path>>(paths: &[P], strip: bool) -> Vec<String> {
+    let components = paths
+        .iter()
+        .map(|p| {
+            let path = p.as_ref().to_string_lossy().replace('\\', "/");
+            let mut components = path
+                .split('/')
+                .filter(|c| !c.is_empty() && *c != ".")
+                .map(String::from)
+                .collect::<Vec<_>>();
+            if let Some(last) = components.last_mut().filter(|_| strip) {
+                *last = strip_extensions(last).to_string();
+            }
+            components
+        })
+        .collect::<Vec<_>>();
+
+    let shortest = components.iter().map(|c| c.len()).min().unwrap_or(0);
+    let common = |get: &dyn Fn(&Vec<String>, usize) -> &String| {
+        (0..shortest)
+            .take_while(|i| {
+                components
+                    .iter()
+                    .all(|c| get(c, *i) == get(&components[0], *i))
+            })
+            .count()
+    };
+    let mut prefix = common(&|c, i| &c[i]);
+    let mut suffix = common(&|c, i| &c[c.len() - 1 - i]);
+
+    // Keep at least one component of each path
+    if paths.len() < 2 {
+        prefix = shortest.saturating_sub(1);
+        suffix = 0;
+    }
+    while prefix + suffix >= shortest && shortest > 0 {
+        match suffix > 0 {
+            true => suffix -= 1,
+            false => prefix -= 1,
+        }
+    }
+
+    components
+        .iter()
+        .map(|c| c[prefix..c.len() - suffix].join("/"))
+        .collect()
+}
@@ -0,0 +1,552 @@
+//! Browser module
+//!
+//! This module contains the navigable tree model used by the terminal UI: [Regions](Region) containing
+//! [Segments](Segment), containing objects, containing [Entries](Entry), each level sorted by size (largest first),
+//! with incremental search and size differences from a [Diff]
+
+use crate::{
+    diff::{Diff, DiffKind},
+    entry::Entry,
+    region::Region,
+    segment::Segment,
+    Parser,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// Name of the root node containing segments placed outside all regions
+const NO_REGION_NAME: &str = "(no region)";
+/// Name of the object node containing entries without object
+const NO_OBJECT_NAME: &str = "(no object)";
+/// Diff item key: kind, name, segment name, library name and object name
+type DiffKey<'a> = (
+    DiffKind,
+    &'a str,
+    Option<&'a str>,
+    Option<&'a str>,
+    Option<&'a str>,
+);
+/// Object node key: segment name, library name and object name
+type ObjectKey<'a> = (&'a str, Option<&'a str>, Option<&'a str>);
+
+/// Number of rows moved by page up/down
+pub const PAGE_SIZE: usize = 20;
+
+/// Enum containing the tree node types
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum NodeKind {
+    /// [Region] or segments placed outside all regions
+    Region,
+    /// [Segment] placed or loaded in the parent region
+    Segment,
+    /// Object (with library) owning entries of the parent segment
+    Object,
+    /// [Entry] of the parent object
+    Entry,
+}
+
+impl NodeKind {
+    /// Returns kind name as used in outputs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeKind::Region => "region",
+            NodeKind::Segment => "segment",
+            NodeKind::Object => "object",
+            NodeKind::Entry => "entry",
+        }
+    }
+}
+
+/// Structure containing a tree node
+#[derive(Debug, Clone)]
+pub struct BrowserNode<'a> {
+    /// Node type
+    kind: NodeKind,
+    /// Displayed name
+    name: String,
+    /// Region used size, segment size or sum of entry sizes (including `*fill*` padding)
+    size: u64,
+    /// Size difference from the old mapfile or [None](Option::None) if no diff is set
+    delta: Option<i64>,
+    /// Level in the tree, 0 for regions
+    depth: usize,
+    /// Index of the parent node or [None](Option::None) for regions
+    parent: Option<usize>,
+    /// Indexes of the child nodes, sorted by size (largest first), then by name
+    children: Vec<usize>,
+    /// [Region] of region nodes
+    region: Option<&'a Region>,
+    /// [Segment] of segment nodes
+    segment: Option<&'a Segment>,
+    /// [Entry] of entry nodes
+    entry: Option<&'a Entry>,
+}
+
+impl<'a> BrowserNode<'a> {
+    fn new(kind: NodeKind, name: &str, size: u64, depth: usize, parent: Option<usize>) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+            size,
+            delta: None,
+            depth,
+            parent,
+            children: vec![],
+            region: None,
+            segment: None,
+            entry: None,
+        }
+    }
+
+    /// Get node [kind](#structfield.kind)
+    pub fn get_kind(&self) -> NodeKind {
+        self.kind
+    }
+
+    /// Get node [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get node [size](#structfield.size)
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    /// Get size [delta](#structfield.delta) or [None](Option::None)
+    pub fn get_delta(&self) -> Option<i64> {
+        self.delta
+    }
+
+    /// Get node [depth](#structfield.depth)
+    pub fn get_depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Get [parent](#structfield.parent) index or [None](Option::None)
+    pub fn get_parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    /// Get [children](#structfield.children) indexes
+    pub fn get_children(&self) -> &[usize] {
+        &self.children
+    }
+
+    /// Get [region](#structfield.region) or [None](Option::None)
+    pub fn get_region(&self) -> Option<&'a Region> {
+        self.region
+    }
+
+    /// Get [segment](#structfield.segment) or [None](Option::None)
+    pub fn get_segment(&self) -> Option<&'a Segment> {
+        self.segment
+    }
+
+    /// Get [entry](#structfield.entry) or [None](Option::None)
+    pub fn get_entry(&self) -> Option<&'a Entry> {
+        self.entry
+    }
+}
+
+/// Structure containing the browsed tree, its expanded nodes, filters and selected row
+#[derive(Debug, Clone, Default)]
+pub struct Browser<'a> {
+    /// All nodes, parents before children
+    nodes: Vec<BrowserNode<'a>>,
+    /// Indexes of region nodes, sorted by size (largest first), then by name
+    roots: Vec<usize>,
+    /// Expanded state of each node
+    expanded: Vec<bool>,
+    /// Case insensitive search text, nodes not containing it (and without such descendants) are hidden
+    search: String,
+    /// If [true], nodes without size difference (and without such descendants) are hidden
+    diff_only: bool,
+    /// Indexes of the displayed nodes, in display order
+    rows: Vec<usize>,
+    /// Selected row
+    selected: usize,
+}
+
+impl<'a> Browser<'a> {
+    /// Creates a new [Browser] of `parser` results, with all nodes collapsed
+    ///
+    /// Segments are placed under every region they are placed (VMA) or loaded (LMA) in, segments other than
+    /// [Parser::get_allocated_segments] are skipped
+    pub fn new(parser: &'a Parser) -> Self {
+        let mut browser = Self::default();
+
+        let segments = parser
+            .get_allocated_segments()
+            .filter(|s| s.get_size().is_some())
+            .collect::<Vec<_>>();
+
+        for region in parser.get_memory_regions() {
+            let index = browser.add_node(BrowserNode::new(
+                NodeKind::Region,
+                region.get_name(),
+                region.get_used_size(),
+                0,
+                None,
+            ));
+            browser.nodes[index].region = Some(region);
+            for segment in segments.iter().filter(|s| region.contains_segment(s)) {
+                browser.add_segment(index, segment);
+            }
+        }
+
+        let outside = segments
+            .iter()
+            .filter(|s| {
+                !parser
+                    .get_memory_regions()
+                    .iter()
+                    .any(|r| r.contains_segment(s))
+            })
+            .collect::<Vec<_>>();
+        if !outside.is_empty() {
+            let size = outside.iter().map(|s| s.get_size().unwrap()).sum();
+            let index = browser.add_node(BrowserNode::new(
+                NodeKind::Region,
+                NO_REGION_NAME,
+                size,
+                0,
+                None,
+            ));
+            for segment in outside {
+                browser.add_segment(index, segment);
+            }
+        }
+
+        browser.roots = (0..browser.nodes.len())
+            .filter(|i| browser.nodes[*i].parent.is_none())
+            .collect();
+        browser.sort_by_size();
+        browser.expanded = vec![false; browser.nodes.len()];
+        browser.refresh();
+
+        browser
+    }
+
+    fn add_node(&mut self, node: BrowserNode<'a>) -> usize {
+        let index = self.nodes.len();
+        if let Some(parent) = node.parent {
+            self.nodes[parent].children.push(index);
+        }
+        self.nodes.push(node);
+        index
+    }
+
+    /// Adds `segment` node under `parent` region node, with its objects and entries
+    fn add_segment(&mut self, parent: usize, segment: &'a Segment) {
+        let index = self.add_node(BrowserNode::new(
+            NodeKind::Segment,
+            segment.get_name(),
+            segment.get_size().unwrap(),
+            1,
+            Some(parent),
+        ));
+        self.nodes[index].segment = Some(segment);
+
+        // Objects are grouped by library and object name (map key order), children are sorted by size afterwards
+        let mut objects: BTreeMap<(Option<&str>, Option<&str>), Vec<&'a Entry>> = BTreeMap::new();
+        for entry in segment.get_entries() {
+            objects
+                .entry((entry.get_library_name(), entry.get_object_name()))
+                .or_default()
+                .push(entry);
+        }
+
+        for ((library_name, object_name), entries) in objects {
+            let name = match (library_name, object_name) {
+                (Some(library_name), Some(object_name)) => format!("{library_name}({object_name})"),
+                (None, Some(object_name)) => object_name.to_string(),
+                _ => NO_OBJECT_NAME.to_string(),
+            };
+            let size = entries.iter().map(|e| e.get_size()).sum();
+            let object = self.add_node(BrowserNode::new(
+                NodeKind::Object,
+                &name,
+                size,
+                2,
+                Some(index),
+            ));
+
+            for entry in entries {
+                let node = self.add_node(BrowserNode::new(
+                    NodeKind::Entry,
+                    entry.get_name(),
+                    entry.get_size(),
+                    3,
+                    Some(object),
+                ));
+                self.nodes[node].entry = Some(entry);
+            }
+        }
+    }
+
+    fn sort_by_size(&mut self) {
+        let keys = self
+            .nodes
+            .iter()
+            .map(|n| (std::cmp::Reverse(n.size), n.name.clone()))
+            .collect::<Vec<_>>();
+        // Stable sort keeps parsing order for equal sizes and names
+        self.roots.sort_by(|a, b| keys[*a].cmp(&keys[*b]));
+        for node in &mut self.nodes {
+            node.children.sort_by(|a, b| keys[*a].cmp(&keys[*b]));
+        }
+    }
+
+    /// Sets size differences from `diff` between an old mapfile and the browsed one
+    ///
+    /// Object and segment differences include the entries removed from them, region differences are the sum of
+    /// their segment differences
+    pub fn set_diff(&mut self, diff: &Diff) {
+        let mut deltas: HashMap<DiffKey, i64> = HashMap::new();
+        let mut object_deltas: HashMap<ObjectKey, i64> = HashMap::new();
+        for item in diff.get_items() {
+            deltas.insert(
+                (
+                    item.get_kind(),
+                    item.get_name(),
+                    item.get_segment_name(),
+                    item.get_library_name(),
+                    item.get_object_name(),
+                ),
+                item.get_delta(),
+            );
+            if let (DiffKind::Entry, Some(segment_name)) =
+                (item.get_kind(), item.get_segment_name())
+            {
+                *object_deltas
+                    .entry((
+                        segment_name,
+                        item.get_library_name(),
+                        item.get_object_name(),
+                    ))
+                    .or_insert(0) += item.get_delta();
+            }
+        }
+
+        // Children are added after their parent, so they are updated first in reverse order
+        for index in (0..self.nodes.len()).rev() {
+            let node = &self.nodes[index];
+            let delta = match node.kind {
+                NodeKind::Region => Some(
+                    node.children
+                        .iter()
+                        .filter_map(|c| self.nodes[*c].delta)
+                        .sum(),
+                ),
+                NodeKind::Segment => Some(
+                    deltas
+                        .get(&(DiffKind::Segment, node.name.as_str(), None, None, None))
+                        .copied()
+                        .unwrap_or(0),
+                ),
+                NodeKind::Object => {
+                    let segment = &self.nodes[node.parent.unwrap()];
+                    let entry = self.nodes[node.children[0]].entry.unwrap();
+                    Some(
+                        object_deltas
+                            .get(&(
+                                segment.name.as_str(),
+                                entry.get_library_name(),
+                                entry.get_object_name(),
+                            ))
+                            .copied()
+                            .unwrap_or(0),
+                    )
+                }
+                NodeKind::Entry => {
+                    let segment = &self.nodes[self.nodes[node.parent.unwrap()].parent.unwrap()];
+                    let entry = node.entry.unwrap();
+                    deltas
+                        .get(&(
+                            DiffKind::Entry,
+                            entry.get_name(),
+                            Some(segment.name.as_str()),
+                            entry.get_library_name(),
+                            entry.get_object_name(),
+                        ))
+                        .copied()
+                }
+            };
+            self.nodes[index].delta = delta;
+        }
+
+        self.refresh();
+    }
+
+    /// Get all nodes
+    pub fn get_nodes(&self) -> &[BrowserNode<'a>] {
+        &self.nodes
+    }
+
+    /// Get node at `index`
+    pub fn get_node(&self, index: usize) -> &BrowserNode<'a> {
+        &self.nodes[index]
+    }
+
+    /// Get region node indexes
+    pub fn get_roots(&self) -> &[usize] {
+        &self.roots
+    }
+
+    /// Returns [true] if node at `index` is expanded
+    pub fn is_expanded(&self, index: usize) -> bool {
+        self.expanded[index]
+    }
+
+    /// Get displayed node indexes, in display order
+    pub fn get_rows(&self) -> &[usize] {
+        &self.rows
+    }
+
+    /// Get selected row
+    pub fn get_selected_row(&self) -> usize {
+        self.selected
+    }
+
+    /// Get selected node or [None](Option::None) if no node is displayed
+    pub fn get_selected(&self) -> Option<&BrowserNode<'a>> {
+        self.rows.get(self.selected).map(|i| &self.nodes[*i])
+    }
+
+    /// Get search text
+    pub fn get_search(&self) -> &str {
+        &self.search
+    }
+
+    /// Set search text. Matching nodes are displayed with their ancestors expanded, other nodes are hidden
+    pub fn set_search(&mut self, search: &str) {
+        self.search = search.to_lowercase();
+        self.refresh();
+    }
+
+    /// Get diff only state
+    pub fn get_diff_only(&self) -> bool {
+        self.diff_only
+    }
+
+    /// Set diff only. If [true], only nodes with a size difference are displayed
+    pub fn set_diff_only(&mut self, value: bool) {
+        self.diff_only = value;
+        self.refresh();
+    }
+
+    /// Moves selection by `offset` rows, keeping it inside displayed rows
+    pub fn move_selection(&mut self, offset: isize) {
+        let last = self.rows.len().saturating_sub(1) as isize;
+        self.selected = (self.selected as isize + offset).clamp(0, last) as usize;
+    }
+
+    /// Selects first row
+    pub fn select_first(&mut self) {
+        self.selected = 0;
+    }
+
+    /// Selects last row
+    pub fn select_last(&mut self) {
+        self.selected = self.rows.len().saturating_sub(1);
+    }
+
+    /// Expands selected node, or collapses it if already expanded
+    pub fn toggle_selected(&mut self) {
+        if let Some(index) = self.rows.get(self.selected).copied() {
+            self.expanded[index] = !self.expanded[index];
+            self.refresh();
+        }
+    }
+
+    /// Expands selected node
+    pub fn expand_selected(&mut self) {
+        if let Some(index) = self.rows.get(self.selected).copied() {
+            self.expanded[index] = true;
+            self.refresh();
+        }
+    }
+
+    /// Collapses selected node, or selects its parent if not expanded
+    pub fn collapse_selected(&mut self) {
+        let index = match self.rows.get(self.selected) {
+            Some(index) => *index,
+            None => return,
+        };
+
+        if self.expanded[index] && !self.nodes[index].children.is_empty() {
+            self.expanded[index] = false;
+            self.refresh();
+        } else if let Some(parent) = self.nodes[index].parent {
+            if let Some(row) = self.rows.iter().position(|i| *i == parent) {
+                self.selected = row;
+            }
+        }
+    }
+
+    /// Returns [true] if node at `index` name contains the search text
+    fn is_search_match(&self, index: usize) -> bool {
+        self.nodes[index].name.to_lowercase().contains(&self.search)
+    }
+
+    /// Returns [true] if node at `index` has a size difference, or diff only is not set
+    fn is_diff_match(&self, index: usize) -> bool {
+        !self.diff_only || self.nodes[index].delta.is_some_and(|d| d != 0)
+    }
+
+    /// Rebuilds displayed rows after a change, keeping the selected node if still displayed
+    ///
+    /// When filtered, nodes are displayed if they match, if one of their descendants matches (these nodes are
+    /// expanded) or if one of their ancestors matches the search
+    fn refresh(&mut self) {
+        let selected = self.rows.get(self.selected).copied();
+        let filtered = !self.search.is_empty() || self.diff_only;
+
+        let mut visible = vec![true; self.nodes.len()];
+        // Any descendant matches
+        let mut matching_below = vec![false; self.nodes.len()];
+        if filtered {
+            // Parents come before children
+            let mut found_above = vec![false; self.nodes.len()];
+            for index in 0..self.nodes.len() {
+                if let Some(parent) = self.nodes[index].parent {
+                    found_above[index] = found_above[parent]
+                        || (!self.search.is_empty() && self.is_search_match(parent));
+                }
+            }
+
+            let mut is_match = vec![false; self.nodes.len()];
+            for index in (0..self.nodes.len()).rev() {
+                is_match[index] = self.is_search_match(index) && self.is_diff_match(index);
+                matching_below[index] = self.nodes[index]
+                    .children
+                    .iter()
+                    .any(|c| is_match[*c] || matching_below[*c]);
+                visible[index] = is_match[index]
+                    || matching_below[index]
+                    || (found_above[index] && self.is_diff_match(index));
+            }
+        }
+
+        let mut rows = vec![];
+        let mut stack = self.roots.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(index) = stack.pop() {
+            if !visible[index] {
+                continue;
+            }
+            rows.push(index);
+
+            let node = &self.nodes[index];
+            // Filtered trees are expanded down to the matching nodes
+            let expanded = self.expanded[index] || matching_below[index];
+            if expanded {
+                stack.extend(node.children.iter().rev());
+            }
+        }
+
+        self.rows = rows;
+        self.selected = selected
+            .and_then(|selected| self.rows.iter().position(|i| *i == selected))
+            .unwrap_or(0);
+    }
+}
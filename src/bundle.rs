@@ -0,0 +1,371 @@
+//! Bundle module
+//!
+//! This module contains the code to hold the results of several images linked for the same product (ex: bootloader,
+//! application core and network core images) as one firmware bundle, to combine the flash usage of images sharing a
+//! physical flash and to find flash ranges used by more than one image
+
+use crate::{region::Region, Parser};
+use std::fmt::Display;
+
+/// Name fragments of regions considered as flash when the region has no attributes
+const FLASH_NAMES: [&str; 2] = ["FLASH", "ROM"];
+
+/// Returns [true] if `region` is non-volatile memory: its attributes do not allow writes (ex: `rx`) or, without
+/// attributes (or only inverted ones), its name contains `FLASH` or `ROM`
+pub fn is_flash(region: &Region) -> bool {
+    // Attributes after `!` are the inverted ones (ex: `rx!w`)
+    let allowed = region
+        .get_attributes()
+        .and_then(|a| a.split('!').next())
+        .unwrap_or_default();
+
+    match allowed.is_empty() {
+        true => {
+            let name = region.get_name().to_uppercase();
+            FLASH_NAMES.iter().any(|n| name.contains(n))
+        }
+        false => !allowed.contains(['w', 'W']),
+    }
+}
+
+/// Enum containing the errors that can occur while building a bundle
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum BundleError {
+    /// An image with the same name is already in the bundle
+    DuplicateImage(String),
+}
+
+impl Display for BundleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BundleError::DuplicateImage(name) => write!(f, "Duplicate bundle image '{name}'"),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+/// Structure containing a named image of the bundle
+pub struct BundleImage {
+    /// Image name (ex: `mcuboot`, `app`, `net`)
+    name: String,
+    /// Image mapfile parser results
+    parser: Parser,
+}
+
+impl BundleImage {
+    /// Get image [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get image [parser](#structfield.parser) results
+    pub fn get_parser(&self) -> &Parser {
+        &self.parser
+    }
+
+    /// Get image memory regions, with their used size
+    pub fn get_regions(&self) -> &[Region] {
+        self.parser.get_memory_regions()
+    }
+}
+
+/// Structure containing a physical flash, made of the overlapping or adjacent flash regions of all images
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SharedFlash {
+    /// Names of the regions, without duplicates, joined with `/` (ex: `FLASH`)
+    name: String,
+    /// Lowest region origin
+    origin: u64,
+    /// Length from [origin](#structfield.origin) to the highest region end
+    length: u64,
+    /// Pairs of image name and region name, sorted by region origin
+    regions: Vec<(String, String)>,
+    /// Pairs of image name and size used by the image, in bundle image order. Images without region in the flash
+    /// are not listed
+    used_sizes: Vec<(String, u64)>,
+}
+
+impl SharedFlash {
+    /// Get flash [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get flash [origin](#structfield.origin)
+    pub fn get_origin(&self) -> u64 {
+        self.origin
+    }
+
+    /// Get flash [length](#structfield.length)
+    pub fn get_length(&self) -> u64 {
+        self.length
+    }
+
+    /// Get flash [regions](#structfield.regions) as pairs of image name and region name
+    pub fn get_regions(&self) -> &[(String, String)] {
+        &self.regions
+    }
+
+    /// Get [used_sizes](#structfield.used_sizes) as pairs of image name and size
+    pub fn get_used_sizes(&self) -> &[(String, u64)] {
+        &self.used_sizes
+    }
+
+    /// Returns the size used by `image` or [None](Option::None) if the image has no region in the flash
+    pub fn get_image_used_size(&self, image: &str) -> Option<u64> {
+        self.used_sizes
+            .iter()
+            .find(|(name, _)| name == image)
+            .map(|(_, size)| *size)
+    }
+
+    /// Returns the size used by all images
+    pub fn get_used_size(&self) -> u64 {
+        self.used_sizes.iter().map(|(_, size)| size).sum()
+    }
+
+    /// Returns [true] if `address` is inside the flash
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.origin && (address - self.origin) < self.length
+    }
+}
+
+/// Structure containing a flash range used by segments of two different images
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Overlap {
+    /// Flash name
+    flash: String,
+    /// Image and segment names placed first (lowest start address)
+    first: (String, String),
+    /// Image and segment names placed second
+    second: (String, String),
+    /// Start address of the overlapping range
+    address: u64,
+    /// Size of the overlapping range
+    size: u64,
+}
+
+impl Overlap {
+    /// Get [flash](#structfield.flash) name
+    pub fn get_flash(&self) -> &str {
+        &self.flash
+    }
+
+    /// Get [first](#structfield.first) image and segment names
+    pub fn get_first(&self) -> (&str, &str) {
+        (&self.first.0, &self.first.1)
+    }
+
+    /// Get [second](#structfield.second) image and segment names
+    pub fn get_second(&self) -> (&str, &str) {
+        (&self.second.0, &self.second.1)
+    }
+
+    /// Get overlap [address](#structfield.address)
+    pub fn get_address(&self) -> u64 {
+        self.address
+    }
+
+    /// Get overlap [size](#structfield.size)
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl Display for Overlap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "image overlap in {} at {:#010x} ({} bytes): {} {} and {} {}",
+            self.flash,
+            self.address,
+            self.size,
+            self.first.0,
+            self.first.1,
+            self.second.0,
+            self.second.1
+        )
+    }
+}
+
+/// Structure containing the images of a firmware bundle
+#[derive(Default)]
+pub struct Bundle {
+    /// Images, in insertion order
+    images: Vec<BundleImage>,
+}
+
+impl Bundle {
+    /// Creates a new empty [Bundle]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `parser` results as image `name`
+    pub fn add_image(&mut self, name: &str, parser: Parser) -> Result<(), BundleError> {
+        if self.get_image(name).is_some() {
+            return Err(BundleError::DuplicateImage(name.to_string()));
+        }
+
+        self.images.push(BundleImage {
+            name: name.to_string(),
+            parser,
+        });
+        Ok(())
+    }
+
+    /// Get all [images](#structfield.images)
+    pub fn get_images(&self) -> &[BundleImage] {
+        &self.images
+    }
+
+    /// Get image named `name` or [None](Option::None)
+    pub fn get_image(&self, name: &str) -> Option<&BundleImage> {
+        self.images.iter().find(|i| i.name == name)
+    }
+
+    /// Returns the physical flashes of the bundle, sorted by origin
+    ///
+    /// Flash regions (see [is_flash]) of all images that overlap or are adjacent (ex: bootloader and application
+    /// partitions) are merged into one flash. The size used by an image is the sum of its segments placed (VMA) or
+    /// loaded (LMA) inside the flash
+    pub fn get_shared_flashes(&self) -> Vec<SharedFlash> {
+        // Tuples of region origin, end (exclusive), image name and region name
+        let mut regions = self
+            .images
+            .iter()
+            .flat_map(|image| {
+                image
+                    .get_regions()
+                    .iter()
+                    .filter(|r| is_flash(r) && r.get_length() > 0)
+                    .map(move |r| {
+                        (
+                            r.get_origin(),
+                            r.get_origin().saturating_add(r.get_length()),
+                            image.get_name(),
+                            r.get_name(),
+                        )
+                    })
+            })
+            .collect::<Vec<_>>();
+        regions.sort_by_key(|(origin, end, _, _)| (*origin, *end));
+
+        let mut flashes: Vec<SharedFlash> = vec![];
+        for (origin, end, image, region) in regions {
+            let flash = match flashes.last_mut() {
+                Some(flash) if origin <= flash.origin.saturating_add(flash.length) => flash,
+                _ => {
+                    flashes.push(SharedFlash {
+                        name: String::new(),
+                        origin,
+                        length: 0,
+                        regions: vec![],
+                        used_sizes: vec![],
+                    });
+                    flashes.last_mut().unwrap()
+                }
+            };
+
+            flash.length = flash.length.max(end - flash.origin);
+            flash.regions.push((image.to_string(), region.to_string()));
+        }
+
+        for flash in &mut flashes {
+            let mut names: Vec<&str> = vec![];
+            for (_, region) in &flash.regions {
+                if !names.contains(&region.as_str()) {
+                    names.push(region);
+                }
+            }
+            flash.name = names.join("/");
+
+            for image in &self.images {
+                if flash.regions.iter().any(|(name, _)| *name == image.name) {
+                    let used = image
+                        .get_placements()
+                        .iter()
+                        .filter(|(_, start, _)| flash.contains(*start))
+                        .map(|(_, start, end)| end - start)
+                        .sum();
+                    flash.used_sizes.push((image.name.clone(), used));
+                }
+            }
+        }
+
+        flashes
+    }
+
+    /// Returns the flash ranges used by segments of two different images, sorted by address
+    ///
+    /// Only flashes are checked, as volatile memory is usually reused by images that do not run at the same time (ex:
+    /// bootloader and application). Overlaps inside one image are found by [Validation](crate::validation::Validation)
+    pub fn find_overlaps(&self) -> Vec<Overlap> {
+        let flashes = self.get_shared_flashes();
+
+        // Tuples of start address, end address (exclusive), image name and segment name
+        let mut placements = vec![];
+        for image in &self.images {
+            for (segment, start, end) in image.get_placements() {
+                if flashes.iter().any(|f| f.contains(start)) {
+                    placements.push((start, end, image.get_name(), segment));
+                }
+            }
+        }
+        placements.sort_by_key(|(start, end, _, _)| (*start, *end));
+
+        let mut overlaps = vec![];
+        for (index, (_, end, image, segment)) in placements.iter().enumerate() {
+            for (other_start, other_end, other_image, other_segment) in &placements[index + 1..] {
+                if other_start >= end {
+                    break;
+                }
+                if image == other_image {
+                    continue;
+                }
+
+                overlaps.push(Overlap {
+                    // Placements are only kept if inside a flash
+                    flash: flashes
+                        .iter()
+                        .find(|f| f.contains(*other_start))
+                        .unwrap()
+                        .name
+                        .clone(),
+                    first: (image.to_string(), segment.to_string()),
+                    second: (other_image.to_string(), other_segment.to_string()),
+                    address: *other_start,
+                    size: (*end).min(*other_end) - other_start,
+                });
+            }
+        }
+        overlaps.sort_by_key(|o| o.address);
+        overlaps
+    }
+}
+
+/// Helper functions for [Bundle] implementation
+impl BundleImage {
+    /// Returns tuples of segment name, start address and end address (exclusive) of the allocated segments, once at
+    /// their address (VMA) and once at their load address (LMA) if different
+    fn get_placements(&self) -> Vec<(&str, u64, u64)> {
+        let mut placements = vec![];
+        for segment in self.parser.get_allocated_segments() {
+            let (address, size) = match (segment.get_address(), segment.get_size()) {
+                (Some(address), Some(size)) if size > 0 => (address, size),
+                _ => continue,
+            };
+
+            placements.push((segment.get_name(), address, address.saturating_add(size)));
+            if let Some(load_address) = segment.get_load_address().filter(|&l| l != address) {
+                placements.push((
+                    segment.get_name(),
+                    load_address,
+                    load_address.saturating_add(size),
+                ));
+            }
+        }
+        placements
+    }
+}
@@ -1,17 +1,40 @@
-use ::parser::xmlwriter::{ToXmlWriter, XmlWriter};
-use ::parser::Parser as MapParser;
+use ::parser::batch::{self, BatchReport};
+use ::parser::bundle::Bundle;
+use ::parser::component::{ComponentRules, Components};
+use ::parser::diff::{Diff, DiffKind};
+use ::parser::elf::{ElfCheck, ElfImage};
+use ::parser::grouping::Grouping;
+use ::parser::input::{Input, STDIN_PATH};
+use ::parser::lookup::AddressIndex;
+use ::parser::padding::Padding;
+use ::parser::query::Query;
+use ::parser::rollup::DirectoryRollup;
+use ::parser::source::{DebugInfo, SourceSizes};
+#[cfg(feature = "trend")]
+use ::parser::trend::{self, BuildInfo, TrendDatabase, TrendKind};
+use ::parser::validation::Validation;
+use ::parser::xmlreader::XmlReader;
+use ::parser::xmlwriter::{self, ToXmlWriter, XmlWriter};
+use ::parser::{Parser as MapParser, SortKey};
 use clap::Parser as CliParser;
 use log::LevelFilter;
-use parser::excelwriter::{ExcelWriter, ToExcelWriter};
+use parser::excelwriter::{self, ExcelWriter, ToExcelWriter};
 use std::path::Path;
 use std::{fs::File, io::Write};
 
+#[cfg(feature = "tui")]
+mod tui;
+
+/// Number of largest padding gaps printed in summary
+const PADDING_GAPS_COUNT: usize = 10;
+
 #[derive(CliParser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    /// Path to input Map file
-    #[arg(short, long, value_name = "PATH")]
-    mapfile: String,
+    /// Path to input Map file, or XML file previously written by this tool (".xml" extension). Use "-" for stdin. Files compressed with gzip, xz, zstd or zip are read directly
+    #[cfg_attr(feature = "trend", arg(short, long, value_name = "PATH", required_unless_present_any = ["trend", "batch", "bundle"]))]
+    #[cfg_attr(not(feature = "trend"), arg(short, long, value_name = "PATH", required_unless_present_any = ["batch", "bundle"]))]
+    mapfile: Option<String>,
 
     /// Path to output XLSX file. If not specified, outputs to "mapfile.xlsx"
     #[arg(
@@ -23,6 +46,10 @@ struct Cli {
     )]
     xlsfile: Option<String>,
 
+    /// Highlight in XLSX output regions using more than PERCENT of their length
+    #[arg(long, value_name = "PERCENT", requires = "xlsfile", default_value_t = 100, value_parser = clap::value_parser!(u32).range(1..))]
+    budget: u32,
+
     /// Path to output XML file. If not specified, outputs to "mapfile.xml"
     #[arg(
         long,
@@ -33,11 +60,210 @@ struct Cli {
     )]
     xmlfile: Option<String>,
 
+    /// Include in XML output the mapfile lines from where entries were parsed (<data> elements)
+    #[arg(long, requires = "xmlfile")]
+    xml_data: bool,
+
+    /// Format of XML output datetime attribute (chrono strftime syntax, ex: "%Y-%m-%dT%H:%M:%SZ")
+    #[arg(long, value_name = "FORMAT", requires = "xmlfile", default_value = xmlwriter::DEFAULT_DATETIME_FORMAT, value_parser = parse_datetime_format)]
+    xml_datetime_format: String,
+
+    /// Path to the linked ELF image, checked against the mapfile (section sizes/addresses, missing sections,
+    /// symbol addresses). Symbol sizes are read from its symbol table
+    #[arg(long, value_name = "PATH", requires = "mapfile")]
+    elf: Option<String>,
+
+    /// Print entry sizes per source file and source directory, from the compilation units of the ELF debug
+    /// information (DWARF)
+    #[arg(long, requires = "elf")]
+    sources: bool,
+
+    /// Print the sizes of the ELF symbols placed inside each entry (ex: functions of an object built without
+    /// -ffunction-sections)
+    #[arg(long, requires = "elf")]
+    entry_symbols: bool,
+
+    /// Print source paths relative to this directory (ex: monorepo root)
+    #[arg(long, value_name = "PATH", requires = "sources")]
+    source_root: Option<String>,
+
+    /// Print address of linker symbol (ex: __heap_start, _estack), and its size with --elf. Can be used multiple times
+    #[arg(long, value_name = "NAME")]
+    symbol: Vec<String>,
+
+    /// Print distance in bytes between two linker symbols (ex: __heap_start:__heap_end for heap size). Can be used multiple times
+    #[arg(long, value_name = "START:END", value_parser = parse_symbol_range)]
+    symbol_range: Vec<(String, String)>,
+
+    /// Print segment, entry, object, library and symbol that own the address (ex: 0x08000104). Can be used multiple times
+    #[arg(long, value_name = "ADDRESS", value_parser = parse_address)]
+    lookup: Vec<u64>,
+
+    /// Check for overlapping entries/segments and gaps not explained by fill
+    #[arg(long)]
+    validate: bool,
+
+    /// Write *fill* padding sizes and alignment gaps into XML and XLSX output (always printed in console summary)
+    #[arg(long)]
+    padding: bool,
+
+    /// Print sizes grouped by function/variable (from -ffunction-sections/-fdata-sections entry names) and by category,
+    /// and write them into XML and XLSX output
+    #[arg(long)]
+    group: bool,
+
+    /// Path to component rules file ("[TARGET:]PATTERN -> COMPONENT" lines, ex: "drivers/wifi/** -> connectivity").
+    /// Sizes per component are printed and written into XML and XLSX output
+    #[arg(long, value_name = "PATH")]
+    components: Option<String>,
+
+    /// Print object sizes summed at every directory level of object and library paths, like du
+    #[arg(long)]
+    rollup: bool,
+
+    /// Limit rollup output to DEPTH directory levels (0 prints only the total)
+    #[arg(long, value_name = "DEPTH", requires = "rollup")]
+    rollup_depth: Option<usize>,
+
+    /// Add demangled C++/Rust names next to mangled entry and symbol names
+    #[arg(long)]
+    demangle: bool,
+
+    /// Order of objects in XML and XLSX output
+    #[arg(long, value_name = "KEY", default_value = "name", value_parser = ["name", "size", "address"])]
+    sort: String,
+
+    /// Compare sizes with an older Map/XML file (ex: of the previous release)
+    #[arg(long, value_name = "PATH", requires = "mapfile")]
+    diff: Option<String>,
+
+    /// Path to output XLSX diff file. If not specified, outputs to "diff.xlsx"
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "diff",
+        default_missing_value = "diff.xlsx",
+        require_equals = true,
+        num_args = 0..=1
+    )]
+    diff_xlsfile: Option<String>,
+
+    /// Print segments, entries, objects or libraries selected by a query (ex: "entries where size > 1024 order by size desc limit 20")
+    #[arg(long, value_name = "QUERY", requires = "mapfile", value_parser = parse_query)]
+    query: Option<Query>,
+
+    /// Query output format
+    #[arg(long, value_name = "FORMAT", requires = "query", default_value = "table", value_parser = ["table", "json"])]
+    query_format: String,
+
+    /// Browse regions, segments, objects and entries in a terminal UI, with size differences if --diff is set
+    #[cfg(feature = "tui")]
+    #[arg(long, requires = "mapfile", conflicts_with_all = ["xmlfile", "xlsfile"])]
+    tui: bool,
+
+    /// Build a combined size matrix of several Map/XML files (paths, glob patterns or directories), one column per variant
+    #[arg(long, value_name = "PATH", num_args = 1.., conflicts_with = "mapfile")]
+    batch: Vec<String>,
+
+    /// Path to output batch CSV file. If not specified, the matrix is printed to console
+    #[arg(long, value_name = "PATH", requires = "batch")]
+    batch_csv: Option<String>,
+
+    /// Report region usage, combined flash usage and overlapping placements of several images of one firmware (ex: bootloader, app core and net core). Images are named after the differing parts of their paths, or explicitly with NAME=PATH
+    #[arg(long, value_name = "[NAME=]PATH", num_args = 1.., conflicts_with_all = ["mapfile", "batch"])]
+    bundle: Vec<String>,
+
+    /// Path to SQLite size history database, used by --record and --trend. Created if missing
+    #[cfg(feature = "trend")]
+    #[arg(long, value_name = "PATH")]
+    database: Option<String>,
+
+    /// Record segment, region, object and library sizes into database, under the given build id
+    #[cfg(feature = "trend")]
+    #[arg(long, value_name = "BUILD_ID", requires_all = ["database", "mapfile"])]
+    record: Option<String>,
+
+    /// Git commit of the recorded build
+    #[cfg(feature = "trend")]
+    #[arg(long, value_name = "HASH", requires = "record")]
+    commit: Option<String>,
+
+    /// Print size history from database of a segment, region, object or library (ex: segment:.text)
+    #[cfg(feature = "trend")]
+    #[arg(long, value_name = "KIND:NAME", requires = "database", value_parser = parse_trend)]
+    trend: Option<(TrendKind, String)>,
+
+    /// Limit size history to the last COUNT builds
+    #[cfg(feature = "trend")]
+    #[arg(long, value_name = "COUNT", requires = "trend")]
+    trend_builds: Option<usize>,
+
+    /// Size history output format
+    #[cfg(feature = "trend")]
+    #[arg(long, value_name = "FORMAT", requires = "trend", default_value = "csv", value_parser = ["csv", "html"])]
+    trend_format: String,
+
+    /// Path to size history output file. If not specified, outputs to console
+    #[cfg(feature = "trend")]
+    #[arg(long, value_name = "PATH", requires = "trend")]
+    trend_output: Option<String>,
+
     /// Set log level
     #[arg(short, long, value_name = "LEVEL", default_value = "error", value_parser= ["off", "0", "error", "1", "warn", "2", "info", "3", "debug", "4", "trace", "5"])]
     loglevel: String,
 }
 
+fn parse_address(value: &str) -> Result<u64, String> {
+    let result = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse::<u64>(),
+    };
+    result.map_err(|e| format!("Invalid address '{value}': {e}"))
+}
+
+fn parse_datetime_format(value: &str) -> Result<String, String> {
+    match xmlwriter::is_valid_datetime_format(value) {
+        true => Ok(value.to_string()),
+        false => Err(format!("Invalid datetime format '{value}'")),
+    }
+}
+
+fn parse_query(value: &str) -> Result<Query, String> {
+    Query::parse(value).map_err(|e| e.to_string())
+}
+
+fn parse_symbol_range(value: &str) -> Result<(String, String), String> {
+    match value.split_once(':') {
+        Some((start, end)) if !start.is_empty() && !end.is_empty() => {
+            Ok((start.to_string(), end.to_string()))
+        }
+        _ => Err(format!(
+            "Invalid symbol range '{value}', expected <START>:<END>"
+        )),
+    }
+}
+
+#[cfg(feature = "trend")]
+fn parse_trend(value: &str) -> Result<(TrendKind, String), String> {
+    let (kind, name) = value
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid trend '{value}', expected <KIND>:<NAME>"))?;
+    let kind = TrendKind::from_name(kind).ok_or_else(|| {
+        format!("Invalid trend kind '{kind}', expected segment, region, object or library")
+    })?;
+    Ok((kind, name.to_string()))
+}
+
+fn to_io_error<E>(e: E) -> std::io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    std::io::Error::other(e)
+}
+
 fn config_log_level(loglevel: &str) {
     let level = match loglevel {
         "off" | "0" => LevelFilter::Off,
@@ -57,29 +283,174 @@ fn main() -> std::io::Result<()> {
 
     config_log_level(&cli.loglevel);
 
-    let file = File::open(&cli.mapfile)?;
-    let data = std::io::read_to_string(file)?;
+    if let Some(mapfile) = &cli.mapfile {
+        process_mapfile(&cli, mapfile)?;
+    }
+
+    if !cli.batch.is_empty() {
+        process_batch(&cli)?;
+    }
+
+    if !cli.bundle.is_empty() {
+        process_bundle(&cli)?;
+    }
 
-    let parser = MapParser::parse(&data);
+    #[cfg(feature = "trend")]
+    if let Some((kind, name)) = &cli.trend {
+        // Checked by clap
+        let database = TrendDatabase::open(cli.database.as_ref().unwrap()).map_err(to_io_error)?;
+        let points = database
+            .query(*kind, name, cli.trend_builds)
+            .map_err(to_io_error)?;
 
-    if let Some(path) = cli.xmlfile {
+        let mut file: Box<dyn Write> = match &cli.trend_output {
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(std::io::stdout()),
+        };
+        match cli.trend_format.as_str() {
+            "html" => trend::write_html(&mut file, *kind, name, &points)?,
+            _ => trend::write_csv(&mut file, &points)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses `mapfile` (or stdin if "-"), decompressing it if needed, or reads it as XML if it has ".xml" extension
+fn read_mapfile(mapfile: &str) -> std::io::Result<MapParser> {
+    let input = Input::open(mapfile).map_err(to_io_error)?;
+
+    match input.is_xml() {
+        true => XmlReader::new(input.get_text().as_bytes())
+            .read()
+            .map_err(to_io_error),
+        false => Ok(MapParser::parse(input.get_text())),
+    }
+}
+
+fn process_batch(cli: &Cli) -> std::io::Result<()> {
+    let paths = batch::find_mapfiles(&cli.batch)?;
+    let names = batch::get_variant_names(&paths);
+
+    let mut parsers = vec![];
+    for path in &paths {
+        parsers.push(read_mapfile(&path.to_string_lossy())?);
+    }
+    let report = BatchReport::new(names.iter().map(|n| n.as_str()).zip(&parsers));
+
+    match &cli.batch_csv {
+        Some(path) => report.write_csv(&mut File::create(path)?),
+        None => report.write_table(&mut std::io::stdout()),
+    }
+}
+
+fn process_bundle(cli: &Cli) -> std::io::Result<()> {
+    let images = cli
+        .bundle
+        .iter()
+        .map(|value| match value.split_once('=') {
+            Some((name, path)) => (Some(name), path),
+            None => (None, value.as_str()),
+        })
+        .collect::<Vec<_>>();
+    // Images without explicit name are named after the differing parts of their paths
+    let unnamed = images
+        .iter()
+        .filter(|(name, _)| name.is_none())
+        .map(|(_, path)| *path)
+        .collect::<Vec<_>>();
+    let mut default_names = batch::get_variant_names(&unnamed).into_iter();
+
+    let mut bundle = Bundle::new();
+    for (name, path) in &images {
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => default_names.next().unwrap(),
+        };
+        bundle
+            .add_image(&name, read_mapfile(path)?)
+            .map_err(to_io_error)?;
+    }
+
+    for image in bundle.get_images() {
+        println!("Image {}", image.get_name());
+        for region in image.get_regions() {
+            println!(
+                "    Region {}: {} of {} bytes used",
+                region.get_name(),
+                region.get_used_size(),
+                region.get_length()
+            );
+        }
+    }
+
+    for flash in bundle.get_shared_flashes() {
+        println!(
+            "Flash {} at {:#010x}: {} of {} bytes used",
+            flash.get_name(),
+            flash.get_origin(),
+            flash.get_used_size(),
+            flash.get_length()
+        );
+        for (image, size) in flash.get_used_sizes() {
+            println!("    {}: {} bytes", image, size);
+        }
+    }
+
+    let overlaps = bundle.find_overlaps();
+    if overlaps.is_empty() {
+        println!("No image overlaps found");
+    }
+    for overlap in overlaps {
+        println!("{overlap}");
+    }
+
+    Ok(())
+}
+
+fn process_mapfile(cli: &Cli, mapfile: &str) -> std::io::Result<()> {
+    let parser = read_mapfile(mapfile)?;
+    // Checked by clap
+    let sort_key = SortKey::from_name(&cli.sort).unwrap();
+    let component_rules = match &cli.components {
+        Some(path) => Some(ComponentRules::open(path)?),
+        None => None,
+    };
+
+    #[cfg(feature = "tui")]
+    if cli.tui {
+        let diff = match &cli.diff {
+            Some(old_mapfile) => Some(Diff::new(&read_mapfile(old_mapfile)?, &parser)),
+            None => None,
+        };
+        return tui::run(&parser, diff.as_ref());
+    }
+
+    if let Some(path) = &cli.xmlfile {
         let file: Box<dyn Write> = match path.eq("stdout") {
             true => Box::new(std::io::stdout()),
-            false => Box::new(std::fs::File::create(&path)?),
+            false => Box::new(std::fs::File::create(path)?),
         };
 
-        let mut xmlwriter = XmlWriter::new(file, &cli.mapfile);
-        xmlwriter.set_skip_data(true);
+        let mut xmlwriter =
+            XmlWriter::with_datetime_format(file, mapfile, &cli.xml_datetime_format);
+        xmlwriter.set_skip_data(!cli.xml_data);
+        xmlwriter.set_demangle(cli.demangle);
+        xmlwriter.set_padding(cli.padding);
+        xmlwriter.set_grouping(cli.group);
+        xmlwriter.set_sort_key(sort_key);
+        if let Some(rules) = &component_rules {
+            xmlwriter.set_component_rules(rules.clone());
+        }
         parser.to_xml_writer(&mut xmlwriter);
-    } else if cli.xlsfile.is_none() {
-        println!(
-            "Parsed mapfile: {}",
-            Path::new(&cli.mapfile)
-                .canonicalize()
-                .unwrap()
-                .to_str()
-                .unwrap()
-        );
+    } else if cli.xlsfile.is_none() && cli.query.is_none() {
+        match mapfile == STDIN_PATH {
+            true => println!("Parsed mapfile: stdin"),
+            false => println!(
+                "Parsed mapfile: {}",
+                Path::new(mapfile).canonicalize().unwrap().to_str().unwrap()
+            ),
+        }
         let mut count = 0;
         for segment in parser.get_memory_map_segments() {
             count += segment.get_entries().len();
@@ -94,10 +465,291 @@ fn main() -> std::io::Result<()> {
             "    Objects count: {}",
             parser.get_memory_map_segments().len()
         );
+        for region in parser.get_memory_regions() {
+            println!(
+                "    Region {}: {} of {} bytes used",
+                region.get_name(),
+                region.get_used_size(),
+                region.get_length()
+            );
+        }
+
+        let padding = Padding::new(&parser);
+        println!("    Padding: {} bytes", padding.get_total_size());
+        for gap in padding.get_gaps().iter().take(PADDING_GAPS_COUNT) {
+            println!(
+                "        {} bytes at {:#010x} after {} ({})",
+                gap.get_size(),
+                gap.get_address(),
+                gap.get_entry_name(),
+                gap.get_segment_name()
+            );
+        }
+    }
+
+    let image = match &cli.elf {
+        Some(path) => Some(ElfImage::open(path)?),
+        None => None,
+    };
+
+    for name in &cli.symbol {
+        // Symbol sizes are not in the mapfile, only in the ELF symbol table
+        let elf_symbol = image.as_ref().and_then(|image| image.get_symbol(name));
+        let address = match parser.get_memory_map_symbol(name) {
+            Some(symbol) => Some(symbol.get_address()),
+            None => elf_symbol.map(|s| s.get_address()),
+        };
+        match (address, elf_symbol) {
+            (Some(address), Some(elf_symbol)) => println!(
+                "{}: {:#010x} ({} bytes)",
+                name,
+                address,
+                elf_symbol.get_size()
+            ),
+            (Some(address), None) => println!("{}: {:#010x}", name, address),
+            (None, _) => println!("{}: not found", name),
+        }
+    }
+
+    for (start, end) in &cli.symbol_range {
+        match parser.get_memory_map_symbols_distance(start, end) {
+            Some(distance) => println!("{}..{}: {} bytes", start, end, distance),
+            None => match (
+                parser.get_memory_map_symbol(start),
+                parser.get_memory_map_symbol(end),
+            ) {
+                (Some(start_symbol), Some(end_symbol)) => println!(
+                    "{}..{}: end is placed {} bytes before start",
+                    start,
+                    end,
+                    start_symbol.get_address() - end_symbol.get_address()
+                ),
+                _ => println!("{}..{}: not found", start, end),
+            },
+        }
     }
 
-    if let Some(path) = cli.xlsfile {
-        let mut excelwriter = ExcelWriter::new(&path).unwrap();
+    if !cli.lookup.is_empty() {
+        let index = AddressIndex::new(&parser);
+        for address in &cli.lookup {
+            let info = match index.lookup(*address) {
+                Some(info) => info,
+                None => {
+                    println!("{:#010x}: not found", address);
+                    continue;
+                }
+            };
+
+            let entry = info.get_entry();
+            let entry_name = match cli.demangle {
+                true => entry.get_demangled_name(),
+                false => None,
+            }
+            .unwrap_or_else(|| entry.get_name().to_string());
+            let location = match info.get_symbol() {
+                Some(symbol) => {
+                    let symbol_name = match cli.demangle {
+                        true => symbol.get_demangled_name(),
+                        false => None,
+                    }
+                    .unwrap_or_else(|| symbol.get_name().to_string());
+                    format!("{}+{:#x}", symbol_name, info.get_offset())
+                }
+                None => format!("{}+{:#x}", entry_name, info.get_offset()),
+            };
+            println!(
+                "{:#010x}: {} in {} ({}) object: {} library: {}",
+                address,
+                location,
+                entry_name,
+                info.get_segment().get_name(),
+                entry.get_object_name().unwrap_or("-"),
+                entry.get_library_name().unwrap_or("-")
+            );
+        }
+    }
+
+    if cli.group {
+        let grouping = Grouping::new(&parser);
+        for (category, size) in grouping.get_categories() {
+            println!("{}: {} bytes", category.as_str(), size);
+        }
+        for group in grouping.get_groups() {
+            let name = match cli.demangle {
+                true => group.get_demangled_name(),
+                false => None,
+            }
+            .unwrap_or_else(|| group.get_name().to_string());
+            println!(
+                "    {} bytes {} ({}, {} entries)",
+                group.get_size(),
+                name,
+                group.get_category().as_str(),
+                group.get_entry_count()
+            );
+        }
+    }
+
+    if let Some(rules) = &component_rules {
+        let components = Components::new(&parser, rules);
+        println!("Components:");
+        for component in components.get_components() {
+            println!(
+                "    {} bytes {} ({} entries)",
+                component.get_size(),
+                component.get_name(),
+                component.get_entry_count()
+            );
+        }
+        println!("    {} bytes unassigned", components.get_unassigned_size());
+    }
+
+    if cli.rollup {
+        let rollup = DirectoryRollup::new(&parser);
+        println!("{:>10}  total", rollup.get_total_size());
+        for (_, node) in rollup.get_nodes(cli.rollup_depth) {
+            println!("{:>10}  {}", node.get_size(), node.get_path());
+        }
+    }
+
+    if cli.validate {
+        let validation = Validation::new(&parser);
+        if validation.is_valid() {
+            println!("No issues found");
+        }
+        for issue in validation.get_issues() {
+            println!("{issue}");
+        }
+    }
+
+    if let Some(image) = &image {
+        let check = ElfCheck::new(&parser, image);
+        // Checked by clap
+        println!("ELF check with {}:", cli.elf.as_ref().unwrap());
+        if check.is_valid() {
+            println!("    No issues found");
+        }
+        for issue in check.get_issues() {
+            println!("    {issue}");
+        }
+
+        if cli.entry_symbols {
+            println!("Entry symbols:");
+            for segment in parser.get_memory_map_segments() {
+                for entry in segment.get_entries() {
+                    let symbols = image.get_entry_symbols(entry);
+                    if symbols.is_empty() {
+                        continue;
+                    }
+                    println!(
+                        "    {} bytes {} ({})",
+                        entry.get_original_size(),
+                        entry.get_name(),
+                        segment.get_name()
+                    );
+                    for symbol in symbols {
+                        println!(
+                            "        {} bytes {} at {:#010x}",
+                            symbol.get_size(),
+                            symbol.get_name(),
+                            symbol.get_address()
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    if let (true, Some(path)) = (cli.sources, &cli.elf) {
+        let debug_info = DebugInfo::parse(&std::fs::read(path)?).map_err(to_io_error)?;
+        if debug_info.is_empty() {
+            println!(
+                "No debug information found in {}",
+                cli.elf.as_ref().unwrap()
+            );
+        }
+        let sizes = SourceSizes::new(&parser, &debug_info, cli.source_root.as_deref());
+        println!("Source directories:");
+        for (directory, size) in sizes.get_directories() {
+            println!("    {} bytes {}", size, directory);
+        }
+        println!("Source files:");
+        for (file, size) in sizes.get_files() {
+            println!("    {} bytes {}", size, file);
+        }
+        println!(
+            "Without debug information: {} bytes",
+            sizes.get_unattributed_size()
+        );
+    }
+
+    if let Some(query) = &cli.query {
+        let result = query.run(&parser);
+        let mut stdout = std::io::stdout();
+        match cli.query_format.as_str() {
+            "json" => result.write_json(&mut stdout)?,
+            _ => result.write_table(&mut stdout)?,
+        }
+    }
+
+    if let Some(old_mapfile) = &cli.diff {
+        let diff = Diff::new(&read_mapfile(old_mapfile)?, &parser);
+        let size = |size: Option<u64>| size.map_or("-".to_string(), |s| s.to_string());
+        println!("Diff with {old_mapfile}:");
+        for kind in DiffKind::ALL {
+            println!(
+                "    {}: {:+} bytes",
+                kind.as_str(),
+                diff.get_total_delta(kind)
+            );
+            // Entries are too many to be printed, they are written only in XLSX diff file
+            if kind == DiffKind::Entry {
+                continue;
+            }
+            for item in diff.get_items_of_kind(kind) {
+                if item.get_delta() != 0 || item.is_added() || item.is_removed() {
+                    println!(
+                        "        {}: {} -> {} ({:+})",
+                        item.get_name(),
+                        size(item.get_old_size()),
+                        size(item.get_new_size()),
+                        item.get_delta()
+                    );
+                }
+            }
+        }
+
+        if let Some(path) = &cli.diff_xlsfile {
+            excelwriter::write_diff(path, &diff).map_err(to_io_error)?;
+        }
+    }
+
+    #[cfg(feature = "trend")]
+    if let Some(build_id) = &cli.record {
+        // Checked by clap
+        let mut database =
+            TrendDatabase::open(cli.database.as_ref().unwrap()).map_err(to_io_error)?;
+        if database.contains_build(build_id).map_err(to_io_error)? {
+            return Err(to_io_error(format!("Build '{build_id}' already recorded")));
+        }
+
+        let mut build = BuildInfo::new(build_id);
+        if let Some(commit) = &cli.commit {
+            build.set_commit(commit);
+        }
+        database.record(&parser, &build).map_err(to_io_error)?;
+    }
+
+    if let Some(path) = &cli.xlsfile {
+        let mut excelwriter = ExcelWriter::new(path).unwrap();
+        excelwriter.set_demangle(cli.demangle);
+        excelwriter.set_padding(cli.padding);
+        excelwriter.set_grouping(cli.group);
+        excelwriter.set_sort_key(sort_key);
+        if let Some(rules) = component_rules {
+            excelwriter.set_component_rules(rules);
+        }
+        excelwriter.set_budget(cli.budget as f64 / 100.0);
         parser.to_excel_writer(&mut excelwriter);
     }
 
@@ -0,0 +1,237 @@
+//! Terminal UI
+//!
+//! Browses the region → segment → object → entry tree of a parsed mapfile, with incremental search, a detail pane
+//! and the size differences from an older mapfile
+
+use ::parser::browser::{Browser, BrowserNode, NodeKind, PAGE_SIZE};
+use ::parser::diff::Diff;
+use ::parser::Parser as MapParser;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+
+/// Input mode of the status line
+#[derive(PartialEq, Eq)]
+enum Mode {
+    /// Keys move in the tree
+    Browse,
+    /// Keys edit the search text
+    Search,
+}
+
+struct App<'a> {
+    browser: Browser<'a>,
+    mode: Mode,
+    search: String,
+    has_diff: bool,
+}
+
+/// Runs the terminal UI on `parser` results until the user quits, with size differences from `diff` if set
+pub fn run(parser: &MapParser, diff: Option<&Diff>) -> std::io::Result<()> {
+    let mut browser = Browser::new(parser);
+    if let Some(diff) = diff {
+        browser.set_diff(diff);
+    }
+    let mut app = App {
+        browser,
+        mode: Mode::Browse,
+        search: String::new(),
+        has_diff: diff.is_some(),
+    };
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl App<'_> {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let key = match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => key,
+                _ => continue,
+            };
+
+            match self.mode {
+                Mode::Search => match key.code {
+                    KeyCode::Enter => self.mode = Mode::Browse,
+                    KeyCode::Esc => {
+                        self.search.clear();
+                        self.browser.set_search("");
+                        self.mode = Mode::Browse;
+                    }
+                    KeyCode::Backspace => {
+                        self.search.pop();
+                        self.browser.set_search(&self.search);
+                    }
+                    KeyCode::Char(c) => {
+                        self.search.push(c);
+                        self.browser.set_search(&self.search);
+                    }
+                    _ => {}
+                },
+                Mode::Browse => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Down | KeyCode::Char('j') => self.browser.move_selection(1),
+                    KeyCode::Up | KeyCode::Char('k') => self.browser.move_selection(-1),
+                    KeyCode::PageDown => self.browser.move_selection(PAGE_SIZE as isize),
+                    KeyCode::PageUp => self.browser.move_selection(-(PAGE_SIZE as isize)),
+                    KeyCode::Home => self.browser.select_first(),
+                    KeyCode::End => self.browser.select_last(),
+                    KeyCode::Enter | KeyCode::Char(' ') => self.browser.toggle_selected(),
+                    KeyCode::Right | KeyCode::Char('l') => self.browser.expand_selected(),
+                    KeyCode::Left | KeyCode::Char('h') => self.browser.collapse_selected(),
+                    KeyCode::Char('/') => self.mode = Mode::Search,
+                    KeyCode::Char('d') if self.has_diff => {
+                        let diff_only = !self.browser.get_diff_only();
+                        self.browser.set_diff_only(diff_only);
+                    }
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, status] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .areas(frame.area());
+        let [tree, detail] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .areas(main);
+
+        let items = self
+            .browser
+            .get_rows()
+            .iter()
+            .map(|index| self.tree_item(*index))
+            .collect::<Vec<_>>();
+        let title = match self.browser.get_diff_only() {
+            true => " Memory map (changed only) ",
+            false => " Memory map ",
+        };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(self.browser.get_selected_row()));
+        frame.render_stateful_widget(list, tree, &mut state);
+
+        let lines = match self.browser.get_selected() {
+            Some(node) => detail_lines(node),
+            None => vec![Line::from("No matching node")],
+        };
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(" Details "))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, detail);
+
+        let text = match self.mode {
+            Mode::Search => format!("/{}", self.search),
+            Mode::Browse => {
+                let mut text =
+                    String::from("q: quit  ↑↓: move  ←→/Enter: collapse/expand  /: search");
+                if self.has_diff {
+                    text.push_str("  d: changed only");
+                }
+                if !self.search.is_empty() {
+                    text.push_str(&format!("  [search: {}]", self.search));
+                }
+                text
+            }
+        };
+        frame.render_widget(Paragraph::new(text), status);
+    }
+
+    fn tree_item(&self, index: usize) -> ListItem<'static> {
+        let node = self.browser.get_node(index);
+        let marker = match (
+            node.get_children().is_empty(),
+            self.browser.is_expanded(index),
+        ) {
+            (true, _) => "  ",
+            (false, true) => "▾ ",
+            (false, false) => "▸ ",
+        };
+
+        let mut spans = vec![
+            Span::raw("  ".repeat(node.get_depth())),
+            Span::raw(marker),
+            Span::raw(format!("{:>10}  ", node.get_size())),
+            Span::raw(node.get_name().to_string()),
+        ];
+        if let Some(delta) = node.get_delta().filter(|d| *d != 0) {
+            let color = match delta > 0 {
+                true => Color::Red,
+                false => Color::Green,
+            };
+            spans.push(Span::styled(
+                format!("  {delta:+}"),
+                Style::default().fg(color),
+            ));
+        }
+        ListItem::new(Line::from(spans))
+    }
+}
+
+/// Returns detail pane lines of `node`: its properties and, for entries, the mapfile lines it was parsed from
+fn detail_lines(node: &BrowserNode) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(format!("{}: {}", node.get_kind().as_str(), node.get_name())),
+        Line::from(format!("Size: {} bytes", node.get_size())),
+    ];
+    if let Some(delta) = node.get_delta() {
+        lines.push(Line::from(format!("Delta: {delta:+} bytes")));
+    }
+
+    match node.get_kind() {
+        NodeKind::Region => {
+            if let Some(region) = node.get_region() {
+                lines.push(Line::from(format!("Origin: {:#010x}", region.get_origin())));
+                lines.push(Line::from(format!("Length: {} bytes", region.get_length())));
+            }
+        }
+        NodeKind::Segment => {
+            if let Some(segment) = node.get_segment() {
+                if let Some(address) = segment.get_address() {
+                    lines.push(Line::from(format!("Address: {address:#010x}")));
+                }
+                if let Some(address) = segment.get_load_address() {
+                    lines.push(Line::from(format!("Load address: {address:#010x}")));
+                }
+                lines.push(Line::from(format!(
+                    "Entries: {}",
+                    segment.get_entries().len()
+                )));
+            }
+        }
+        NodeKind::Object => {}
+        NodeKind::Entry => {
+            if let Some(entry) = node.get_entry() {
+                lines.push(Line::from(format!(
+                    "Address: {:#010x}",
+                    entry.get_address()
+                )));
+                if let Some(object_name) = entry.get_object_name() {
+                    lines.push(Line::from(format!("Object: {object_name}")));
+                }
+                if let Some(library_name) = entry.get_library_name() {
+                    lines.push(Line::from(format!("Library: {library_name}")));
+                }
+                lines.push(Line::from(""));
+                lines.extend(entry.get_data().lines().map(|l| Line::from(l.to_string())));
+            }
+        }
+    }
+
+    lines
+}
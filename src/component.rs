@@ -0,0 +1,374 @@
+//! Component module
+//!
+//! This module contains the code to map objects, libraries and [Entries](Entry) to logical components (ex: teams)
+//! using a rules file, and to sum [Entry] sizes per component
+//!
+//! Each rules file line has the `[TARGET:]PATTERN -> COMPONENT` format, where `TARGET` is one of `object`
+//! (default), `library` or `entry`. Empty lines and lines starting with `#` are ignored:
+//!
+//! ```text
+//! # Object paths
+//! drivers/wifi/** -> connectivity
+//! library:libbt*.a -> connectivity
+//! entry:.text.crypto_* -> security
+//! ```
+//!
+//! Patterns use glob syntax: `*` matches any characters except `/`, `**` matches any characters and `?` matches a
+//! single character except `/`. Patterns not starting with `/` also match at any directory level (ex: `wifi/*.o`
+//! matches `build/drivers/wifi/scan.o`). The first matching rule, in file order, assigns the component
+
+use crate::{
+    entry::Entry,
+    xmlwriter::{ToXmlWriter, XmlWriter},
+    Parser,
+};
+use regex::Regex;
+use std::{collections::HashMap, fmt::Display, io::Write, path::Path};
+use xml::writer::XmlEvent;
+
+/// Separator between rule pattern and component name
+const RULE_SEPARATOR: &str = "->";
+
+/// Enum containing the names matched by a rule
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum RuleTarget {
+    /// Object name (path) of the entry
+    Object,
+    /// Library name (path) of the entry
+    Library,
+    /// Entry name
+    Entry,
+}
+
+impl RuleTarget {
+    /// Returns target name as used in rules files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleTarget::Object => "object",
+            RuleTarget::Library => "library",
+            RuleTarget::Entry => "entry",
+        }
+    }
+
+    /// Returns [RuleTarget] matching `name` or [None](Option::None) if unknown
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "object" => Some(RuleTarget::Object),
+            "library" => Some(RuleTarget::Library),
+            "entry" => Some(RuleTarget::Entry),
+            _ => None,
+        }
+    }
+}
+
+/// Enum containing the errors that can occur while reading a rules file
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RuleError {
+    /// Line is not in `[TARGET:]PATTERN -> COMPONENT` format
+    InvalidLine {
+        /// Line number, starting with 1
+        line: usize,
+        /// Line text
+        text: String,
+    },
+    /// Unknown rule target
+    InvalidTarget {
+        /// Line number, starting with 1
+        line: usize,
+        /// Target name
+        target: String,
+    },
+}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleError::InvalidLine { line, text } => write!(
+                f,
+                "Invalid rule at line {line} '{text}', expected [TARGET:]PATTERN -> COMPONENT"
+            ),
+            RuleError::InvalidTarget { line, target } => write!(
+                f,
+                "Invalid rule target at line {line} '{target}', expected object, library or entry"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+/// Structure containing a rule mapping names matched by a pattern to a component
+#[derive(Debug, Clone)]
+pub struct ComponentRule {
+    /// Matched names
+    target: RuleTarget,
+    /// Glob pattern
+    pattern: String,
+    /// Component name
+    component: String,
+    /// Pattern converted to regular expression
+    regex: Regex,
+}
+
+impl ComponentRule {
+    /// Creates a new [ComponentRule] assigning names of `target` matched by glob `pattern` to `component`
+    pub fn new(target: RuleTarget, pattern: &str, component: &str) -> Self {
+        Self {
+            target,
+            pattern: pattern.to_string(),
+            component: component.to_string(),
+            regex: Self::to_regex(pattern),
+        }
+    }
+
+    /// Converts glob `pattern` to an anchored [Regex]
+    fn to_regex(pattern: &str) -> Regex {
+        let mut regex = match pattern.starts_with('/') {
+            true => String::from("^"),
+            false => String::from("^(?:.*/)?"),
+        };
+
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    // "**/" also matches no directory
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        regex.push_str("(?:.*/)?");
+                    } else {
+                        regex.push_str(".*");
+                    }
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                _ => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push('$');
+
+        // Only literals and valid constructs are generated
+        Regex::new(&regex).unwrap()
+    }
+
+    /// Get rule [target](#structfield.target)
+    pub fn get_target(&self) -> RuleTarget {
+        self.target
+    }
+
+    /// Get rule [pattern](#structfield.pattern)
+    pub fn get_pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Get rule [component](#structfield.component)
+    pub fn get_component(&self) -> &str {
+        &self.component
+    }
+
+    /// Returns [true] if the rule matches `entry`
+    pub fn is_match(&self, entry: &Entry) -> bool {
+        let name = match self.target {
+            RuleTarget::Object => entry.get_object_name(),
+            RuleTarget::Library => entry.get_library_name(),
+            RuleTarget::Entry => Some(entry.get_name()),
+        };
+        name.is_some_and(|name| self.regex.is_match(name))
+    }
+}
+
+/// Structure containing the rules read from a rules file
+#[derive(Debug, Clone, Default)]
+pub struct ComponentRules {
+    /// List of rules, in file order
+    rules: Vec<ComponentRule>,
+}
+
+impl ComponentRules {
+    /// Creates a new empty [ComponentRules]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses rules file content `data`
+    pub fn parse(data: &str) -> Result<Self, RuleError> {
+        let mut rules = Self::new();
+
+        for (index, text) in data.lines().enumerate() {
+            let line = text.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid_line = || RuleError::InvalidLine {
+                line: index + 1,
+                text: text.to_string(),
+            };
+            let (pattern, component) = line.rsplit_once(RULE_SEPARATOR).ok_or_else(invalid_line)?;
+            let (pattern, component) = (pattern.trim(), component.trim());
+
+            // Windows paths (ex: C:/...) are not supported, so a ':' always separates the target
+            let (target, pattern) = match pattern.split_once(':') {
+                Some((target, pattern)) => match RuleTarget::from_name(target.trim()) {
+                    Some(target) => (target, pattern.trim()),
+                    None => {
+                        return Err(RuleError::InvalidTarget {
+                            line: index + 1,
+                            target: target.trim().to_string(),
+                        })
+                    }
+                },
+                None => (RuleTarget::Object, pattern),
+            };
+            if pattern.is_empty() || component.is_empty() {
+                return Err(invalid_line());
+            }
+
+            rules.add_rule(ComponentRule::new(target, pattern, component));
+        }
+
+        Ok(rules)
+    }
+
+    /// Reads rules file at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let data = std::fs::read_to_string(path)?;
+        Self::parse(&data).map_err(std::io::Error::other)
+    }
+
+    /// Adds new [ComponentRule], matched after the existing ones
+    pub fn add_rule(&mut self, rule: ComponentRule) {
+        self.rules.push(rule);
+    }
+
+    /// Get all [rules](#structfield.rules)
+    pub fn get_rules(&self) -> &[ComponentRule] {
+        &self.rules
+    }
+
+    /// Get component of `entry` from the first matching rule or [None](Option::None)
+    pub fn get_component(&self, entry: &Entry) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|r| r.is_match(entry))
+            .map(|r| r.get_component())
+    }
+}
+
+/// Structure containing a component size
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ComponentSize {
+    /// Component name
+    name: String,
+    /// Sum of entry sizes, including `*fill*` padding
+    size: u64,
+    /// Number of entries
+    entry_count: usize,
+}
+
+impl ComponentSize {
+    /// Get component [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get component [size](#structfield.size)
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    /// Get [entry_count](#structfield.entry_count)
+    pub fn get_entry_count(&self) -> usize {
+        self.entry_count
+    }
+}
+
+/// Structure containing entry sizes per component
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Components {
+    /// List of components, sorted by size (largest first). Components without matching entries are included
+    components: Vec<ComponentSize>,
+    /// Size of entries not matched by any rule
+    unassigned_size: u64,
+}
+
+impl Components {
+    /// Creates a new [Components] from `parser` results and `rules`
+    ///
+    /// Only entries from [Parser::get_allocated_segments] are counted
+    pub fn new(parser: &Parser, rules: &ComponentRules) -> Self {
+        let mut sizes: HashMap<&str, (u64, usize)> = rules
+            .get_rules()
+            .iter()
+            .map(|r| (r.get_component(), (0, 0)))
+            .collect();
+        let mut unassigned_size = 0;
+
+        for segment in parser.get_allocated_segments() {
+            for entry in segment.get_entries() {
+                match rules.get_component(entry) {
+                    Some(component) => {
+                        let (size, count) = sizes.get_mut(component).unwrap();
+                        *size += entry.get_size();
+                        *count += 1;
+                    }
+                    None => unassigned_size += entry.get_size(),
+                }
+            }
+        }
+
+        let mut components = sizes
+            .into_iter()
+            .map(|(name, (size, entry_count))| ComponentSize {
+                name: name.to_string(),
+                size,
+                entry_count,
+            })
+            .collect::<Vec<_>>();
+        components.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+        Self {
+            components,
+            unassigned_size,
+        }
+    }
+
+    /// Get all [components](#structfield.components)
+    pub fn get_components(&self) -> &[ComponentSize] {
+        &self.components
+    }
+
+    /// Get size of component `name` or [None](Option::None) if there is no rule for it
+    pub fn get_size(&self, name: &str) -> Option<u64> {
+        self.components
+            .iter()
+            .find(|c| c.name == name)
+            .map(|c| c.size)
+    }
+
+    /// Get [unassigned_size](#structfield.unassigned_size)
+    pub fn get_unassigned_size(&self) -> u64 {
+        self.unassigned_size
+    }
+}
+
+impl<W: Write> ToXmlWriter<W> for Components {
+    fn to_xml_writer(&self, writer: &mut XmlWriter<W>) {
+        writer.start_element(
+            XmlEvent::start_element("components")
+                .attr("count", &self.components.len().to_string())
+                .attr("unassigned_size", &self.unassigned_size.to_string()),
+        );
+        for component in &self.components {
+            writer.start_element(
+                XmlEvent::start_element("component")
+                    .attr("name", &component.name)
+                    .attr("size", &component.size.to_string())
+                    .attr("entries", &component.entry_count.to_string()),
+            );
+            writer.end_element();
+        }
+        writer.end_element();
+    }
+}
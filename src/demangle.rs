@@ -0,0 +1,40 @@
+//! Demangle module
+//!
+//! This module contains the code to demangle C++ (Itanium ABI) and Rust (legacy and v0) symbol names
+
+/// Prefixes of mangled names: Itanium/Rust legacy (`_Z`, `__Z` on Mach-O) and Rust v0 (`_R`)
+const MANGLED_PREFIXES: [&str; 3] = ["_Z", "__Z", "_R"];
+
+/// Splits `name` into a section prefix and the mangled symbol
+///
+/// Entry names built with `-ffunction-sections`/`-fdata-sections` keep the section name in front of the
+/// symbol (ex: `.text._ZN3foo3barEv`), which is returned as prefix including the trailing dot
+fn split_mangled(name: &str) -> Option<(&str, &str)> {
+    if MANGLED_PREFIXES.iter().any(|p| name.starts_with(p)) {
+        return Some(("", name));
+    }
+
+    name.match_indices('.')
+        .map(|(pos, _)| name.split_at(pos + 1))
+        .find(|(_, symbol)| MANGLED_PREFIXES.iter().any(|p| symbol.starts_with(p)))
+}
+
+/// Demangles a single mangled `symbol`, trying Rust first since legacy Rust names are also valid Itanium names
+fn demangle_symbol(symbol: &str) -> Option<String> {
+    if let Ok(demangled) = rustc_demangle::try_demangle(symbol) {
+        // Alternate format skips the hash suffix
+        return Some(format!("{demangled:#}"));
+    }
+
+    cpp_demangle::Symbol::new(symbol)
+        .ok()
+        .and_then(|s| s.demangle(&cpp_demangle::DemangleOptions::default()).ok())
+}
+
+/// Returns demangled `name` or [None](Option::None) if the name is not mangled or can not be demangled
+///
+/// Section prefixes are kept (ex: `.text._ZN3foo3barEv` becomes `.text.foo::bar()`)
+pub fn demangle(name: &str) -> Option<String> {
+    let (prefix, symbol) = split_mangled(name)?;
+    demangle_symbol(symbol).map(|demangled| format!("{prefix}{demangled}"))
+}
@@ -0,0 +1,261 @@
+//! Diff module
+//!
+//! This module contains the code to compare the sizes of [Segments](crate::segment::Segment), objects, libraries
+//! and [Entries](crate::entry::Entry) of two [Parser] results (old and new mapfile)
+
+use crate::Parser;
+use std::collections::BTreeMap;
+
+/// Enum containing the compared item types
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum DiffKind {
+    /// Segment size
+    Segment,
+    /// Sum of all segment sizes of an object
+    Object,
+    /// Sum of all entry sizes of a library
+    Library,
+    /// Entry size, including fill
+    Entry,
+}
+
+impl DiffKind {
+    /// All kinds, in output order
+    pub const ALL: [DiffKind; 4] = [
+        DiffKind::Segment,
+        DiffKind::Object,
+        DiffKind::Library,
+        DiffKind::Entry,
+    ];
+
+    /// Returns kind name as used in outputs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiffKind::Segment => "segment",
+            DiffKind::Object => "object",
+            DiffKind::Library => "library",
+            DiffKind::Entry => "entry",
+        }
+    }
+}
+
+/// Key identifying an item in both mapfiles: kind, name, segment name, library name and object name (only for
+/// entries)
+type DiffKey = (
+    DiffKind,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+/// Structure containing the old and new size of an item
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DiffItem {
+    /// Item type
+    kind: DiffKind,
+    /// Item name
+    name: String,
+    /// Name of the segment where the entry is placed, [None](Option::None) if not an entry
+    segment_name: Option<String>,
+    /// Name of the library the entry belongs to, [None](Option::None) if not an entry or missing
+    library_name: Option<String>,
+    /// Name of the object the entry belongs to, [None](Option::None) if not an entry or missing
+    object_name: Option<String>,
+    /// Size in old mapfile or [None](Option::None) if added
+    old_size: Option<u64>,
+    /// Size in new mapfile or [None](Option::None) if removed
+    new_size: Option<u64>,
+}
+
+impl DiffItem {
+    /// Get item [kind](#structfield.kind)
+    pub fn get_kind(&self) -> DiffKind {
+        self.kind
+    }
+
+    /// Get item [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get [segment_name](#structfield.segment_name) or [None](Option::None)
+    pub fn get_segment_name(&self) -> Option<&str> {
+        self.segment_name.as_deref()
+    }
+
+    /// Get [library_name](#structfield.library_name) or [None](Option::None)
+    pub fn get_library_name(&self) -> Option<&str> {
+        self.library_name.as_deref()
+    }
+
+    /// Get [object_name](#structfield.object_name) or [None](Option::None)
+    pub fn get_object_name(&self) -> Option<&str> {
+        self.object_name.as_deref()
+    }
+
+    /// Get [old_size](#structfield.old_size) or [None](Option::None) if added
+    pub fn get_old_size(&self) -> Option<u64> {
+        self.old_size
+    }
+
+    /// Get [new_size](#structfield.new_size) or [None](Option::None) if removed
+    pub fn get_new_size(&self) -> Option<u64> {
+        self.new_size
+    }
+
+    /// Get size difference between new and old mapfile. Missing sizes count as 0
+    pub fn get_delta(&self) -> i64 {
+        self.new_size.unwrap_or(0) as i64 - self.old_size.unwrap_or(0) as i64
+    }
+
+    /// Get size difference as percent of old size or [None](Option::None) if old size is missing or 0
+    pub fn get_percent_change(&self) -> Option<f64> {
+        match self.old_size {
+            Some(old_size) if old_size > 0 => {
+                Some(self.get_delta() as f64 * 100.0 / old_size as f64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns [true] if the item is missing from old mapfile
+    pub fn is_added(&self) -> bool {
+        self.old_size.is_none()
+    }
+
+    /// Returns [true] if the item is missing from new mapfile
+    pub fn is_removed(&self) -> bool {
+        self.new_size.is_none()
+    }
+}
+
+/// Returns sizes of all items of `parser`
+fn collect_sizes(parser: &Parser) -> BTreeMap<DiffKey, u64> {
+    let mut sizes = BTreeMap::new();
+
+    for segment in parser.get_memory_map_segments() {
+        if let Some(size) = segment.get_size() {
+            *sizes
+                .entry((
+                    DiffKind::Segment,
+                    segment.get_name().to_string(),
+                    None,
+                    None,
+                    None,
+                ))
+                .or_insert(0) += size;
+        }
+
+        for entry in segment.get_entries() {
+            *sizes
+                .entry((
+                    DiffKind::Entry,
+                    entry.get_name().to_string(),
+                    Some(segment.get_name().to_string()),
+                    entry.get_library_name().map(String::from),
+                    entry.get_object_name().map(String::from),
+                ))
+                .or_insert(0) += entry.get_size();
+
+            if let Some(library_name) = entry.get_library_name() {
+                *sizes
+                    .entry((
+                        DiffKind::Library,
+                        library_name.to_string(),
+                        None,
+                        None,
+                        None,
+                    ))
+                    .or_insert(0) += entry.get_size();
+            }
+        }
+    }
+
+    for object in parser.get_memory_map_objects().values() {
+        sizes.insert(
+            (
+                DiffKind::Object,
+                object.get_name().to_string(),
+                None,
+                None,
+                None,
+            ),
+            object.get_total_size(),
+        );
+    }
+
+    sizes
+}
+
+/// Structure containing the size differences between two mapfiles
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Diff {
+    /// List of items found in any of the mapfiles, sorted by kind and name
+    items: Vec<DiffItem>,
+}
+
+impl Diff {
+    /// Creates a new [Diff] between `old` and `new` parser results
+    pub fn new(old: &Parser, new: &Parser) -> Self {
+        let old_sizes = collect_sizes(old);
+        let mut new_sizes = collect_sizes(new);
+
+        let mut items = BTreeMap::new();
+        for (key, old_size) in old_sizes {
+            let new_size = new_sizes.remove(&key);
+            items.insert(key, (Some(old_size), new_size));
+        }
+        for (key, new_size) in new_sizes {
+            items.insert(key, (None, Some(new_size)));
+        }
+
+        let items = items
+            .into_iter()
+            .map(
+                |((kind, name, segment_name, library_name, object_name), (old_size, new_size))| {
+                    DiffItem {
+                        kind,
+                        name,
+                        segment_name,
+                        library_name,
+                        object_name,
+                        old_size,
+                        new_size,
+                    }
+                },
+            )
+            .collect();
+
+        Self { items }
+    }
+
+    /// Get all items, sorted by kind and name
+    pub fn get_items(&self) -> &[DiffItem] {
+        &self.items
+    }
+
+    /// Get all items of `kind`, sorted by name
+    pub fn get_items_of_kind(&self, kind: DiffKind) -> Vec<&DiffItem> {
+        self.items.iter().filter(|i| i.kind == kind).collect()
+    }
+
+    /// Get items that are missing from old mapfile
+    pub fn get_added(&self) -> Vec<&DiffItem> {
+        self.items.iter().filter(|i| i.is_added()).collect()
+    }
+
+    /// Get items that are missing from new mapfile
+    pub fn get_removed(&self) -> Vec<&DiffItem> {
+        self.items.iter().filter(|i| i.is_removed()).collect()
+    }
+
+    /// Get sum of size differences of all items of `kind`
+    pub fn get_total_delta(&self, kind: DiffKind) -> i64 {
+        self.items
+            .iter()
+            .filter(|i| i.kind == kind)
+            .map(|i| i.get_delta())
+            .sum()
+    }
+}
@@ -0,0 +1,410 @@
+//! ELF module
+//!
+//! This module contains the code to read section headers and the symbol table of the final ELF image and to
+//! cross-check them against the [Segments](Segment), [Entries](crate::entry::Entry) and
+//! [LinkerSymbols](crate::symbol::LinkerSymbol) parsed from the mapfile
+
+use crate::{entry::Entry, segment::Segment, Parser};
+use object::{
+    elf::SHF_ALLOC, Architecture, Object, ObjectSection, ObjectSymbol, SectionFlags, SymbolKind,
+};
+use std::{fmt::Display, path::Path};
+
+/// Structure containing an ELF section header
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ElfSection {
+    /// Section name
+    name: String,
+    /// Section address (`sh_addr`)
+    address: u64,
+    /// Section size (`sh_size`)
+    size: u64,
+    /// [true] if the section occupies target memory (`SHF_ALLOC` flag)
+    allocated: bool,
+}
+
+impl ElfSection {
+    /// Get section [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get section [address](#structfield.address)
+    pub fn get_address(&self) -> u64 {
+        self.address
+    }
+
+    /// Get section [size](#structfield.size)
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns [true] if the section occupies target memory
+    pub fn is_allocated(&self) -> bool {
+        self.allocated
+    }
+}
+
+/// Structure containing an ELF symbol table entry
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ElfSymbol {
+    /// Symbol name
+    name: String,
+    /// Symbol address (`st_value`, without the Thumb bit of ARM functions)
+    address: u64,
+    /// Symbol size (`st_size`), 0 if unknown
+    size: u64,
+    /// Name of the section where the symbol is defined or [None](Option::None) for absolute symbols
+    section_name: Option<String>,
+}
+
+impl ElfSymbol {
+    /// Get symbol [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get symbol [address](#structfield.address)
+    pub fn get_address(&self) -> u64 {
+        self.address
+    }
+
+    /// Get symbol [size](#structfield.size)
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    /// Get [section_name](#structfield.section_name) or [None](Option::None)
+    pub fn get_section_name(&self) -> Option<&str> {
+        self.section_name.as_deref()
+    }
+}
+
+/// Structure containing the section headers and the defined symbols of an ELF image
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ElfImage {
+    /// List of sections, in section header order
+    sections: Vec<ElfSection>,
+    /// List of defined symbols, sorted by address
+    symbols: Vec<ElfSymbol>,
+}
+
+impl ElfImage {
+    /// Reads section headers and symbol table from ELF image `data`
+    pub fn parse(data: &[u8]) -> Result<Self, object::Error> {
+        let file = object::File::parse(data)?;
+
+        let mut sections = vec![];
+        for section in file.sections() {
+            let name = section.name()?;
+            // Null section
+            if name.is_empty() {
+                continue;
+            }
+            let allocated = match section.flags() {
+                SectionFlags::Elf { sh_flags } => sh_flags & SHF_ALLOC as u64 != 0,
+                _ => false,
+            };
+            sections.push(ElfSection {
+                name: name.to_string(),
+                address: section.address(),
+                size: section.size(),
+                allocated,
+            });
+        }
+
+        let mut symbols = vec![];
+        for symbol in file.symbols() {
+            let kind = symbol.kind();
+            if symbol.is_undefined()
+                || !matches!(
+                    kind,
+                    SymbolKind::Text | SymbolKind::Data | SymbolKind::Tls | SymbolKind::Unknown
+                )
+            {
+                continue;
+            }
+            let name = symbol.name()?;
+            if name.is_empty() {
+                continue;
+            }
+
+            let mut address = symbol.address();
+            if file.architecture() == Architecture::Arm && kind == SymbolKind::Text {
+                address &= !1;
+            }
+            let section_name = match symbol.section_index() {
+                Some(index) => Some(file.section_by_index(index)?.name()?.to_string()),
+                None => None,
+            };
+            symbols.push(ElfSymbol {
+                name: name.to_string(),
+                address,
+                size: symbol.size(),
+                section_name,
+            });
+        }
+        symbols.sort_by_key(|s| s.address);
+
+        Ok(Self { sections, symbols })
+    }
+
+    /// Reads ELF image from file at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::parse(&data).map_err(std::io::Error::other)
+    }
+
+    /// Get all [sections](#structfield.sections)
+    pub fn get_sections(&self) -> &[ElfSection] {
+        &self.sections
+    }
+
+    /// Get first section named `name` or [None](Option::None)
+    pub fn get_section(&self, name: &str) -> Option<&ElfSection> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    /// Get all [symbols](#structfield.symbols)
+    pub fn get_symbols(&self) -> &[ElfSymbol] {
+        &self.symbols
+    }
+
+    /// Get first symbol named `name` or [None](Option::None)
+    pub fn get_symbol(&self, name: &str) -> Option<&ElfSymbol> {
+        self.symbols.iter().find(|s| s.name == name)
+    }
+
+    /// Get symbols with a size placed inside `entry`, sorted by address
+    pub fn get_entry_symbols(&self, entry: &Entry) -> Vec<&ElfSymbol> {
+        let start = entry.get_address();
+        let end = start + entry.get_original_size();
+        self.symbols
+            .iter()
+            .filter(|s| s.size > 0 && s.section_name.is_some())
+            .filter(|s| s.address >= start && s.address < end)
+            .collect()
+    }
+}
+
+/// Enum containing ELF cross-check issue types
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum ElfIssueKind {
+    /// Segment size differs from ELF section size
+    SizeMismatch,
+    /// Segment address differs from ELF section address
+    AddressMismatch,
+    /// Segment with a size is missing from ELF sections
+    MissingSection,
+    /// Allocated ELF section with a size is missing from mapfile segments
+    MissingSegment,
+    /// Entry address range is not inside the ELF section of its segment
+    EntryOutOfSection,
+    /// Linker symbol address differs from ELF symbol address
+    SymbolMismatch,
+}
+
+impl ElfIssueKind {
+    /// Returns kind name as used in outputs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ElfIssueKind::SizeMismatch => "size mismatch",
+            ElfIssueKind::AddressMismatch => "address mismatch",
+            ElfIssueKind::MissingSection => "missing section",
+            ElfIssueKind::MissingSegment => "missing segment",
+            ElfIssueKind::EntryOutOfSection => "entry out of section",
+            ElfIssueKind::SymbolMismatch => "symbol mismatch",
+        }
+    }
+}
+
+/// Structure containing an ELF cross-check issue
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ElfIssue {
+    /// Issue type
+    kind: ElfIssueKind,
+    /// Segment, entry or symbol name
+    name: String,
+    /// Size or address (entry address for [EntryOutOfSection](ElfIssueKind::EntryOutOfSection)) from mapfile
+    /// or [None](Option::None) if missing
+    map_value: Option<u64>,
+    /// Size or address (section address for [EntryOutOfSection](ElfIssueKind::EntryOutOfSection)) from ELF
+    /// image or [None](Option::None) if missing
+    elf_value: Option<u64>,
+}
+
+impl ElfIssue {
+    /// Get issue [kind](#structfield.kind)
+    pub fn get_kind(&self) -> ElfIssueKind {
+        self.kind
+    }
+
+    /// Get segment, entry or symbol [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get [map_value](#structfield.map_value) or [None](Option::None)
+    pub fn get_map_value(&self) -> Option<u64> {
+        self.map_value
+    }
+
+    /// Get [elf_value](#structfield.elf_value) or [None](Option::None)
+    pub fn get_elf_value(&self) -> Option<u64> {
+        self.elf_value
+    }
+}
+
+impl Display for ElfIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = |value: Option<u64>| match (self.kind, value) {
+            (_, None) => "missing".to_string(),
+            (ElfIssueKind::SizeMismatch, Some(size))
+            | (ElfIssueKind::MissingSection, Some(size))
+            | (ElfIssueKind::MissingSegment, Some(size)) => format!("{size} bytes"),
+            (_, Some(address)) => format!("{address:#010x}"),
+        };
+
+        write!(
+            f,
+            "{} {}: {} in mapfile, {} in ELF",
+            self.kind.as_str(),
+            self.name,
+            value(self.map_value),
+            value(self.elf_value)
+        )
+    }
+}
+
+/// Structure containing the results of the mapfile and ELF image cross-check
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ElfCheck {
+    /// List of found issues
+    issues: Vec<ElfIssue>,
+}
+
+impl ElfCheck {
+    /// Creates a new [ElfCheck] from `parser` results and ELF `image`
+    ///
+    /// Only [Parser::get_allocated_segments] with a size are checked
+    pub fn new(parser: &Parser, image: &ElfImage) -> Self {
+        let mut check = Self::default();
+
+        let segments = parser
+            .get_allocated_segments()
+            .filter(|s| s.get_size().is_some_and(|size| size > 0))
+            .collect::<Vec<_>>();
+
+        for segment in &segments {
+            match image.get_section(segment.get_name()) {
+                Some(section) => check.check_segment(segment, section),
+                None => check.add_issue(
+                    ElfIssueKind::MissingSection,
+                    segment.get_name(),
+                    segment.get_size(),
+                    None,
+                ),
+            }
+        }
+
+        for section in image
+            .get_sections()
+            .iter()
+            .filter(|s| s.allocated && s.size > 0)
+        {
+            if !segments.iter().any(|s| s.get_name() == section.name) {
+                check.add_issue(
+                    ElfIssueKind::MissingSegment,
+                    &section.name,
+                    None,
+                    Some(section.size),
+                );
+            }
+        }
+
+        for symbol in parser.get_memory_map_symbols() {
+            let mut elf_symbols = image
+                .get_symbols()
+                .iter()
+                .filter(|s| s.name == symbol.get_name())
+                .peekable();
+            // Symbols not referenced by the image (ex: unused PROVIDE) are missing from ELF
+            let elf_symbol = match elf_symbols.peek() {
+                Some(elf_symbol) => *elf_symbol,
+                None => continue,
+            };
+            // Local symbols of different objects can have the same name
+            if !elf_symbols.any(|s| s.address == symbol.get_address()) {
+                check.add_issue(
+                    ElfIssueKind::SymbolMismatch,
+                    symbol.get_name(),
+                    Some(symbol.get_address()),
+                    Some(elf_symbol.address),
+                );
+            }
+        }
+
+        check
+    }
+
+    /// Compares `segment` size, address and entries with ELF `section`
+    fn check_segment(&mut self, segment: &Segment, section: &ElfSection) {
+        if segment.get_size() != Some(section.size) {
+            self.add_issue(
+                ElfIssueKind::SizeMismatch,
+                segment.get_name(),
+                segment.get_size(),
+                Some(section.size),
+            );
+        }
+        if segment.get_address() != Some(section.address) {
+            self.add_issue(
+                ElfIssueKind::AddressMismatch,
+                segment.get_name(),
+                segment.get_address(),
+                Some(section.address),
+            );
+        }
+
+        let section_end = section.address + section.size;
+        for entry in segment.get_entries() {
+            let size = entry.get_original_size();
+            if size > 0
+                && (entry.get_address() < section.address
+                    || entry.get_address() + size > section_end)
+            {
+                self.add_issue(
+                    ElfIssueKind::EntryOutOfSection,
+                    entry.get_name(),
+                    Some(entry.get_address()),
+                    Some(section.address),
+                );
+            }
+        }
+    }
+
+    fn add_issue(
+        &mut self,
+        kind: ElfIssueKind,
+        name: &str,
+        map_value: Option<u64>,
+        elf_value: Option<u64>,
+    ) {
+        self.issues.push(ElfIssue {
+            kind,
+            name: name.to_string(),
+            map_value,
+            elf_value,
+        });
+    }
+
+    /// Get all found [issues](#structfield.issues)
+    pub fn get_issues(&self) -> &[ElfIssue] {
+        &self.issues
+    }
+
+    /// Returns [true] if mapfile and ELF image are consistent
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
@@ -3,6 +3,7 @@
 //! This module contains the code to process and store entry information
 
 use crate::{
+    demangle::demangle,
     excelwriter::ToExcelWriter,
     xmlwriter::{ToXmlWriter, XmlWriter},
 };
@@ -11,6 +12,7 @@ use xml::writer::XmlEvent;
 
 /// Structure containing entry information
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Entry {
     /// Entry name
     name: String,
@@ -82,11 +84,21 @@ impl Entry {
         &self.name
     }
 
+    /// Get demangled entry [name](#structfield.name) or [None](Option::None) if the name is not mangled
+    pub fn get_demangled_name(&self) -> Option<String> {
+        demangle(&self.name)
+    }
+
     /// Get entry [address](#structfield.address)
     pub fn get_address(&self) -> u64 {
         self.address
     }
 
+    /// Set [data](#structfield.data)
+    pub fn set_data(&mut self, data: &str) {
+        self.data = data.to_string();
+    }
+
     /// Get [data](#structfield.data) from where the entry was parsed
     pub fn get_data(&self) -> &str {
         &self.data
@@ -128,13 +140,30 @@ impl<W: Write> ToXmlWriter<W> for Entry {
         let fill_size = self.fill_size.to_string();
         let fill_overlaps = self.fill_overlaps.to_string();
 
-        let entry_element = XmlEvent::start_element("entry")
+        let demangled_name = match writer.get_demangle() {
+            true => self.get_demangled_name(),
+            false => None,
+        };
+
+        let mut entry_element = XmlEvent::start_element("entry")
             .attr("name", self.name.as_str())
             .attr("address", &addr)
             .attr("size", &size)
             .attr("fill_size", &fill_size)
             .attr("fill_overlaps", &fill_overlaps);
 
+        if let Some(object_name) = &self.object_name {
+            entry_element = entry_element.attr("object", object_name);
+        }
+
+        if let Some(library_name) = &self.library_name {
+            entry_element = entry_element.attr("library", library_name);
+        }
+
+        if let Some(demangled_name) = &demangled_name {
+            entry_element = entry_element.attr("demangled", demangled_name);
+        }
+
         writer.start_element(entry_element);
 
         if !writer.get_skip_data() {
@@ -1,7 +1,23 @@
 use log::error;
-use xlsxwriter::{prelude::FormatAlignment, Format, Workbook, Worksheet, XlsxError};
+use xlsxwriter::{
+    chart::ChartType,
+    prelude::{FormatAlignment, FormatColor},
+    worksheet::conditional_format::{ConditionalDataBar, ConditionalFormat},
+    Format, Workbook, Worksheet, XlsxError,
+};
 
-use crate::{entry::Entry, object::Object, segment::Segment};
+use crate::{
+    component::{ComponentRules, Components},
+    diff::{Diff, DiffItem, DiffKind},
+    entry::Entry,
+    grouping::Grouping,
+    object::Object,
+    padding::Padding,
+    region::Region,
+    segment::Segment,
+    symbol::LinkerSymbol,
+    SortKey,
+};
 
 pub trait ToExcelWriter {
     fn to_excel_writer<'a, 'b>(&'a self, writer: &mut ExcelWriter<'b>)
@@ -9,20 +25,66 @@ pub trait ToExcelWriter {
         'a: 'b;
 }
 
+/// Number format of address cells
+///
+/// Excel number formats can not display numbers in base 16, so addresses are written as plain integers (that can
+/// be sorted and used in formulas), followed by a column with their hex representation, see [write_address]
+const ADDRESS_FORMAT: &str = "0";
+
 pub struct ExcelWriter<'a> {
     wb: Option<Workbook>,
+    address_format: Format,
     current_segment: Option<&'a Segment>,
+    demangle: bool,
+    sort_key: SortKey,
+    component_rules: Option<ComponentRules>,
+    padding: bool,
+    grouping: bool,
+    budget: f64,
+    summary_count: u32,
+    region_count: u32,
     segment_count: u32,
     entry_count: u32,
     obj_count: u32,
+    symbol_count: u32,
+    padding_count: u32,
+    gap_count: u32,
+    group_count: u32,
+    category_count: u32,
+    component_count: u32,
 }
 
 impl<'a> ExcelWriter<'a> {
+    fn write_summary_header(ws: &mut Worksheet, format: &Format) -> Result<(), XlsxError> {
+        ws.write_string(0, 0, "Nr", Some(format))?;
+        ws.write_string(0, 1, "Type", Some(format))?;
+        ws.write_string(0, 2, "Name", Some(format))?;
+        ws.write_string(0, 3, "Size", Some(format))?;
+        ws.write_string(0, 4, "Length", Some(format))?;
+        ws.write_string(0, 5, "Free", Some(format))?;
+        ws.write_string(0, 6, "Used %", Some(format))?;
+        Ok(())
+    }
+
+    fn write_region_header(ws: &mut Worksheet, format: &Format) -> Result<(), XlsxError> {
+        ws.write_string(0, 0, "Nr", Some(format))?;
+        ws.write_string(0, 1, "Region", Some(format))?;
+        ws.write_string(0, 2, "Origin", Some(format))?;
+        ws.write_string(0, 3, "Origin (hex)", Some(format))?;
+        ws.write_string(0, 4, "Length", Some(format))?;
+        ws.write_string(0, 5, "Used", Some(format))?;
+        ws.write_string(0, 6, "Attributes", Some(format))?;
+        Ok(())
+    }
+
     fn write_segment_header(ws: &mut Worksheet, format: &Format) -> Result<(), XlsxError> {
         ws.write_string(0, 0, "Nr", Some(format))?;
         ws.write_string(0, 1, "Segment", Some(format))?;
         ws.write_string(0, 2, "Address", Some(format))?;
-        ws.write_string(0, 3, "Size", Some(format))?;
+        ws.write_string(0, 3, "Address (hex)", Some(format))?;
+        ws.write_string(0, 4, "Size", Some(format))?;
+        ws.write_string(0, 5, "Load Address", Some(format))?;
+        ws.write_string(0, 6, "Load Address (hex)", Some(format))?;
         Ok(())
     }
 
@@ -31,7 +93,14 @@ impl<'a> ExcelWriter<'a> {
         ws.write_string(0, 1, "Segment", Some(format))?;
         ws.write_string(0, 2, "Entry", Some(format))?;
         ws.write_string(0, 3, "Address", Some(format))?;
-        ws.write_string(0, 4, "Size", Some(format))?;
+        ws.write_string(0, 4, "Address (hex)", Some(format))?;
+        ws.write_string(0, 5, "End Address", Some(format))?;
+        ws.write_string(0, 6, "End Address (hex)", Some(format))?;
+        ws.write_string(0, 7, "Size", Some(format))?;
+        ws.write_string(0, 8, "Fill", Some(format))?;
+        ws.write_string(0, 9, "Object", Some(format))?;
+        ws.write_string(0, 10, "Library", Some(format))?;
+        ws.write_string(0, 11, "Demangled", Some(format))?;
         Ok(())
     }
 
@@ -43,11 +112,73 @@ impl<'a> ExcelWriter<'a> {
         Ok(())
     }
 
+    fn write_symbol_header(ws: &mut Worksheet, format: &Format) -> Result<(), XlsxError> {
+        ws.write_string(0, 0, "Nr", Some(format))?;
+        ws.write_string(0, 1, "Symbol", Some(format))?;
+        ws.write_string(0, 2, "Address", Some(format))?;
+        ws.write_string(0, 3, "Address (hex)", Some(format))?;
+        ws.write_string(0, 4, "Kind", Some(format))?;
+        ws.write_string(0, 5, "Expression", Some(format))?;
+        ws.write_string(0, 6, "Demangled", Some(format))?;
+        Ok(())
+    }
+
+    fn write_padding_header(ws: &mut Worksheet, format: &Format) -> Result<(), XlsxError> {
+        ws.write_string(0, 0, "Nr", Some(format))?;
+        ws.write_string(0, 1, "Type", Some(format))?;
+        ws.write_string(0, 2, "Name", Some(format))?;
+        ws.write_string(0, 3, "Padding", Some(format))?;
+        Ok(())
+    }
+
+    fn write_gap_header(ws: &mut Worksheet, format: &Format) -> Result<(), XlsxError> {
+        ws.write_string(0, 0, "Nr", Some(format))?;
+        ws.write_string(0, 1, "Segment", Some(format))?;
+        ws.write_string(0, 2, "Entry", Some(format))?;
+        ws.write_string(0, 3, "Next Entry", Some(format))?;
+        ws.write_string(0, 4, "Object", Some(format))?;
+        ws.write_string(0, 5, "Address", Some(format))?;
+        ws.write_string(0, 6, "Address (hex)", Some(format))?;
+        ws.write_string(0, 7, "Size", Some(format))?;
+        Ok(())
+    }
+
+    fn write_group_header(ws: &mut Worksheet, format: &Format) -> Result<(), XlsxError> {
+        ws.write_string(0, 0, "Nr", Some(format))?;
+        ws.write_string(0, 1, "Name", Some(format))?;
+        ws.write_string(0, 2, "Category", Some(format))?;
+        ws.write_string(0, 3, "Size", Some(format))?;
+        ws.write_string(0, 4, "Entries", Some(format))?;
+        ws.write_string(0, 5, "Demangled", Some(format))?;
+        Ok(())
+    }
+
+    fn write_category_header(ws: &mut Worksheet, format: &Format) -> Result<(), XlsxError> {
+        ws.write_string(0, 0, "Nr", Some(format))?;
+        ws.write_string(0, 1, "Category", Some(format))?;
+        ws.write_string(0, 2, "Size", Some(format))?;
+        Ok(())
+    }
+
+    fn write_component_header(ws: &mut Worksheet, format: &Format) -> Result<(), XlsxError> {
+        ws.write_string(0, 0, "Nr", Some(format))?;
+        ws.write_string(0, 1, "Component", Some(format))?;
+        ws.write_string(0, 2, "Size", Some(format))?;
+        ws.write_string(0, 3, "Entries", Some(format))?;
+        Ok(())
+    }
+
     pub fn new(file: &str) -> Result<Self, XlsxError> {
         let wb: Workbook = Workbook::new(file)?;
         let mut header_format = Format::new();
         header_format.set_align(FormatAlignment::Left);
 
+        let mut summary_ws = wb.add_worksheet(Some("Summary"))?;
+        Self::write_summary_header(&mut summary_ws, &header_format)?;
+
+        let mut region_ws = wb.add_worksheet(Some("Regions"))?;
+        Self::write_region_header(&mut region_ws, &header_format)?;
+
         let mut segment_ws = wb.add_worksheet(Some("Segments"))?;
         Self::write_segment_header(&mut segment_ws, &header_format)?;
 
@@ -57,15 +188,210 @@ impl<'a> ExcelWriter<'a> {
         let mut obj_ws = wb.add_worksheet(Some("Objects"))?;
         Self::write_object_header(&mut obj_ws, &header_format)?;
 
+        let mut symbol_ws = wb.add_worksheet(Some("Symbols"))?;
+        Self::write_symbol_header(&mut symbol_ws, &header_format)?;
+
+        let mut address_format = Format::new();
+        address_format.set_num_format(ADDRESS_FORMAT);
+
         Ok(Self {
             wb: Some(wb),
+            address_format,
             current_segment: None,
+            demangle: false,
+            sort_key: SortKey::Name,
+            component_rules: None,
+            padding: false,
+            grouping: false,
+            budget: 1.0,
+            summary_count: 0,
+            region_count: 0,
             segment_count: 0,
             entry_count: 0,
             obj_count: 0,
+            symbol_count: 0,
+            padding_count: 0,
+            gap_count: 0,
+            group_count: 0,
+            category_count: 0,
+            component_count: 0,
         })
     }
 
+    /// Set demangle. If [true], demangled entry and symbol names are written next to mangled ones
+    pub fn set_demangle(&mut self, value: bool) {
+        self.demangle = value;
+    }
+
+    /// Set sort key, used to order objects
+    pub fn set_sort_key(&mut self, value: SortKey) {
+        self.sort_key = value;
+    }
+
+    /// Get sort key
+    pub fn get_sort_key(&self) -> SortKey {
+        self.sort_key
+    }
+
+    /// Set component rules. If set, sizes per component are written into "Components" worksheet
+    pub fn set_component_rules(&mut self, rules: ComponentRules) {
+        self.component_rules = Some(rules);
+    }
+
+    /// Get component rules or [None](Option::None)
+    pub fn get_component_rules(&self) -> Option<&ComponentRules> {
+        self.component_rules.as_ref()
+    }
+
+    /// Set padding. If [true], `*fill*` padding sizes and alignment gaps are written into "Padding" and "Gaps"
+    /// worksheets
+    pub fn set_padding(&mut self, value: bool) {
+        self.padding = value;
+    }
+
+    /// Get padding state
+    pub fn get_padding(&self) -> bool {
+        self.padding
+    }
+
+    /// Set grouping. If [true], sizes grouped by logical symbol and category are written into "Groups" and
+    /// "Categories" worksheets
+    pub fn set_grouping(&mut self, value: bool) {
+        self.grouping = value;
+    }
+
+    /// Get grouping state
+    pub fn get_grouping(&self) -> bool {
+        self.grouping
+    }
+
+    /// Set region usage budget, as fraction of region length (ex: 0.9). Regions using more are highlighted in red
+    pub fn set_budget(&mut self, value: f64) {
+        self.budget = value;
+    }
+
+    /// Writes region usage and allocated segment sizes into "Summary" worksheet, with a bar chart of region usage
+    /// and a pie chart of segment sizes
+    pub fn write_summary(&mut self, regions: &[Region], segments: &[Segment]) {
+        let wb = self.wb.as_ref().unwrap();
+        let mut summary_ws = wb.get_worksheet("Summary").unwrap().unwrap();
+        let mut percent_format = Format::new();
+        percent_format.set_num_format("0.0%");
+
+        let region_row = self.summary_count + 1;
+        for region in regions {
+            let row = self.summary_count + 1;
+            let length = region.get_length();
+            let used = region.get_used_size();
+            summary_ws
+                .write_number(row, 0, self.summary_count as f64, None)
+                .unwrap();
+            summary_ws.write_string(row, 1, "Region", None).unwrap();
+            summary_ws
+                .write_string(row, 2, region.get_name(), None)
+                .unwrap();
+            summary_ws.write_number(row, 3, used as f64, None).unwrap();
+            summary_ws
+                .write_number(row, 4, length as f64, None)
+                .unwrap();
+            summary_ws
+                .write_number(row, 5, length.saturating_sub(used) as f64, None)
+                .unwrap();
+            if length > 0 {
+                summary_ws
+                    .write_number(row, 6, used as f64 / length as f64, Some(&percent_format))
+                    .unwrap();
+            }
+            self.summary_count += 1;
+        }
+
+        let segment_row = self.summary_count + 1;
+        for segment in segments {
+            let size = match segment.get_size() {
+                Some(size) if segment.is_allocated() && size > 0 => size,
+                _ => continue,
+            };
+            let row = self.summary_count + 1;
+            summary_ws
+                .write_number(row, 0, self.summary_count as f64, None)
+                .unwrap();
+            summary_ws.write_string(row, 1, "Segment", None).unwrap();
+            summary_ws
+                .write_string(row, 2, segment.get_name(), None)
+                .unwrap();
+            summary_ws.write_number(row, 3, size as f64, None).unwrap();
+            self.summary_count += 1;
+        }
+
+        if segment_row > region_row {
+            let last_row = segment_row - 1;
+            let mut chart = wb.add_chart(ChartType::BarStacked);
+            chart.add_title("Region usage").unwrap();
+            for (name, col) in [("Used", 3), ("Free", 5)] {
+                let mut series = chart.add_series(None, None).unwrap();
+                series.set_name(name).unwrap();
+                series
+                    .set_categories("Summary", region_row, 2, last_row, 2)
+                    .unwrap();
+                series
+                    .set_values("Summary", region_row, col, last_row, col)
+                    .unwrap();
+            }
+            summary_ws.insert_chart(1, 8, &chart).unwrap();
+        }
+
+        if self.summary_count + 1 > segment_row {
+            let last_row = self.summary_count;
+            let mut chart = wb.add_chart(ChartType::Pie);
+            chart.add_title("Segment usage").unwrap();
+            let mut series = chart.add_series(None, None).unwrap();
+            series
+                .set_categories("Summary", segment_row, 2, last_row, 2)
+                .unwrap();
+            series
+                .set_values("Summary", segment_row, 3, last_row, 3)
+                .unwrap();
+            series.set_labels();
+            summary_ws.insert_chart(17, 8, &chart).unwrap();
+        }
+    }
+
+    pub fn write_region(&mut self, region: &Region) {
+        let mut region_ws = self
+            .wb
+            .as_ref()
+            .unwrap()
+            .get_worksheet("Regions")
+            .unwrap()
+            .unwrap();
+
+        let row = self.region_count + 1;
+        region_ws
+            .write_number(row, 0, self.region_count as f64, None)
+            .unwrap();
+        region_ws
+            .write_string(row, 1, region.get_name(), None)
+            .unwrap();
+        write_address(
+            &mut region_ws,
+            row,
+            2,
+            region.get_origin(),
+            &self.address_format,
+        )
+        .unwrap();
+        region_ws
+            .write_number(row, 4, region.get_length() as f64, None)
+            .unwrap();
+        region_ws
+            .write_number(row, 5, region.get_used_size() as f64, None)
+            .unwrap();
+        if let Some(attributes) = region.get_attributes() {
+            region_ws.write_string(row, 6, attributes, None).unwrap();
+        }
+        self.region_count += 1;
+    }
+
     pub fn write_segment(&mut self, segment: &'a Segment) {
         let mut segment_ws = self
             .wb
@@ -83,12 +409,14 @@ impl<'a> ExcelWriter<'a> {
             .write_string(row, 1, segment.get_name(), None)
             .unwrap();
         if let Some(address) = segment.get_address() {
-            let s_address = format!("{:#016x}", address);
-            segment_ws.write_string(row, 2, &s_address, None).unwrap();
+            write_address(&mut segment_ws, row, 2, address, &self.address_format).unwrap();
             segment_ws
-                .write_number(row, 3, segment.get_size().unwrap() as f64, None)
+                .write_number(row, 4, segment.get_size().unwrap() as f64, None)
                 .unwrap();
         }
+        if let Some(load_address) = segment.get_load_address() {
+            write_address(&mut segment_ws, row, 5, load_address, &self.address_format).unwrap();
+        }
         self.segment_count += 1;
 
         self.current_segment = Some(segment);
@@ -117,11 +445,35 @@ impl<'a> ExcelWriter<'a> {
         entry_ws
             .write_string(row, 2, entries.get_name(), None)
             .unwrap();
-        let s_address = format!("{:#016x}", entries.get_address());
-        entry_ws.write_string(row, 3, &s_address, None).unwrap();
+        let address = entries.get_address();
+        write_address(&mut entry_ws, row, 3, address, &self.address_format).unwrap();
+        write_address(
+            &mut entry_ws,
+            row,
+            5,
+            address.saturating_add(entries.get_size()),
+            &self.address_format,
+        )
+        .unwrap();
         entry_ws
-            .write_number(row, 4, entries.get_size() as f64, None)
+            .write_number(row, 7, entries.get_size() as f64, None)
             .unwrap();
+        entry_ws
+            .write_number(row, 8, entries.get_fill_size() as f64, None)
+            .unwrap();
+        if let Some(object_name) = entries.get_object_name() {
+            entry_ws.write_string(row, 9, object_name, None).unwrap();
+        }
+        if let Some(library_name) = entries.get_library_name() {
+            entry_ws.write_string(row, 10, library_name, None).unwrap();
+        }
+        if self.demangle {
+            if let Some(demangled_name) = entries.get_demangled_name() {
+                entry_ws
+                    .write_string(row, 11, &demangled_name, None)
+                    .unwrap();
+            }
+        }
         self.entry_count += 1;
     }
 
@@ -155,10 +507,454 @@ impl<'a> ExcelWriter<'a> {
             self.obj_count += 1;
         }
     }
+
+    pub fn write_symbol(&mut self, symbol: &LinkerSymbol) {
+        let mut symbol_ws = self
+            .wb
+            .as_ref()
+            .unwrap()
+            .get_worksheet("Symbols")
+            .unwrap()
+            .unwrap();
+
+        let row = self.symbol_count + 1;
+        symbol_ws
+            .write_number(row, 0, self.symbol_count as f64, None)
+            .unwrap();
+        symbol_ws
+            .write_string(row, 1, symbol.get_name(), None)
+            .unwrap();
+        write_address(
+            &mut symbol_ws,
+            row,
+            2,
+            symbol.get_address(),
+            &self.address_format,
+        )
+        .unwrap();
+        symbol_ws
+            .write_string(row, 4, symbol.get_kind().as_str(), None)
+            .unwrap();
+        if let Some(expression) = symbol.get_expression() {
+            symbol_ws.write_string(row, 5, expression, None).unwrap();
+        }
+        if self.demangle {
+            if let Some(demangled_name) = symbol.get_demangled_name() {
+                symbol_ws
+                    .write_string(row, 6, &demangled_name, None)
+                    .unwrap();
+            }
+        }
+        self.symbol_count += 1;
+    }
+
+    /// Writes padding sizes into "Padding" worksheet and alignment gaps into "Gaps" worksheet, adding them if
+    /// missing
+    pub fn write_padding(&mut self, padding: &Padding) {
+        let mut padding_ws = get_or_add_worksheet(
+            self.wb.as_ref().unwrap(),
+            "Padding",
+            Self::write_padding_header,
+        )
+        .unwrap();
+
+        let sizes = [
+            ("Region", padding.get_regions()),
+            ("Segment", padding.get_segments()),
+            ("Object", padding.get_objects()),
+        ];
+
+        for (kind, sizes) in sizes {
+            for (name, size) in sizes {
+                let row = self.padding_count + 1;
+                padding_ws
+                    .write_number(row, 0, self.padding_count as f64, None)
+                    .unwrap();
+                padding_ws.write_string(row, 1, kind, None).unwrap();
+                padding_ws.write_string(row, 2, name, None).unwrap();
+                padding_ws.write_number(row, 3, *size as f64, None).unwrap();
+                self.padding_count += 1;
+            }
+        }
+
+        let mut gap_ws =
+            get_or_add_worksheet(self.wb.as_ref().unwrap(), "Gaps", Self::write_gap_header)
+                .unwrap();
+
+        for gap in padding.get_gaps() {
+            let row = self.gap_count + 1;
+            gap_ws
+                .write_number(row, 0, self.gap_count as f64, None)
+                .unwrap();
+            gap_ws
+                .write_string(row, 1, gap.get_segment_name(), None)
+                .unwrap();
+            gap_ws
+                .write_string(row, 2, gap.get_entry_name(), None)
+                .unwrap();
+            if let Some(next_entry_name) = gap.get_next_entry_name() {
+                gap_ws.write_string(row, 3, next_entry_name, None).unwrap();
+            }
+            if let Some(object_name) = gap.get_object_name() {
+                gap_ws.write_string(row, 4, object_name, None).unwrap();
+            }
+            write_address(&mut gap_ws, row, 5, gap.get_address(), &self.address_format).unwrap();
+            gap_ws
+                .write_number(row, 7, gap.get_size() as f64, None)
+                .unwrap();
+            self.gap_count += 1;
+        }
+    }
+
+    /// Writes group sizes into "Groups" worksheet and category sizes into "Categories" worksheet, adding them if
+    /// missing
+    pub fn write_grouping(&mut self, grouping: &Grouping) {
+        let mut group_ws = get_or_add_worksheet(
+            self.wb.as_ref().unwrap(),
+            "Groups",
+            Self::write_group_header,
+        )
+        .unwrap();
+
+        for group in grouping.get_groups() {
+            let row = self.group_count + 1;
+            group_ws
+                .write_number(row, 0, self.group_count as f64, None)
+                .unwrap();
+            group_ws
+                .write_string(row, 1, group.get_name(), None)
+                .unwrap();
+            group_ws
+                .write_string(row, 2, group.get_category().as_str(), None)
+                .unwrap();
+            group_ws
+                .write_number(row, 3, group.get_size() as f64, None)
+                .unwrap();
+            group_ws
+                .write_number(row, 4, group.get_entry_count() as f64, None)
+                .unwrap();
+            if self.demangle {
+                if let Some(demangled_name) = group.get_demangled_name() {
+                    group_ws
+                        .write_string(row, 5, &demangled_name, None)
+                        .unwrap();
+                }
+            }
+            self.group_count += 1;
+        }
+
+        let mut category_ws = get_or_add_worksheet(
+            self.wb.as_ref().unwrap(),
+            "Categories",
+            Self::write_category_header,
+        )
+        .unwrap();
+
+        for (category, size) in grouping.get_categories() {
+            let row = self.category_count + 1;
+            category_ws
+                .write_number(row, 0, self.category_count as f64, None)
+                .unwrap();
+            category_ws
+                .write_string(row, 1, category.as_str(), None)
+                .unwrap();
+            category_ws
+                .write_number(row, 2, *size as f64, None)
+                .unwrap();
+            self.category_count += 1;
+        }
+    }
+
+    /// Writes component sizes into "Components" worksheet, adding it if missing, with unassigned entries size in
+    /// the last row
+    pub fn write_components(&mut self, components: &Components) {
+        let mut component_ws = get_or_add_worksheet(
+            self.wb.as_ref().unwrap(),
+            "Components",
+            Self::write_component_header,
+        )
+        .unwrap();
+
+        for component in components.get_components() {
+            let row = self.component_count + 1;
+            component_ws
+                .write_number(row, 0, self.component_count as f64, None)
+                .unwrap();
+            component_ws
+                .write_string(row, 1, component.get_name(), None)
+                .unwrap();
+            component_ws
+                .write_number(row, 2, component.get_size() as f64, None)
+                .unwrap();
+            component_ws
+                .write_number(row, 3, component.get_entry_count() as f64, None)
+                .unwrap();
+            self.component_count += 1;
+        }
+
+        let row = self.component_count + 1;
+        component_ws
+            .write_number(row, 0, self.component_count as f64, None)
+            .unwrap();
+        component_ws
+            .write_string(row, 1, "(unassigned)", None)
+            .unwrap();
+        component_ws
+            .write_number(row, 2, components.get_unassigned_size() as f64, None)
+            .unwrap();
+        self.component_count += 1;
+    }
+}
+
+/// Returns format with red background, used for values over budget or growing
+fn red_format() -> Format {
+    let mut format = Format::new();
+    format
+        .set_font_color(FormatColor::Custom(0x9C0006))
+        .set_bg_color(FormatColor::Custom(0xFFC7CE));
+    format
+}
+
+/// Returns format with green background, used for shrinking values
+fn green_format() -> Format {
+    let mut format = Format::new();
+    format
+        .set_font_color(FormatColor::Custom(0x006100))
+        .set_bg_color(FormatColor::Custom(0xC6EFCE));
+    format
+}
+
+/// Returns worksheet `name` of `wb`, adding it with the header row written by `write_header` if missing. Used for
+/// optional worksheets, that are only added when written
+fn get_or_add_worksheet<'w>(
+    wb: &'w Workbook,
+    name: &str,
+    write_header: fn(&mut Worksheet, &Format) -> Result<(), XlsxError>,
+) -> Result<Worksheet<'w>, XlsxError> {
+    if let Some(ws) = wb.get_worksheet(name)? {
+        return Ok(ws);
+    }
+
+    let mut header_format = Format::new();
+    header_format.set_align(FormatAlignment::Left);
+    let mut ws = wb.add_worksheet(Some(name))?;
+    write_header(&mut ws, &header_format)?;
+    Ok(ws)
+}
+
+/// Writes `address` into `col` column as a number, that can be sorted and used in formulas, and into the next
+/// column as a hex string, with the same width as the XML output (ex: `0x00000008000000`)
+///
+/// Cell numbers are [f64], so addresses above 2^53 lose exactness in the number column. The hex column is exact
+fn write_address(
+    ws: &mut Worksheet,
+    row: u32,
+    col: u16,
+    address: u64,
+    format: &Format,
+) -> Result<(), XlsxError> {
+    ws.write_number(row, col, address as f64, Some(format))?;
+    ws.write_string(row, col + 1, &format!("{:#016x}", address), None)
+}
+
+/// Freezes the header row of `ws` and adds autofilter over the header and `rows` rows, up to `last_col` column
+fn format_table(ws: &mut Worksheet, rows: u32, last_col: u16) -> Result<(), XlsxError> {
+    ws.freeze_panes(1, 0);
+    ws.autofilter(0, 0, rows, last_col)
+}
+
+/// Helper functions for [Drop]
+impl<'a> ExcelWriter<'a> {
+    /// Freezes the header row and adds autofilter on every worksheet, data bars on size columns and red
+    /// highlighting of regions over budget. Done once all rows are written
+    fn format_worksheets(&self) -> Result<(), XlsxError> {
+        let wb = self.wb.as_ref().unwrap();
+
+        // Worksheet name, number of rows, last column and size columns
+        let worksheets: [(&str, u32, u16, &[u16]); 11] = [
+            ("Summary", self.summary_count, 6, &[]),
+            ("Regions", self.region_count, 6, &[5]),
+            ("Segments", self.segment_count, 6, &[4]),
+            ("Entries", self.entry_count, 11, &[7]),
+            ("Objects", self.obj_count, 3, &[3]),
+            ("Symbols", self.symbol_count, 6, &[]),
+            ("Padding", self.padding_count, 3, &[3]),
+            ("Gaps", self.gap_count, 7, &[7]),
+            ("Groups", self.group_count, 5, &[3]),
+            ("Categories", self.category_count, 2, &[2]),
+            ("Components", self.component_count, 3, &[2]),
+        ];
+
+        let data_bar = ConditionalFormat::data_bar(&ConditionalDataBar::new());
+        for (name, count, last_col, size_cols) in worksheets {
+            // Optional worksheets are missing if not written
+            let mut ws = match wb.get_worksheet(name)? {
+                Some(ws) => ws,
+                None => continue,
+            };
+            format_table(&mut ws, count, last_col)?;
+            if count == 0 {
+                continue;
+            }
+            for col in size_cols {
+                ws.conditional_format_range(1, *col, count, *col, &data_bar)?;
+            }
+        }
+
+        let over_budget_format = red_format();
+
+        if self.region_count > 0 {
+            let over_budget = ConditionalFormat::formula(
+                &format!("=$F2>$E2*{}", self.budget),
+                &over_budget_format,
+            );
+            wb.get_worksheet("Regions")?
+                .unwrap()
+                .conditional_format_range(1, 5, self.region_count, 5, &over_budget)?;
+        }
+
+        if self.summary_count > 0 {
+            let over_budget =
+                ConditionalFormat::cell_greater_than(self.budget, &over_budget_format);
+            wb.get_worksheet("Summary")?
+                .unwrap()
+                .conditional_format_range(1, 6, self.summary_count, 6, &over_budget)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl<'a> Drop for ExcelWriter<'a> {
     fn drop(&mut self) {
+        self.format_worksheets().unwrap();
         self.wb.take().unwrap().close().unwrap();
     }
 }
+
+/// Writes old size, new size, delta and percent change of `item` starting with `col` column
+fn write_diff_sizes(
+    ws: &mut Worksheet,
+    row: u32,
+    col: u16,
+    item: &DiffItem,
+    percent_format: &Format,
+) -> Result<(), XlsxError> {
+    if let Some(old_size) = item.get_old_size() {
+        ws.write_number(row, col, old_size as f64, None)?;
+    }
+    if let Some(new_size) = item.get_new_size() {
+        ws.write_number(row, col + 1, new_size as f64, None)?;
+    }
+    ws.write_number(row, col + 2, item.get_delta() as f64, None)?;
+    if let Some(percent) = item.get_percent_change() {
+        ws.write_number(row, col + 3, percent / 100.0, Some(percent_format))?;
+    }
+    Ok(())
+}
+
+/// Writes the XLSX diff workbook of `diff` into `file`
+///
+/// Segments, objects, libraries and entries are written into separate worksheets with old size, new size, delta
+/// and percent change. Growth is highlighted in red and shrinkage in green. Added and removed items are also
+/// listed in "Added" and "Removed" worksheets
+pub fn write_diff(file: &str, diff: &Diff) -> Result<(), XlsxError> {
+    let wb = Workbook::new(file)?;
+    let mut header_format = Format::new();
+    header_format.set_align(FormatAlignment::Left);
+    let mut percent_format = Format::new();
+    percent_format.set_num_format("0.0%");
+    let growth = ConditionalFormat::cell_greater_than(0.0, &red_format());
+    let shrinkage = ConditionalFormat::cell_less_than(0.0, &green_format());
+
+    let sheets = [
+        ("Segments", DiffKind::Segment),
+        ("Objects", DiffKind::Object),
+        ("Libraries", DiffKind::Library),
+        ("Entries", DiffKind::Entry),
+    ];
+    for (sheet, kind) in sheets {
+        let mut ws = wb.add_worksheet(Some(sheet))?;
+        ws.write_string(0, 0, "Nr", Some(&header_format))?;
+        let mut col = 1;
+        let names: &[&str] = match kind {
+            DiffKind::Entry => &["Segment", "Entry", "Library", "Object"],
+            _ => &["Name"],
+        };
+        for name in names
+            .iter()
+            .chain(&["Old Size", "New Size", "Delta", "Change %"])
+        {
+            ws.write_string(0, col, name, Some(&header_format))?;
+            col += 1;
+        }
+        let last_col = col - 1;
+        let size_col = names.len() as u16 + 1;
+
+        let items = diff.get_items_of_kind(kind);
+        for (index, item) in items.iter().enumerate() {
+            let row = index as u32 + 1;
+            ws.write_number(row, 0, index as f64, None)?;
+            match kind {
+                DiffKind::Entry => {
+                    if let Some(segment_name) = item.get_segment_name() {
+                        ws.write_string(row, 1, segment_name, None)?;
+                    }
+                    ws.write_string(row, 2, item.get_name(), None)?;
+                    if let Some(library_name) = item.get_library_name() {
+                        ws.write_string(row, 3, library_name, None)?;
+                    }
+                    if let Some(object_name) = item.get_object_name() {
+                        ws.write_string(row, 4, object_name, None)?;
+                    }
+                }
+                _ => ws.write_string(row, 1, item.get_name(), None)?,
+            }
+            write_diff_sizes(&mut ws, row, size_col, item, &percent_format)?;
+        }
+
+        let count = items.len() as u32;
+        format_table(&mut ws, count, last_col)?;
+        if count > 0 {
+            ws.conditional_format_range(1, size_col + 2, count, last_col, &growth)?;
+            ws.conditional_format_range(1, size_col + 2, count, last_col, &shrinkage)?;
+        }
+    }
+
+    let sheets = [
+        ("Added", diff.get_added(), red_format()),
+        ("Removed", diff.get_removed(), green_format()),
+    ];
+    for (sheet, items, format) in sheets {
+        let mut ws = wb.add_worksheet(Some(sheet))?;
+        for (col, name) in ["Nr", "Kind", "Name", "Segment", "Library", "Object", "Size"]
+            .iter()
+            .enumerate()
+        {
+            ws.write_string(0, col as u16, name, Some(&header_format))?;
+        }
+
+        for (index, item) in items.iter().enumerate() {
+            let row = index as u32 + 1;
+            ws.write_number(row, 0, index as f64, None)?;
+            ws.write_string(row, 1, item.get_kind().as_str(), None)?;
+            ws.write_string(row, 2, item.get_name(), None)?;
+            if let Some(segment_name) = item.get_segment_name() {
+                ws.write_string(row, 3, segment_name, None)?;
+            }
+            if let Some(library_name) = item.get_library_name() {
+                ws.write_string(row, 4, library_name, None)?;
+            }
+            if let Some(object_name) = item.get_object_name() {
+                ws.write_string(row, 5, object_name, None)?;
+            }
+            if let Some(size) = item.get_old_size().or(item.get_new_size()) {
+                ws.write_number(row, 6, size as f64, Some(&format))?;
+            }
+        }
+
+        format_table(&mut ws, items.len() as u32, 6)?;
+    }
+
+    wb.close()
+}
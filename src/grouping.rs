@@ -0,0 +1,269 @@
+//! Grouping module
+//!
+//! This module contains the code to group [Entries](Entry) by logical symbol (function/variable) and category,
+//! using the section names generated by `-ffunction-sections`/`-fdata-sections` (ex: `.text.my_func`)
+
+use crate::{
+    demangle::demangle,
+    entry::Entry,
+    segment::Segment,
+    xmlwriter::{ToXmlWriter, XmlWriter},
+    Parser,
+};
+use std::{collections::HashMap, io::Write};
+use xml::writer::XmlEvent;
+
+/// Enum containing source level categories of [Entries](Entry)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum Category {
+    /// Functions (ex: `.text`)
+    Code,
+    /// Read only variables and constants (ex: `.rodata`)
+    ConstData,
+    /// Initialized variables (ex: `.data`)
+    InitData,
+    /// Zero initialized variables (ex: `.bss`, `COMMON`)
+    ZeroData,
+    /// Everything else (ex: `.init_array`, `.ARM.exidx`)
+    Other,
+}
+
+impl Category {
+    /// All categories, in output order
+    pub const ALL: [Category; 5] = [
+        Category::Code,
+        Category::ConstData,
+        Category::InitData,
+        Category::ZeroData,
+        Category::Other,
+    ];
+
+    /// Returns category name as used in outputs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Category::Code => "code",
+            Category::ConstData => "const_data",
+            Category::InitData => "init_data",
+            Category::ZeroData => "zero_data",
+            Category::Other => "other",
+        }
+    }
+}
+
+/// Section names and their category. Longer names must come before the names they start with
+const SECTION_CATEGORIES: [(&str, Category); 18] = [
+    (".text.unlikely", Category::Code),
+    (".text.startup", Category::Code),
+    (".text.exit", Category::Code),
+    (".text.hot", Category::Code),
+    (".text", Category::Code),
+    (".rodata", Category::ConstData),
+    (".srodata", Category::ConstData),
+    (".data.rel.ro.local", Category::ConstData),
+    (".data.rel.ro", Category::ConstData),
+    (".data.rel.local", Category::InitData),
+    (".data.rel", Category::InitData),
+    (".data", Category::InitData),
+    (".sdata", Category::InitData),
+    (".tdata", Category::InitData),
+    (".bss", Category::ZeroData),
+    (".sbss", Category::ZeroData),
+    (".tbss", Category::ZeroData),
+    ("COMMON", Category::ZeroData),
+];
+
+/// Returns the category of section `name` and the symbol name placed after the section name, if any
+fn classify(name: &str) -> Option<(Category, Option<&str>)> {
+    SECTION_CATEGORIES.iter().find_map(|(section, category)| {
+        let rest = name.strip_prefix(section)?;
+        match rest.strip_prefix('.') {
+            Some(symbol) if !symbol.is_empty() => Some((*category, Some(symbol))),
+            _ if rest.is_empty() => Some((*category, None)),
+            _ => None,
+        }
+    })
+}
+
+/// Returns [true] if `symbol` is a merged constants/strings section suffix (ex: `str1.1`, `cst4`)
+fn is_merged_section(symbol: &str) -> bool {
+    ["str", "cst"].iter().any(|prefix| {
+        symbol
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+    })
+}
+
+/// Returns the logical symbol name and category of `entry` placed in `segment`
+///
+/// If the entry name does not contain a symbol name (ex: `.text`, merged strings) the entry name is used.
+/// If the entry name is not a known section, the category is taken from the segment name
+fn get_symbol_and_category<'a>(segment: &Segment, entry: &'a Entry) -> (&'a str, Category) {
+    let name = entry.get_name();
+    match classify(name) {
+        Some((category, Some(symbol))) if !is_merged_section(symbol) => (symbol, category),
+        Some((category, _)) => (name, category),
+        None => match classify(segment.get_name()) {
+            Some((category, _)) => (name, category),
+            None => (name, Category::Other),
+        },
+    }
+}
+
+/// Structure containing the size of a logical symbol, summed over all its [Entries](Entry)
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SymbolGroup {
+    /// Symbol name, or entry name if the entry does not carry a symbol name
+    name: String,
+    /// Symbol category
+    category: Category,
+    /// Sum of entry sizes, without fill
+    size: u64,
+    /// Number of grouped entries
+    entry_count: usize,
+}
+
+impl SymbolGroup {
+    /// Get group [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get demangled group [name](#structfield.name) or [None](Option::None) if the name is not mangled
+    pub fn get_demangled_name(&self) -> Option<String> {
+        demangle(&self.name)
+    }
+
+    /// Get group [category](#structfield.category)
+    pub fn get_category(&self) -> Category {
+        self.category
+    }
+
+    /// Get group [size](#structfield.size)
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    /// Get [entry_count](#structfield.entry_count)
+    pub fn get_entry_count(&self) -> usize {
+        self.entry_count
+    }
+}
+
+/// Structure containing [Entries](Entry) grouped by logical symbol and the size of each [Category]
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Grouping {
+    /// List of all groups, sorted by size (largest first)
+    groups: Vec<SymbolGroup>,
+    /// Pairs of category and size, for all categories, in [Category::ALL] order
+    categories: Vec<(Category, u64)>,
+}
+
+impl Grouping {
+    /// Creates a new [Grouping] from `parser` results
+    ///
+    /// Only entries from [Parser::get_allocated_segments] are grouped
+    pub fn new(parser: &Parser) -> Self {
+        let mut groups: HashMap<(&str, Category), SymbolGroup> = HashMap::new();
+
+        for segment in parser.get_allocated_segments() {
+            for entry in segment.get_entries() {
+                let (name, category) = get_symbol_and_category(segment, entry);
+                let group = groups
+                    .entry((name, category))
+                    .or_insert_with(|| SymbolGroup {
+                        name: name.to_string(),
+                        category,
+                        size: 0,
+                        entry_count: 0,
+                    });
+                group.size += entry.get_original_size();
+                group.entry_count += 1;
+            }
+        }
+
+        let mut groups = groups.into_values().collect::<Vec<_>>();
+        groups.sort_by(|a, b| {
+            b.size
+                .cmp(&a.size)
+                .then_with(|| a.name.cmp(&b.name))
+                .then(a.category.cmp(&b.category))
+        });
+
+        let categories = Category::ALL
+            .iter()
+            .map(|category| {
+                let size = groups
+                    .iter()
+                    .filter(|g| g.category == *category)
+                    .map(|g| g.size)
+                    .sum();
+                (*category, size)
+            })
+            .collect();
+
+        Self { groups, categories }
+    }
+
+    /// Get all groups, largest first
+    pub fn get_groups(&self) -> &[SymbolGroup] {
+        &self.groups
+    }
+
+    /// Get pairs of category and size
+    pub fn get_categories(&self) -> &[(Category, u64)] {
+        &self.categories
+    }
+
+    /// Get size of `category`
+    pub fn get_category_size(&self, category: Category) -> u64 {
+        self.categories
+            .iter()
+            .find(|(c, _)| *c == category)
+            .map_or(0, |(_, size)| *size)
+    }
+}
+
+impl<W: Write> ToXmlWriter<W> for Grouping {
+    fn to_xml_writer(&self, writer: &mut XmlWriter<W>) {
+        writer.start_element(XmlEvent::start_element("grouping"));
+
+        writer.start_element(
+            XmlEvent::start_element("categories").attr("count", &self.categories.len().to_string()),
+        );
+        for (category, size) in &self.categories {
+            writer.start_element(
+                XmlEvent::start_element("category")
+                    .attr("name", category.as_str())
+                    .attr("size", &size.to_string()),
+            );
+            writer.end_element();
+        }
+        writer.end_element();
+
+        writer.start_element(
+            XmlEvent::start_element("groups").attr("count", &self.groups.len().to_string()),
+        );
+        for group in &self.groups {
+            let size = group.size.to_string();
+            let entry_count = group.entry_count.to_string();
+            let demangled_name = match writer.get_demangle() {
+                true => group.get_demangled_name(),
+                false => None,
+            };
+
+            let mut element = XmlEvent::start_element("group")
+                .attr("name", &group.name)
+                .attr("category", group.category.as_str())
+                .attr("size", &size)
+                .attr("entries", &entry_count);
+            if let Some(demangled_name) = &demangled_name {
+                element = element.attr("demangled", demangled_name);
+            }
+            writer.start_element(element);
+            writer.end_element();
+        }
+        writer.end_element();
+
+        writer.end_element();
+    }
+}
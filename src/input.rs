@@ -0,0 +1,246 @@
+//! Input module
+//!
+//! This module contains the code to read mapfiles (or XML files previously written by this tool) from plain files,
+//! standard input and `.gz`, `.xz`, `.zst` or `.zip` compressed files. Compression is detected from the data
+//! itself, so compressed data is also accepted from standard input. `.xz` and `.zst` inputs require the `xz` and
+//! `zstd` features
+
+use std::{
+    fmt::Display,
+    io::{Cursor, Read},
+    path::Path,
+};
+
+/// Path used for standard input
+pub const STDIN_PATH: &str = "-";
+
+/// Enum containing the supported compression formats
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Compression {
+    /// Plain data
+    None,
+    /// gzip (`.gz`)
+    Gzip,
+    /// xz (`.xz`)
+    Xz,
+    /// Zstandard (`.zst`)
+    Zstd,
+    /// Zip archive (`.zip`) containing a single mapfile
+    Zip,
+}
+
+impl Compression {
+    /// Returns compression name as used in outputs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Xz => "xz",
+            Compression::Zstd => "zstd",
+            Compression::Zip => "zip",
+        }
+    }
+
+    /// Returns file extension of the compression format, or [None](Option::None) for plain data
+    pub fn get_extension(&self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gz"),
+            Compression::Xz => Some("xz"),
+            Compression::Zstd => Some("zst"),
+            Compression::Zip => Some("zip"),
+        }
+    }
+
+    /// Returns compression format of `data` from its first bytes (magic number)
+    pub fn detect(data: &[u8]) -> Self {
+        const MAGICS: [(&[u8], Compression); 5] = [
+            (&[0x1f, 0x8b], Compression::Gzip),
+            (&[0xfd, b'7', b'z', b'X', b'Z', 0x00], Compression::Xz),
+            (&[0x28, 0xb5, 0x2f, 0xfd], Compression::Zstd),
+            (&[b'P', b'K', 0x03, 0x04], Compression::Zip),
+            // Empty archive
+            (&[b'P', b'K', 0x05, 0x06], Compression::Zip),
+        ];
+
+        MAGICS
+            .iter()
+            .find(|(magic, _)| data.starts_with(magic))
+            .map(|(_, compression)| *compression)
+            .unwrap_or(Compression::None)
+    }
+}
+
+/// Enum containing the errors that can occur while reading an input
+#[derive(Debug)]
+pub enum InputError {
+    /// File could not be read or data could not be decompressed
+    Io(std::io::Error),
+    /// Zip archive could not be read
+    Zip(zip::result::ZipError),
+    /// Zip archive does not contain a single mapfile
+    ZipContent {
+        /// Names of the archive files
+        names: Vec<String>,
+    },
+    /// Decompressed data is not UTF-8 text
+    NotText,
+    /// Compression format support is not enabled (`xz` or `zstd` feature)
+    Unsupported(Compression),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Io(e) => write!(f, "{e}"),
+            InputError::Zip(e) => write!(f, "Invalid zip archive: {e}"),
+            InputError::ZipContent { names } => write!(
+                f,
+                "Zip archive must contain a single file or a single .map/.xml file, found [{}]",
+                names.join(", ")
+            ),
+            InputError::NotText => write!(f, "Input is not a text file"),
+            InputError::Unsupported(compression) => write!(
+                f,
+                "{} input is not supported, enable '{}' feature",
+                compression.as_str(),
+                compression.as_str()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+impl From<std::io::Error> for InputError {
+    fn from(value: std::io::Error) -> Self {
+        InputError::Io(value)
+    }
+}
+
+impl From<zip::result::ZipError> for InputError {
+    fn from(value: zip::result::ZipError) -> Self {
+        InputError::Zip(value)
+    }
+}
+
+/// Structure containing a decompressed input
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Input {
+    /// Name of the input: path without compression extension, zip archive member name, or `-` for standard input
+    name: String,
+    /// Compression format of the read data
+    compression: Compression,
+    /// Decompressed text
+    text: String,
+}
+
+impl Input {
+    /// Creates a new [Input] named `name` from possibly compressed `data`
+    pub fn parse(name: &str, data: Vec<u8>) -> Result<Self, InputError> {
+        let compression = Compression::detect(&data);
+
+        let mut name = match compression.get_extension() {
+            Some(extension) => Path::new(name)
+                .extension()
+                .filter(|ext| ext.eq_ignore_ascii_case(extension))
+                .map(|_| {
+                    Path::new(name)
+                        .with_extension("")
+                        .to_string_lossy()
+                        .to_string()
+                })
+                .unwrap_or_else(|| name.to_string()),
+            None => name.to_string(),
+        };
+
+        let mut text = String::new();
+        match compression {
+            Compression::None => text = String::from_utf8(data).map_err(|_| InputError::NotText)?,
+            Compression::Gzip => {
+                // Concatenated gzip members (ex: `cat a.gz b.gz`) are read as a single stream
+                flate2::read::MultiGzDecoder::new(data.as_slice()).read_to_string(&mut text)?;
+            }
+            #[cfg(feature = "xz")]
+            Compression::Xz => {
+                xz2::read::XzDecoder::new_multi_decoder(data.as_slice())
+                    .read_to_string(&mut text)?;
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => {
+                zstd::stream::read::Decoder::new(data.as_slice())?.read_to_string(&mut text)?;
+            }
+            #[cfg(not(feature = "xz"))]
+            Compression::Xz => return Err(InputError::Unsupported(compression)),
+            #[cfg(not(feature = "zstd"))]
+            Compression::Zstd => return Err(InputError::Unsupported(compression)),
+            Compression::Zip => {
+                let mut archive = zip::ZipArchive::new(Cursor::new(data))?;
+                let names = archive
+                    .file_names()
+                    .filter(|n| !n.ends_with('/'))
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+
+                let is_mapfile = |name: &&String| {
+                    Path::new(name.as_str()).extension().is_some_and(|ext| {
+                        ext.eq_ignore_ascii_case("map") || ext.eq_ignore_ascii_case("xml")
+                    })
+                };
+                let member = match names.len() {
+                    1 => names[0].clone(),
+                    _ => {
+                        let mapfiles = names.iter().filter(is_mapfile).collect::<Vec<_>>();
+                        match mapfiles.as_slice() {
+                            [member] => member.to_string(),
+                            _ => return Err(InputError::ZipContent { names }),
+                        }
+                    }
+                };
+
+                archive.by_name(&member)?.read_to_string(&mut text)?;
+                name = member;
+            }
+        }
+
+        Ok(Self {
+            name,
+            compression,
+            text,
+        })
+    }
+
+    /// Reads input from file at `path`, or from standard input if `path` is `-`
+    pub fn open(path: &str) -> Result<Self, InputError> {
+        let mut data = vec![];
+        match path == STDIN_PATH {
+            true => std::io::stdin().lock().read_to_end(&mut data)?,
+            false => std::fs::File::open(path)?.read_to_end(&mut data)?,
+        };
+        Self::parse(path, data)
+    }
+
+    /// Get input [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get [compression](#structfield.compression)
+    pub fn get_compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Get decompressed [text](#structfield.text)
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns [true] if the input is an XML file: its name has `.xml` extension or, without extension (ex: standard
+    /// input), its text starts with `<`
+    pub fn is_xml(&self) -> bool {
+        match Path::new(&self.name).extension() {
+            Some(ext) => ext.eq_ignore_ascii_case("xml"),
+            None => self.text.trim_start().starts_with('<'),
+        }
+    }
+}
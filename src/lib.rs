@@ -1,18 +1,42 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
 
+pub mod batch;
+pub mod browser;
+pub mod bundle;
+pub mod component;
+pub mod demangle;
+pub mod diff;
+pub mod elf;
 pub mod entry;
 pub mod excelwriter;
+pub mod grouping;
+pub mod input;
+pub mod lookup;
 pub mod object;
+pub mod padding;
+pub mod query;
+pub mod region;
+pub mod rollup;
 pub mod segment;
+pub mod source;
+pub mod symbol;
+pub mod trend;
+pub mod validation;
+pub mod xmlreader;
 pub mod xmlwriter;
 
+use component::Components;
 use entry::Entry;
 use excelwriter::{ExcelWriter, ToExcelWriter};
-use log::{error, info, warn};
+use grouping::Grouping;
+use log::{debug, error, info, warn};
 use object::Object;
+use padding::Padding;
 use regex::Regex;
+use region::Region;
 use segment::Segment;
+use symbol::{LinkerSymbol, SymbolKind};
 use xml::writer::XmlEvent;
 use xmlwriter::{ToXmlWriter, XmlWriter};
 
@@ -21,6 +45,7 @@ const HEX_REGEX: &str = "0x([[:xdigit:]]+)";
 
 /// Enum containing section types
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Section {
     /// Corresponds to 'Archive member included to satisfy reference by file (symbol)' section
     ArchiveMembers,
@@ -34,27 +59,74 @@ pub enum Section {
     MemoryMap,
 }
 
+/// Enum containing the keys used to order output lists
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub enum SortKey {
+    /// Alphabetical order of names
+    #[default]
+    Name,
+    /// Largest size first
+    Size,
+    /// Lowest address first
+    Address,
+}
+
+impl SortKey {
+    /// Returns key name as used in CLI
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortKey::Name => "name",
+            SortKey::Size => "size",
+            SortKey::Address => "address",
+        }
+    }
+
+    /// Returns the key with `name` or [None](Option::None) if unknown
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "name" => Some(SortKey::Name),
+            "size" => Some(SortKey::Size),
+            "address" => Some(SortKey::Address),
+            _ => None,
+        }
+    }
+}
+
 /// Struct containing parsing results
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parser {
+    /// List of parsed memory configuration regions
+    memory_regions: Vec<Region>,
     /// List of parsed memory map segments
     memory_map_segments: Vec<Segment>,
-    /// Hash Containing name of object as key and corresponding [Object] as value
-    memory_map_objects: HashMap<String, Object>,
+    /// Map containing name of object as key and corresponding [Object] as value, ordered by name
+    memory_map_objects: BTreeMap<String, Object>,
+    /// List of parsed memory map linker symbols
+    memory_map_symbols: Vec<LinkerSymbol>,
 }
 
 impl Parser {
     /// Returns new [Parser]. Used in UT
     pub fn new() -> Self {
         Self {
+            memory_regions: vec![],
             memory_map_segments: vec![],
-            memory_map_objects: HashMap::new(),
+            memory_map_objects: BTreeMap::new(),
+            memory_map_symbols: vec![],
         }
     }
 
     /// Clears structure. Used in UT
     pub fn clear(&mut self) {
+        self.memory_regions.clear();
         self.memory_map_objects.clear();
         self.memory_map_segments.clear();
+        self.memory_map_symbols.clear();
+    }
+
+    /// Returns all stored [Region]'s
+    pub fn get_memory_regions(&self) -> &[Region] {
+        &self.memory_regions
     }
 
     /// Returns all stored [Segment]'s
@@ -62,13 +134,108 @@ impl Parser {
         &self.memory_map_segments
     }
 
-    /// Returns all stored [Object]'s
-    pub fn get_memory_map_objects(&self) -> &HashMap<String, Object> {
+    /// Returns stored [Segment]'s that occupy target memory
+    ///
+    /// Size reports, lookups and checks only take these segments and their entries into account,
+    /// segments that are not allocated (debug information, comments, etc.) are skipped
+    pub fn get_allocated_segments(&self) -> impl Iterator<Item = &Segment> {
+        self.memory_map_segments.iter().filter(|s| s.is_allocated())
+    }
+
+    /// Returns all stored [Object]'s, ordered by name
+    pub fn get_memory_map_objects(&self) -> &BTreeMap<String, Object> {
         &self.memory_map_objects
     }
 
+    /// Returns all stored [Object]'s, ordered by `key`
+    ///
+    /// Objects are ordered by name ([Name](SortKey::Name)), total size, largest first ([Size](SortKey::Size)) or
+    /// lowest address of their entries ([Address](SortKey::Address)). Ties are ordered by name
+    pub fn get_sorted_objects(&self, key: SortKey) -> Vec<&Object> {
+        let mut objects = self.memory_map_objects.values().collect::<Vec<_>>();
+        match key {
+            SortKey::Name => {}
+            SortKey::Size => objects.sort_by_key(|o| std::cmp::Reverse(o.get_total_size())),
+            SortKey::Address => {
+                let mut addresses: HashMap<&str, u64> = HashMap::new();
+                for entry in self.get_allocated_segments().flat_map(|s| s.get_entries()) {
+                    if let Some(object_name) = entry.get_object_name() {
+                        let address = addresses.entry(object_name).or_insert(u64::MAX);
+                        *address = (*address).min(entry.get_address());
+                    }
+                }
+                // Objects without allocated entries are placed last
+                objects.sort_by_key(|o| addresses.get(o.get_name()).copied().unwrap_or(u64::MAX));
+            }
+        }
+        objects
+    }
+
+    /// Returns all stored [LinkerSymbol]'s
+    pub fn get_memory_map_symbols(&self) -> &[LinkerSymbol] {
+        &self.memory_map_symbols
+    }
+
+    /// Returns the first [LinkerSymbol] with given `name` or [None](Option::None) if missing
+    pub fn get_memory_map_symbol(&self, name: &str) -> Option<&LinkerSymbol> {
+        self.memory_map_symbols
+            .iter()
+            .find(|s| s.get_name() == name)
+    }
+
+    /// Returns the distance in bytes between `start` and `end` [LinkerSymbol]'s (ex: `__heap_start` and `__heap_end`)
+    ///
+    /// Returns [None](Option::None) if any of the symbols is missing or `end` is placed before `start`
+    pub fn get_memory_map_symbols_distance(&self, start: &str, end: &str) -> Option<u64> {
+        let start = self.get_memory_map_symbol(start)?.get_address();
+        let end = self.get_memory_map_symbol(end)?.get_address();
+        end.checked_sub(start)
+    }
+
+    /// Adds new [LinkerSymbol]
+    pub fn add_symbol(&mut self, symbol: LinkerSymbol) {
+        self.memory_map_symbols.push(symbol);
+    }
+
+    /// Adds `segment` size to `region` used size if the [Segment] is placed (VMA) or loaded (LMA) in it
+    fn update_region_used_size(region: &mut Region, segment: &Segment) {
+        if region.contains_segment(segment) {
+            region.update_used_size(segment.get_size().unwrap());
+        }
+    }
+
+    /// Adds new [Region]
+    pub fn add_region(&mut self, mut region: Region) {
+        // Account segments that were already added
+        for segment in &self.memory_map_segments {
+            Self::update_region_used_size(&mut region, segment);
+        }
+
+        self.memory_regions.push(region);
+    }
+
+    /// Adds new [Object]. If an object with the same name exists, segment sizes are added to it
+    pub fn add_object(&mut self, object: Object) {
+        match self.memory_map_objects.get_mut(object.get_name()) {
+            Some(obj) => {
+                for name in object.get_all_segments() {
+                    obj.update_segment_size(name, object.get_segment_size(name).unwrap());
+                }
+            }
+            None => {
+                self.memory_map_objects
+                    .insert(object.get_name().to_string(), object);
+            }
+        }
+    }
+
     /// Adds new [Segment]
     pub fn add_segment(&mut self, segment: Segment) {
+        // Update used size of regions where segment is placed or loaded
+        for region in self.memory_regions.iter_mut() {
+            Self::update_region_used_size(region, &segment);
+        }
+
         // For each entry in the parsed segment
         for entry in segment.get_entries() {
             // Get object name
@@ -135,7 +302,7 @@ impl Parser {
         // Parse line by line until regex matches the info
         // It covers the cases when there are other lines we do not use before
         // Usually it should be right after the line containing the name or on the same line
-        while let Some(line) = iter.next() {
+        for line in iter.by_ref() {
             if let Some(cap) = info_regex.captures(line) {
                 let address = u64::from_str_radix(cap.get(1).unwrap().as_str(), 16).unwrap();
                 let size = u64::from_str_radix(cap.get(2).unwrap().as_str(), 16).unwrap();
@@ -159,8 +326,8 @@ impl Parser {
                 entry = Some(tmp);
                 break;
             } else {
-                // skip lines that contain linker information
-                if !line.contains("*(SORT_BY_ALIGNMENT(") {
+                // skip lines that contain linker information or symbols
+                if !line.contains("*(SORT_BY_ALIGNMENT(") && !Self::is_symbol_line(line) {
                     info!("Skipped line while parsing '{}' entry:\n{line}", name);
                 }
             }
@@ -173,7 +340,7 @@ impl Parser {
         }
 
         // Parse line by line until regex matches the fill
-        while let Some(line) = iter.next() {
+        for line in iter {
             if let Some(cap) = fill_regex.captures(line) {
                 let entry = entry.as_mut().unwrap();
 
@@ -199,8 +366,8 @@ impl Parser {
                 entry.set_fill(address, size);
                 break;
             } else {
-                // skip lines that contain linker information
-                if !line.contains("*(SORT_BY_ALIGNMENT(") {
+                // skip lines that contain linker information or symbols
+                if !line.contains("*(SORT_BY_ALIGNMENT(") && !Self::is_symbol_line(line) {
                     info!("Skipped line while parsing '{}' entry:\n{line}", name);
                 }
             }
@@ -211,6 +378,90 @@ impl Parser {
         entry
     }
 
+    /// Returns [true] if `line` contains a symbol, assignment or linker statement placed at an address:
+    /// <spaces> <address> <statement>
+    /// <spaces> [!provide] <statement>
+    fn is_symbol_line(line: &str) -> bool {
+        let trimmed = line.trim_start();
+        trimmed.len() != line.len()
+            && (trimmed.starts_with("0x") || trimmed.starts_with("[!provide]"))
+    }
+
+    /// Parses all [LinkerSymbol]'s found in `data`
+    ///
+    /// Statements that do not define a symbol (`ASSERT`, location counter assignments,
+    /// `PROVIDE` statements that were not referenced) are ignored
+    pub fn parse_symbols(data: &str) -> Vec<LinkerSymbol> {
+        // <spaces> <address> <statement>
+        // Compile regex
+        let line_regex = Regex::new(&format!(r"^\s+{HEX_REGEX}\s+(\S.*?)\s*$")).unwrap();
+
+        // PROVIDE (<name> = <expression>)
+        // Compile regex
+        let provide_regex =
+            Regex::new(r"^(PROVIDE|PROVIDE_HIDDEN|HIDDEN)\s*\(\s*([^\s=,]+)\s*[=,]\s*(.*)\)$")
+                .unwrap();
+
+        // <name> = <expression>
+        // Compile regex
+        let assignment_regex = Regex::new(r"^([^\s=]+)\s*=\s*(.+)$").unwrap();
+
+        let mut symbols = vec![];
+
+        for line in data.lines() {
+            if !Self::is_symbol_line(line) {
+                continue;
+            }
+
+            let cap = match line_regex.captures(line) {
+                Some(cap) => cap,
+                None => {
+                    debug!("Ignored linker statement:\n{line}");
+                    continue;
+                }
+            };
+
+            let address = u64::from_str_radix(cap.get(1).unwrap().as_str(), 16).unwrap();
+            let statement = cap.get(2).unwrap().as_str();
+
+            if let Some(cap) = provide_regex.captures(statement) {
+                let kind = match cap.get(1).unwrap().as_str() {
+                    "HIDDEN" => SymbolKind::Assignment,
+                    _ => SymbolKind::Provide,
+                };
+                let mut symbol = LinkerSymbol::new(cap.get(2).unwrap().as_str(), address, kind);
+                symbol.set_expression(cap.get(3).unwrap().as_str().trim());
+                symbols.push(symbol);
+            } else if statement.starts_with("ASSERT") || statement.starts_with("0x") {
+                // Assertions and data statements (ex: LONG) do not define symbols
+                debug!("Ignored linker statement:\n{line}");
+            } else if let Some(cap) = assignment_regex.captures(statement) {
+                let name = cap.get(1).unwrap().as_str();
+                // Location counter is not a symbol
+                if name == "." {
+                    debug!("Ignored linker statement:\n{line}");
+                    continue;
+                }
+                let mut symbol = LinkerSymbol::new(name, address, SymbolKind::Assignment);
+                symbol.set_expression(cap.get(2).unwrap().as_str());
+                symbols.push(symbol);
+            } else {
+                symbols.push(LinkerSymbol::new(
+                    statement,
+                    address,
+                    SymbolKind::Definition,
+                ));
+            }
+        }
+
+        symbols
+    }
+
+    /// Tries to parse a line containing a [LinkerSymbol]. Returns [None](Option::None) if fails
+    pub fn parse_symbol_info(line: &str) -> Option<LinkerSymbol> {
+        Self::parse_symbols(line).pop()
+    }
+
     /// Tries to parse a string containing a [Segment]. Returns [None](Option::None) if fails
     pub fn parse_segment_info(data: &str) -> Option<Segment> {
         if data.trim().is_empty() {
@@ -221,8 +472,14 @@ impl Parser {
         // Compile name regex
         let name_regex = Regex::new(&format!(r"^{NAME_REGEX}")).unwrap();
 
+        // Segment info line can contain the load address (LMA) when it differs from address (VMA):
+        // <address> <size>
+        // <address> <size> load address <load address>
         // Compile info regex
-        let info_regex = Regex::new(&format!(r"\s+{HEX_REGEX}\s+{HEX_REGEX}")).unwrap();
+        let info_regex = Regex::new(&format!(
+            r"\s+{HEX_REGEX}\s+{HEX_REGEX}(?:\s+load address\s+{HEX_REGEX})?"
+        ))
+        .unwrap();
 
         let mut iter = data.lines().peekable();
         let line = iter.peek().unwrap();
@@ -252,6 +509,10 @@ impl Parser {
 
                 let mut tmp = Segment::new(name);
                 tmp.set_size_and_address(size, address);
+                if let Some(load_address) = cap.get(3) {
+                    let load_address = u64::from_str_radix(load_address.as_str(), 16).unwrap();
+                    tmp.set_load_address(load_address);
+                }
                 segment = Some(tmp);
                 break;
             }
@@ -265,6 +526,34 @@ impl Parser {
         segment
     }
 
+    /// Tries to parse a line containing a [Region]. Returns [None](Option::None) if fails
+    pub fn parse_region_info(line: &str) -> Option<Region> {
+        // <name> <origin> <length> [<attributes>]
+        // Compile regex
+        let region_regex = Regex::new(&format!(
+            r"^{NAME_REGEX}\s+{HEX_REGEX}\s+{HEX_REGEX}(?:\s+([[:alpha:]!]+))?\s*$"
+        ))
+        .unwrap();
+
+        let cap = region_regex.captures(line)?;
+        let name = cap.get(1).unwrap().as_str();
+
+        // Catch-all region, it does not describe real memory
+        if name == "*default*" {
+            return None;
+        }
+
+        let origin = u64::from_str_radix(cap.get(2).unwrap().as_str(), 16).unwrap();
+        let length = u64::from_str_radix(cap.get(3).unwrap().as_str(), 16).unwrap();
+
+        let mut region = Region::new(name, origin, length);
+        if let Some(attributes) = cap.get(4) {
+            region.set_attributes(attributes.as_str());
+        }
+
+        Some(region)
+    }
+
     /// Returns a vector containing pairs of start/end of [Entries](Entry), The first pair (0, n) represents the [Segment] information.
     /// Valid [Entries](Entry) start from index of 1 (if any)
     pub fn split_segment(data: &str) -> Vec<(usize, usize)> {
@@ -352,10 +641,7 @@ impl Parser {
     pub fn parse(data: &str) -> Self {
         let mut current_section = None;
 
-        let mut parser: Parser = Self {
-            memory_map_segments: vec![],
-            memory_map_objects: HashMap::new(),
-        };
+        let mut parser: Parser = Self::new();
 
         for chunk in data.split("\n\n") {
             if chunk.is_empty() {
@@ -383,10 +669,26 @@ impl Parser {
                     Section::MemoryConfiguration => {
                         if let Some(section) = Self::parse_section(first_line) {
                             current_section = Some(section);
+                        } else {
+                            for line in chunk.lines() {
+                                if let Some(region) = Self::parse_region_info(line) {
+                                    parser.add_region(region);
+                                } else {
+                                    info!("Skipped line while parsing regions:\n{line}");
+                                }
+                            }
                         }
                     }
                     Section::MemoryMap => {
-                        if let Some(segment) = Self::parse_segment(chunk) {
+                        // Symbols and assignments can be placed inside or outside segments
+                        Self::parse_symbols(chunk)
+                            .into_iter()
+                            .for_each(|s| parser.add_symbol(s));
+
+                        if Self::is_symbol_line(first_line) {
+                            // Chunk contains only linker statements
+                            continue;
+                        } else if let Some(segment) = Self::parse_segment(chunk) {
                             parser.add_segment(segment);
                         } else if let Some(section) = Self::parse_section(first_line) {
                             current_section = Some(section);
@@ -403,8 +705,31 @@ impl Parser {
     }
 }
 
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Helper functions for [to_xml_writer](#method.to_xml_writer) trait implementation
 impl Parser {
+    fn write_regions<W: Write>(&self, writer: &mut XmlWriter<W>) {
+        let count = self.memory_regions.len();
+        if count > 0 {
+            writer.start_element(
+                XmlEvent::start_element("section").attr("name", "MemoryConfiguration"),
+            );
+            writer.start_element(
+                XmlEvent::start_element("regions").attr("count", &count.to_string()),
+            );
+            self.memory_regions
+                .iter()
+                .for_each(|r| r.to_xml_writer(writer));
+            writer.end_element();
+            writer.end_element();
+        }
+    }
+
     fn write_segments<W: Write>(&self, writer: &mut XmlWriter<W>) {
         let count = self.memory_map_segments.len();
         if count > 0 {
@@ -418,14 +743,27 @@ impl Parser {
         }
     }
 
+    fn write_symbols<W: Write>(&self, writer: &mut XmlWriter<W>) {
+        let count = self.memory_map_symbols.len();
+        if count > 0 {
+            writer.start_element(
+                XmlEvent::start_element("symbols").attr("count", &count.to_string()),
+            );
+            self.memory_map_symbols
+                .iter()
+                .for_each(|s| s.to_xml_writer(writer));
+            writer.end_element();
+        }
+    }
+
     fn write_objects<W: Write>(&self, writer: &mut XmlWriter<W>) {
         let count = self.memory_map_objects.len();
         if count > 0 {
             writer.start_element(
                 XmlEvent::start_element("objects").attr("count", &count.to_string()),
             );
-            self.memory_map_objects
-                .values()
+            self.get_sorted_objects(writer.get_sort_key())
+                .into_iter()
                 .for_each(|o| o.to_xml_writer(writer));
             writer.end_element();
         }
@@ -434,7 +772,7 @@ impl Parser {
 
 impl<W: Write> ToXmlWriter<W> for Parser {
     fn to_xml_writer(&self, writer: &mut XmlWriter<W>) {
-        // We only parse memory map section
+        // We only parse memory configuration and memory map sections
 
         // writer.start_element(XmlEvent::start_element("section").attr("name", "ArchiveMembers"));
         // writer.end_element();
@@ -445,9 +783,7 @@ impl<W: Write> ToXmlWriter<W> for Parser {
         // writer.start_element(XmlEvent::start_element("section").attr("name", "DiscardedInput"));
         // writer.end_element();
 
-        // writer
-        //     .start_element(XmlEvent::start_element("section").attr("name", "MemoryConfiguration"));
-        // writer.end_element();
+        self.write_regions(writer);
 
         writer.start_element(XmlEvent::start_element("section").attr("name", "MemoryMap"));
 
@@ -455,6 +791,23 @@ impl<W: Write> ToXmlWriter<W> for Parser {
 
         self.write_objects(writer);
 
+        self.write_symbols(writer);
+
+        if !self.memory_map_segments.is_empty() {
+            if writer.get_padding() {
+                Padding::new(self).to_xml_writer(writer);
+            }
+            if writer.get_grouping() {
+                Grouping::new(self).to_xml_writer(writer);
+            }
+            let components = writer
+                .get_component_rules()
+                .map(|rules| Components::new(self, rules));
+            if let Some(components) = components {
+                components.to_xml_writer(writer);
+            }
+        }
+
         writer.end_element();
     }
 }
@@ -464,12 +817,37 @@ impl ToExcelWriter for Parser {
     where
         'a: 'b,
     {
+        writer.write_summary(&self.memory_regions, &self.memory_map_segments);
+
+        for region in self.memory_regions.iter() {
+            writer.write_region(region);
+        }
+
         for segment in self.memory_map_segments.iter() {
             segment.to_excel_writer(writer);
         }
 
-        for object in self.memory_map_objects.values() {
+        for object in self.get_sorted_objects(writer.get_sort_key()) {
             writer.write_object(object);
         }
+
+        for symbol in self.memory_map_symbols.iter() {
+            writer.write_symbol(symbol);
+        }
+
+        if writer.get_padding() {
+            writer.write_padding(&Padding::new(self));
+        }
+
+        if writer.get_grouping() {
+            writer.write_grouping(&Grouping::new(self));
+        }
+
+        let components = writer
+            .get_component_rules()
+            .map(|rules| Components::new(self, rules));
+        if let Some(components) = components {
+            writer.write_components(&components);
+        }
     }
 }
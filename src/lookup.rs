@@ -0,0 +1,171 @@
+//! Lookup module
+//!
+//! This module contains the code to find which [Entry] (and [Segment], object, library, symbol) owns an address
+
+use crate::{
+    entry::Entry,
+    segment::Segment,
+    symbol::{LinkerSymbol, SymbolKind},
+    Parser,
+};
+
+/// Structure containing the lookup result for an address
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct AddressInfo<'a> {
+    /// Looked up address
+    address: u64,
+    /// [Segment] containing the [Entry]
+    segment: &'a Segment,
+    /// [Entry] containing the address
+    entry: &'a Entry,
+    /// Nearest [LinkerSymbol] inside the [Entry], placed at or before the address
+    symbol: Option<&'a LinkerSymbol>,
+}
+
+impl<'a> AddressInfo<'a> {
+    /// Get looked up [address](#structfield.address)
+    pub fn get_address(&self) -> u64 {
+        self.address
+    }
+
+    /// Get [segment](#structfield.segment)
+    pub fn get_segment(&self) -> &'a Segment {
+        self.segment
+    }
+
+    /// Get [entry](#structfield.entry)
+    pub fn get_entry(&self) -> &'a Entry {
+        self.entry
+    }
+
+    /// Get [symbol](#structfield.symbol) or [None](Option::None) if there is no symbol for the address
+    pub fn get_symbol(&self) -> Option<&'a LinkerSymbol> {
+        self.symbol
+    }
+
+    /// Get offset of the address from [symbol](#structfield.symbol), or from [entry](#structfield.entry) if there is no symbol
+    pub fn get_offset(&self) -> u64 {
+        match self.symbol {
+            Some(symbol) => self.address - symbol.get_address(),
+            None => self.address - self.entry.get_address(),
+        }
+    }
+}
+
+/// Interval index over all parsed [Entries](Entry) used to look up addresses
+pub struct AddressIndex<'a> {
+    /// Tuples of start address, end address (exclusive), [Segment] and [Entry], sorted by start address
+    intervals: Vec<(u64, u64, &'a Segment, &'a Entry)>,
+    /// Biggest end address of all intervals up to (and including) the same index
+    max_ends: Vec<u64>,
+    /// [LinkerSymbol]'s sorted by address
+    symbols: Vec<&'a LinkerSymbol>,
+}
+
+impl<'a> AddressIndex<'a> {
+    /// Creates a new [AddressIndex] from `parser` results
+    ///
+    /// Only entries with a size, from [Parser::get_allocated_segments] are indexed
+    pub fn new(parser: &'a Parser) -> Self {
+        let mut intervals = vec![];
+
+        for segment in parser.get_allocated_segments() {
+            for entry in segment.get_entries() {
+                let size = entry.get_original_size();
+                if size == 0 {
+                    continue;
+                }
+
+                let start = entry.get_address();
+                intervals.push((start, start.saturating_add(size), segment, entry));
+            }
+        }
+
+        intervals.sort_by_key(|(start, _, _, _)| *start);
+
+        let mut max_end = 0;
+        let max_ends = intervals
+            .iter()
+            .map(|(_, end, _, _)| {
+                max_end = max_end.max(*end);
+                max_end
+            })
+            .collect();
+
+        let mut symbols = parser.get_memory_map_symbols().iter().collect::<Vec<_>>();
+        symbols.sort_by_key(|s| s.get_address());
+
+        Self {
+            intervals,
+            max_ends,
+            symbols,
+        }
+    }
+
+    /// Returns the number of indexed entries
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Returns [true] if there are no indexed entries
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Looks up `address`. Returns [None](Option::None) if no [Entry] contains it
+    ///
+    /// If multiple entries contain the address, the smallest one is returned
+    pub fn lookup(&self, address: u64) -> Option<AddressInfo<'a>> {
+        // Intervals that start after the address can not contain it
+        let count = self
+            .intervals
+            .partition_point(|(start, _, _, _)| *start <= address);
+
+        let mut found: Option<&(u64, u64, &Segment, &Entry)> = None;
+
+        // Walk back while there still are intervals that end after the address
+        for i in (0..count).rev() {
+            if self.max_ends[i] <= address {
+                break;
+            }
+
+            let interval = &self.intervals[i];
+            if interval.1 <= address {
+                continue;
+            }
+
+            let smaller = match found {
+                Some(f) => (interval.1 - interval.0) < (f.1 - f.0),
+                None => true,
+            };
+            if smaller {
+                found = Some(interval);
+            }
+        }
+
+        let (start, _, segment, entry) = *found?;
+
+        // Nearest symbol at or before the address that is still inside the entry
+        // If there are more at the same address, prefer symbols defined by input sections
+        let count = self.symbols.partition_point(|s| s.get_address() <= address);
+        let mut candidates = self.symbols[..count]
+            .iter()
+            .rev()
+            .take_while(|s| s.get_address() >= start)
+            .copied()
+            .peekable();
+        let symbol = candidates.peek().copied().map(|nearest| {
+            candidates
+                .take_while(|s| s.get_address() == nearest.get_address())
+                .find(|s| s.get_kind() == SymbolKind::Definition)
+                .unwrap_or(nearest)
+        });
+
+        Some(AddressInfo {
+            address,
+            segment,
+            entry,
+            symbol,
+        })
+    }
+}
@@ -2,18 +2,19 @@
 //!
 //! This module contains the code to process and store object information
 
-use std::{collections::HashMap, io::Write};
+use std::{collections::BTreeMap, io::Write};
 
 use xml::writer::XmlEvent;
 
 use crate::xmlwriter::{ToXmlWriter, XmlWriter};
 
 /// Structure containing object information
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Object {
     /// Object name
     name: String,
-    /// Hash map that contains segment name as key and sum of all asociated entry sizes as value
-    segment_size: HashMap<String, u64>,
+    /// Map that contains segment name as key and sum of all asociated entry sizes as value, ordered by name
+    segment_size: BTreeMap<String, u64>,
 }
 
 impl Object {
@@ -21,7 +22,7 @@ impl Object {
     pub fn new(name: &str) -> Self {
         Self {
             name: name.to_string(),
-            segment_size: HashMap::new(),
+            segment_size: BTreeMap::new(),
         }
     }
 
@@ -45,7 +46,7 @@ impl Object {
         self.segment_size.get(name).copied()
     }
 
-    /// Returns a list of all stored segments name
+    /// Returns a list of all stored segments name, in alphabetical order
     pub fn get_all_segments(&self) -> Vec<&str> {
         self.segment_size
             .keys()
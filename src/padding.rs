@@ -0,0 +1,227 @@
+//! Padding module
+//!
+//! This module contains the code to analyze `*fill*` padding (alignment waste) of parsed [Entries](Entry)
+
+use crate::{
+    entry::Entry,
+    xmlwriter::{ToXmlWriter, XmlWriter},
+    Parser,
+};
+use std::{collections::HashMap, io::Write};
+use xml::writer::XmlEvent;
+
+/// Structure containing a padding gap found after an [Entry]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PaddingGap {
+    /// Name of the segment containing the gap
+    segment_name: String,
+    /// Name of the entry the padding belongs to
+    entry_name: String,
+    /// Name of the entry placed after the gap, which usually requires the alignment, or [None](Option::None) if last
+    next_entry_name: Option<String>,
+    /// Object name of the entry or [None](Option::None)
+    object_name: Option<String>,
+    /// Start address of the padding
+    address: u64,
+    /// Padding size
+    size: u64,
+}
+
+impl PaddingGap {
+    /// Get [segment_name](#structfield.segment_name)
+    pub fn get_segment_name(&self) -> &str {
+        &self.segment_name
+    }
+
+    /// Get [entry_name](#structfield.entry_name)
+    pub fn get_entry_name(&self) -> &str {
+        &self.entry_name
+    }
+
+    /// Get [next_entry_name](#structfield.next_entry_name) or [None](Option::None)
+    pub fn get_next_entry_name(&self) -> Option<&str> {
+        self.next_entry_name.as_deref()
+    }
+
+    /// Get [object_name](#structfield.object_name) or [None](Option::None)
+    pub fn get_object_name(&self) -> Option<&str> {
+        self.object_name.as_deref()
+    }
+
+    /// Get padding [address](#structfield.address)
+    pub fn get_address(&self) -> u64 {
+        self.address
+    }
+
+    /// Get padding [size](#structfield.size)
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// Structure containing padding totals per segment, object and region and the list of gaps
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Padding {
+    /// Pairs of segment name and padding size, in parsing order
+    segments: Vec<(String, u64)>,
+    /// Pairs of object name and padding size, sorted by size (largest first)
+    objects: Vec<(String, u64)>,
+    /// Pairs of region name and padding size, in parsing order
+    regions: Vec<(String, u64)>,
+    /// List of all gaps, sorted by size (largest first)
+    gaps: Vec<PaddingGap>,
+}
+
+impl Padding {
+    /// Returns the address where `entry` padding starts
+    fn get_fill_address(entry: &Entry) -> u64 {
+        match entry.get_fill_overlaps() {
+            true => entry.get_address(),
+            false => entry.get_address() + entry.get_original_size(),
+        }
+    }
+
+    /// Creates a new [Padding] analysis from `parser` results
+    pub fn new(parser: &Parser) -> Self {
+        let mut padding = Self::default();
+        let mut objects: HashMap<&str, u64> = HashMap::new();
+
+        for segment in parser.get_memory_map_segments() {
+            let entries = segment.get_entries();
+            let mut sum = 0;
+
+            for (i, entry) in entries.iter().enumerate() {
+                let size = entry.get_fill_size();
+                if size == 0 {
+                    continue;
+                }
+
+                sum += size;
+
+                if let Some(object_name) = entry.get_object_name() {
+                    *objects.entry(object_name).or_insert(0) += size;
+                }
+
+                padding.gaps.push(PaddingGap {
+                    segment_name: segment.get_name().to_string(),
+                    entry_name: entry.get_name().to_string(),
+                    next_entry_name: entries.get(i + 1).map(|e| e.get_name().to_string()),
+                    object_name: entry.get_object_name().map(|s| s.to_string()),
+                    address: Self::get_fill_address(entry),
+                    size,
+                });
+            }
+
+            padding.segments.push((segment.get_name().to_string(), sum));
+        }
+
+        for region in parser.get_memory_regions() {
+            let sum = parser
+                .get_memory_map_segments()
+                .iter()
+                .filter(|s| region.contains_segment(s))
+                .flat_map(|s| s.get_entries())
+                .map(|e| e.get_fill_size())
+                .sum();
+            padding.regions.push((region.get_name().to_string(), sum));
+        }
+
+        padding.objects = objects
+            .into_iter()
+            .map(|(name, size)| (name.to_string(), size))
+            .collect();
+        padding
+            .objects
+            .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        padding
+            .gaps
+            .sort_by(|a, b| b.size.cmp(&a.size).then(a.address.cmp(&b.address)));
+
+        padding
+    }
+
+    /// Get sum of all padding
+    pub fn get_total_size(&self) -> u64 {
+        self.segments.iter().map(|(_, size)| size).sum()
+    }
+
+    /// Get pairs of segment name and padding size
+    pub fn get_segments(&self) -> &[(String, u64)] {
+        &self.segments
+    }
+
+    /// Get pairs of object name and padding size, largest first
+    pub fn get_objects(&self) -> &[(String, u64)] {
+        &self.objects
+    }
+
+    /// Get pairs of region name and padding size
+    pub fn get_regions(&self) -> &[(String, u64)] {
+        &self.regions
+    }
+
+    /// Get all gaps, largest first
+    pub fn get_gaps(&self) -> &[PaddingGap] {
+        &self.gaps
+    }
+}
+
+/// Helper functions for [to_xml_writer](#method.to_xml_writer) trait implementation
+impl Padding {
+    fn write_sizes<W: Write>(
+        writer: &mut XmlWriter<W>,
+        list_name: &str,
+        element_name: &str,
+        sizes: &[(String, u64)],
+    ) {
+        writer.start_element(
+            XmlEvent::start_element(list_name).attr("count", &sizes.len().to_string()),
+        );
+        for (name, size) in sizes {
+            writer.start_element(
+                XmlEvent::start_element(element_name)
+                    .attr("name", name)
+                    .attr("size", &size.to_string()),
+            );
+            writer.end_element();
+        }
+        writer.end_element();
+    }
+}
+
+impl<W: Write> ToXmlWriter<W> for Padding {
+    fn to_xml_writer(&self, writer: &mut XmlWriter<W>) {
+        writer.start_element(
+            XmlEvent::start_element("padding").attr("size", &self.get_total_size().to_string()),
+        );
+
+        Self::write_sizes(writer, "regions", "region", &self.regions);
+        Self::write_sizes(writer, "segments", "segment", &self.segments);
+        Self::write_sizes(writer, "objects", "object", &self.objects);
+
+        writer.start_element(
+            XmlEvent::start_element("gaps").attr("count", &self.gaps.len().to_string()),
+        );
+        for gap in &self.gaps {
+            let address = format!("{:#016x}", gap.address);
+            let size = gap.size.to_string();
+            let mut element = XmlEvent::start_element("gap")
+                .attr("segment", &gap.segment_name)
+                .attr("entry", &gap.entry_name)
+                .attr("address", &address)
+                .attr("size", &size);
+            if let Some(next_entry_name) = &gap.next_entry_name {
+                element = element.attr("next_entry", next_entry_name);
+            }
+            if let Some(object_name) = &gap.object_name {
+                element = element.attr("object", object_name);
+            }
+            writer.start_element(element);
+            writer.end_element();
+        }
+        writer.end_element();
+
+        writer.end_element();
+    }
+}
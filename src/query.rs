@@ -0,0 +1,901 @@
+//! Query module
+//!
+//! This module contains a small query language over [Parser] results, to list [Segments](crate::segment::Segment),
+//! [Entries](crate::entry::Entry), [Objects](crate::object::Object) and libraries matching a filter:
+//!
+//! ```text
+//! entries where segment =~ "^\.text" and size > 1024 and library = "libc.a" order by size desc limit 20
+//! ```
+//!
+//! A query has the `SOURCE [where EXPRESSION] [order by FIELD [asc|desc]] [limit COUNT]` format, where `SOURCE` is
+//! one of `segments`, `entries`, `objects` or `libraries`. Expressions compare a field with a literal (`=`, `!=`,
+//! `<`, `<=`, `>`, `>=`, or `=~`, `!~` for regular expressions) and are combined with `and`, `or`, `not` and
+//! parentheses. Number literals are decimal or hexadecimal (`0x` prefix), text literals are double quoted (`\"`
+//! and `\\` are the only escapes, other backslashes are kept for regular expressions). Keywords are case insensitive
+
+use crate::Parser;
+use regex::Regex;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    io::Write,
+};
+
+/// Enum containing the queried item types
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum QuerySource {
+    /// Mapfile segments
+    Segments,
+    /// Entries of all segments
+    Entries,
+    /// Objects, with the library of their entries
+    Objects,
+    /// Libraries, from entry library names
+    Libraries,
+}
+
+impl QuerySource {
+    /// Returns source name as used in queries
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuerySource::Segments => "segments",
+            QuerySource::Entries => "entries",
+            QuerySource::Objects => "objects",
+            QuerySource::Libraries => "libraries",
+        }
+    }
+
+    /// Returns [QuerySource] matching `name` or [None](Option::None) if unknown
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "segments" => Some(QuerySource::Segments),
+            "entries" => Some(QuerySource::Entries),
+            "objects" => Some(QuerySource::Objects),
+            "libraries" => Some(QuerySource::Libraries),
+            _ => None,
+        }
+    }
+
+    /// Returns source fields (columns), in output order
+    pub fn get_fields(&self) -> &'static [(&'static str, FieldKind)] {
+        match self {
+            QuerySource::Segments => &[
+                ("name", FieldKind::Text),
+                ("address", FieldKind::Address),
+                ("load_address", FieldKind::Address),
+                ("size", FieldKind::Number),
+                ("entries", FieldKind::Number),
+            ],
+            QuerySource::Entries => &[
+                ("name", FieldKind::Text),
+                ("segment", FieldKind::Text),
+                ("address", FieldKind::Address),
+                ("size", FieldKind::Number),
+                ("fill", FieldKind::Number),
+                ("object", FieldKind::Text),
+                ("library", FieldKind::Text),
+            ],
+            QuerySource::Objects => &[
+                ("name", FieldKind::Text),
+                ("library", FieldKind::Text),
+                ("size", FieldKind::Number),
+                ("segments", FieldKind::Number),
+            ],
+            QuerySource::Libraries => &[
+                ("name", FieldKind::Text),
+                ("size", FieldKind::Number),
+                ("objects", FieldKind::Number),
+                ("entries", FieldKind::Number),
+            ],
+        }
+    }
+
+    /// Returns index and kind of field `name` or [None](Option::None) if unknown
+    fn get_field(&self, name: &str) -> Option<(usize, FieldKind)> {
+        self.get_fields()
+            .iter()
+            .position(|(field, _)| *field == name)
+            .map(|index| (index, self.get_fields()[index].1))
+    }
+}
+
+/// Enum containing the field value types
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum FieldKind {
+    /// Name
+    Text,
+    /// Size or count
+    Number,
+    /// Address, printed in hexadecimal in tables
+    Address,
+}
+
+/// Enum containing a field value. Missing values (ex: entry without library) are lower than all other values
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub enum Value {
+    /// Missing value
+    Null,
+    /// Number or address
+    Number(u64),
+    /// Text
+    Text(String),
+}
+
+impl From<Option<&str>> for Value {
+    fn from(value: Option<&str>) -> Self {
+        match value {
+            Some(text) => Value::Text(text.to_string()),
+            None => Value::Null,
+        }
+    }
+}
+
+impl From<Option<u64>> for Value {
+    fn from(value: Option<u64>) -> Self {
+        match value {
+            Some(number) => Value::Number(number),
+            None => Value::Null,
+        }
+    }
+}
+
+/// Enum containing the comparison operators
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum CompareOp {
+    /// `=`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<`
+    Less,
+    /// `<=`
+    LessEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterEqual,
+    /// `=~`, regular expression matches
+    Match,
+    /// `!~`, regular expression does not match
+    NotMatch,
+}
+
+impl CompareOp {
+    /// Returns operator as used in queries
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompareOp::Equal => "=",
+            CompareOp::NotEqual => "!=",
+            CompareOp::Less => "<",
+            CompareOp::LessEqual => "<=",
+            CompareOp::Greater => ">",
+            CompareOp::GreaterEqual => ">=",
+            CompareOp::Match => "=~",
+            CompareOp::NotMatch => "!~",
+        }
+    }
+
+    /// Returns [CompareOp] matching `name` or [None](Option::None) if unknown
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "=" => Some(CompareOp::Equal),
+            "!=" => Some(CompareOp::NotEqual),
+            "<" => Some(CompareOp::Less),
+            "<=" => Some(CompareOp::LessEqual),
+            ">" => Some(CompareOp::Greater),
+            ">=" => Some(CompareOp::GreaterEqual),
+            "=~" => Some(CompareOp::Match),
+            "!~" => Some(CompareOp::NotMatch),
+            _ => None,
+        }
+    }
+}
+
+/// Enum containing the errors that can occur while parsing a query
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum QueryError {
+    /// Query does not follow the grammar
+    Syntax {
+        /// Character position, starting with 0
+        position: usize,
+        /// What was expected
+        message: String,
+    },
+    /// Unknown source
+    UnknownSource(String),
+    /// Unknown field of source
+    UnknownField {
+        /// Source name
+        source: String,
+        /// Field name
+        field: String,
+    },
+    /// Literal type does not match field type, or regular expression used on a number field
+    TypeMismatch {
+        /// Field name
+        field: String,
+        /// Operator
+        op: String,
+    },
+    /// Invalid regular expression
+    InvalidRegex {
+        /// Pattern text
+        pattern: String,
+        /// Regex error message
+        message: String,
+    },
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryError::Syntax { position, message } => {
+                write!(f, "Invalid query at position {position}: {message}")
+            }
+            QueryError::UnknownSource(source) => write!(
+                f,
+                "Unknown query source '{source}', expected segments, entries, objects or libraries"
+            ),
+            QueryError::UnknownField { source, field } => {
+                write!(f, "Unknown field '{field}' of {source}")
+            }
+            QueryError::TypeMismatch { field, op } => {
+                write!(f, "Invalid value type for '{field} {op}'")
+            }
+            QueryError::InvalidRegex { pattern, message } => {
+                write!(f, "Invalid regular expression '{pattern}': {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// Enum containing the query tokens
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Token {
+    /// Keyword, source or field name
+    Identifier(String),
+    /// Number literal
+    Number(u64),
+    /// Text literal
+    Text(String),
+    /// Comparison operator
+    Operator(CompareOp),
+    /// `(`
+    OpenParen,
+    /// `)`
+    CloseParen,
+}
+
+/// Splits `text` into tokens and their character positions
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut index = 0;
+
+    while index < chars.len() {
+        let start = index;
+        let c = chars[index];
+
+        let token = match c {
+            _ if c.is_whitespace() => {
+                index += 1;
+                continue;
+            }
+            '(' => {
+                index += 1;
+                Token::OpenParen
+            }
+            ')' => {
+                index += 1;
+                Token::CloseParen
+            }
+            '"' => {
+                let mut value = String::new();
+                index += 1;
+                loop {
+                    match chars.get(index) {
+                        None => {
+                            return Err(QueryError::Syntax {
+                                position: start,
+                                message: "unterminated text literal".to_string(),
+                            })
+                        }
+                        Some('"') => break,
+                        Some('\\') if matches!(chars.get(index + 1), Some('"' | '\\')) => {
+                            value.push(chars[index + 1]);
+                            index += 1;
+                        }
+                        Some(c) => value.push(*c),
+                    }
+                    index += 1;
+                }
+                index += 1;
+                Token::Text(value)
+            }
+            '=' | '!' | '<' | '>' => {
+                let two = chars[index..chars.len().min(index + 2)]
+                    .iter()
+                    .collect::<String>();
+                let (op, length) = match CompareOp::from_name(&two) {
+                    Some(op) => (op, 2),
+                    None => match CompareOp::from_name(&c.to_string()) {
+                        Some(op) => (op, 1),
+                        None => {
+                            return Err(QueryError::Syntax {
+                                position: start,
+                                message: format!("unknown operator '{two}'"),
+                            })
+                        }
+                    },
+                };
+                index += length;
+                Token::Operator(op)
+            }
+            _ if c.is_ascii_alphanumeric() || c == '_' => {
+                while index < chars.len()
+                    && (chars[index].is_ascii_alphanumeric() || chars[index] == '_')
+                {
+                    index += 1;
+                }
+                let word = chars[start..index].iter().collect::<String>();
+
+                match c.is_ascii_digit() {
+                    true => {
+                        let value =
+                            match word.strip_prefix("0x").or_else(|| word.strip_prefix("0X")) {
+                                Some(hex) => u64::from_str_radix(hex, 16),
+                                None => word.parse::<u64>(),
+                            };
+                        Token::Number(value.map_err(|_| QueryError::Syntax {
+                            position: start,
+                            message: format!("invalid number '{word}'"),
+                        })?)
+                    }
+                    false => Token::Identifier(word),
+                }
+            }
+            _ => {
+                return Err(QueryError::Syntax {
+                    position: start,
+                    message: format!("unexpected character '{c}'"),
+                })
+            }
+        };
+
+        tokens.push((start, token));
+    }
+
+    Ok(tokens)
+}
+
+/// Enum containing a filter expression
+#[derive(Debug, Clone)]
+pub enum Expression {
+    /// Field compared with a literal
+    Compare {
+        /// Field index in source fields
+        field: usize,
+        /// Comparison operator
+        op: CompareOp,
+        /// Literal value
+        value: Value,
+        /// Compiled literal for `=~` and `!~` operators
+        regex: Option<Regex>,
+    },
+    /// Both expressions are true
+    And(Box<Expression>, Box<Expression>),
+    /// Any expression is true
+    Or(Box<Expression>, Box<Expression>),
+    /// Expression is false
+    Not(Box<Expression>),
+}
+
+impl Expression {
+    /// Returns [true] if `row` matches the expression
+    ///
+    /// Missing values only match `!=` and `!~`
+    pub fn is_match(&self, row: &[Value]) -> bool {
+        match self {
+            Expression::Compare {
+                field,
+                op,
+                value,
+                regex,
+            } => {
+                let field = &row[*field];
+                match (op, field) {
+                    (CompareOp::NotEqual | CompareOp::NotMatch, Value::Null) => true,
+                    (_, Value::Null) => false,
+                    (CompareOp::Equal, _) => field == value,
+                    (CompareOp::NotEqual, _) => field != value,
+                    (CompareOp::Less, _) => field < value,
+                    (CompareOp::LessEqual, _) => field <= value,
+                    (CompareOp::Greater, _) => field > value,
+                    (CompareOp::GreaterEqual, _) => field >= value,
+                    (CompareOp::Match, Value::Text(text)) => regex.as_ref().unwrap().is_match(text),
+                    (CompareOp::NotMatch, Value::Text(text)) => {
+                        !regex.as_ref().unwrap().is_match(text)
+                    }
+                    // Checked while parsing
+                    _ => false,
+                }
+            }
+            Expression::And(left, right) => left.is_match(row) && right.is_match(row),
+            Expression::Or(left, right) => left.is_match(row) || right.is_match(row),
+            Expression::Not(expression) => !expression.is_match(row),
+        }
+    }
+}
+
+/// Recursive descent parser of query tokens
+struct QueryParser {
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+    /// Query length, used as error position at end of query
+    length: usize,
+    source: QuerySource,
+}
+
+impl QueryParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(_, token)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .map(|(position, _)| *position)
+            .unwrap_or(self.length)
+    }
+
+    fn error(&self, message: &str) -> QueryError {
+        QueryError::Syntax {
+            position: self.position(),
+            message: match self.peek() {
+                Some(_) => message.to_string(),
+                None => format!("{message}, found end of query"),
+            },
+        }
+    }
+
+    /// Returns [true] and moves to next token if current token is `keyword`
+    fn accept_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Identifier(word)) if word.eq_ignore_ascii_case(keyword) => {
+                self.index += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), QueryError> {
+        match self.accept_keyword(keyword) {
+            true => Ok(()),
+            false => Err(self.error(&format!("expected '{keyword}'"))),
+        }
+    }
+
+    /// Parses a field name, returning its index and kind
+    fn field(&mut self) -> Result<(usize, FieldKind, String), QueryError> {
+        let name = match self.peek() {
+            Some(Token::Identifier(name)) => name.to_lowercase(),
+            _ => return Err(self.error("expected field name")),
+        };
+        let (index, kind) =
+            self.source
+                .get_field(&name)
+                .ok_or_else(|| QueryError::UnknownField {
+                    source: self.source.as_str().to_string(),
+                    field: name.clone(),
+                })?;
+        self.index += 1;
+        Ok((index, kind, name))
+    }
+
+    fn or_expression(&mut self) -> Result<Expression, QueryError> {
+        let mut expression = self.and_expression()?;
+        while self.accept_keyword("or") {
+            expression = Expression::Or(Box::new(expression), Box::new(self.and_expression()?));
+        }
+        Ok(expression)
+    }
+
+    fn and_expression(&mut self) -> Result<Expression, QueryError> {
+        let mut expression = self.unary_expression()?;
+        while self.accept_keyword("and") {
+            expression = Expression::And(Box::new(expression), Box::new(self.unary_expression()?));
+        }
+        Ok(expression)
+    }
+
+    fn unary_expression(&mut self) -> Result<Expression, QueryError> {
+        if self.accept_keyword("not") {
+            return Ok(Expression::Not(Box::new(self.unary_expression()?)));
+        }
+
+        if self.peek() == Some(&Token::OpenParen) {
+            self.index += 1;
+            let expression = self.or_expression()?;
+            if self.peek() != Some(&Token::CloseParen) {
+                return Err(self.error("expected ')'"));
+            }
+            self.index += 1;
+            return Ok(expression);
+        }
+
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expression, QueryError> {
+        let (field, kind, name) = self.field()?;
+
+        let op = match self.peek() {
+            Some(Token::Operator(op)) => *op,
+            _ => return Err(self.error("expected comparison operator")),
+        };
+        self.index += 1;
+
+        let value = match self.peek() {
+            Some(Token::Number(number)) => Value::Number(*number),
+            Some(Token::Text(text)) => Value::Text(text.clone()),
+            _ => return Err(self.error("expected number or text literal")),
+        };
+        self.index += 1;
+
+        let is_regex = matches!(op, CompareOp::Match | CompareOp::NotMatch);
+        let is_valid = match (&value, kind) {
+            (Value::Text(_), FieldKind::Text) => true,
+            (Value::Number(_), FieldKind::Number | FieldKind::Address) => !is_regex,
+            _ => false,
+        };
+        if !is_valid {
+            return Err(QueryError::TypeMismatch {
+                field: name,
+                op: op.as_str().to_string(),
+            });
+        }
+
+        let regex = match (is_regex, &value) {
+            (true, Value::Text(pattern)) => {
+                Some(Regex::new(pattern).map_err(|e| QueryError::InvalidRegex {
+                    pattern: pattern.clone(),
+                    message: e.to_string(),
+                })?)
+            }
+            _ => None,
+        };
+
+        Ok(Expression::Compare {
+            field,
+            op,
+            value,
+            regex,
+        })
+    }
+}
+
+/// Structure containing a parsed query
+#[derive(Debug, Clone)]
+pub struct Query {
+    /// Queried items
+    source: QuerySource,
+    /// Filter or [None](Option::None) to select all items
+    filter: Option<Expression>,
+    /// Field index used for sorting and [true] if descending, or [None](Option::None) to keep mapfile order
+    order: Option<(usize, bool)>,
+    /// Maximum number of rows or [None](Option::None) if not limited
+    limit: Option<usize>,
+}
+
+impl Query {
+    /// Parses query `text`
+    pub fn parse(text: &str) -> Result<Self, QueryError> {
+        let tokens = tokenize(text)?;
+        let source = match tokens.first() {
+            Some((_, Token::Identifier(name))) => QuerySource::from_name(&name.to_lowercase())
+                .ok_or_else(|| QueryError::UnknownSource(name.clone()))?,
+            _ => {
+                return Err(QueryError::Syntax {
+                    position: 0,
+                    message: "expected segments, entries, objects or libraries".to_string(),
+                })
+            }
+        };
+
+        let mut parser = QueryParser {
+            tokens,
+            index: 1,
+            length: text.chars().count(),
+            source,
+        };
+
+        let filter = match parser.accept_keyword("where") {
+            true => Some(parser.or_expression()?),
+            false => None,
+        };
+
+        let order = match parser.accept_keyword("order") {
+            true => {
+                parser.expect_keyword("by")?;
+                let (field, _, _) = parser.field()?;
+                let descending = match parser.accept_keyword("desc") {
+                    true => true,
+                    false => {
+                        parser.accept_keyword("asc");
+                        false
+                    }
+                };
+                Some((field, descending))
+            }
+            false => None,
+        };
+
+        let limit = match parser.accept_keyword("limit") {
+            true => match parser.peek() {
+                Some(Token::Number(count)) => {
+                    let count = *count as usize;
+                    parser.index += 1;
+                    Some(count)
+                }
+                _ => return Err(parser.error("expected row count")),
+            },
+            false => None,
+        };
+
+        if parser.peek().is_some() {
+            return Err(parser.error("expected 'where', 'order by', 'limit' or end of query"));
+        }
+
+        Ok(Self {
+            source,
+            filter,
+            order,
+            limit,
+        })
+    }
+
+    /// Get query [source](#structfield.source)
+    pub fn get_source(&self) -> QuerySource {
+        self.source
+    }
+
+    /// Get [limit](#structfield.limit) or [None](Option::None)
+    pub fn get_limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Runs the query on `parser` results
+    pub fn run(&self, parser: &Parser) -> QueryResult {
+        let mut rows = Self::collect_rows(self.source, parser);
+
+        if let Some(filter) = &self.filter {
+            rows.retain(|row| filter.is_match(row));
+        }
+        if let Some((field, descending)) = self.order {
+            // Stable sort keeps mapfile order for equal values
+            match descending {
+                true => rows.sort_by(|a, b| b[field].cmp(&a[field])),
+                false => rows.sort_by(|a, b| a[field].cmp(&b[field])),
+            }
+        }
+        if let Some(limit) = self.limit {
+            rows.truncate(limit);
+        }
+
+        QueryResult {
+            source: self.source,
+            rows,
+        }
+    }
+
+    /// Returns the rows of all `source` items, in mapfile order (objects and libraries by name)
+    fn collect_rows(source: QuerySource, parser: &Parser) -> Vec<Vec<Value>> {
+        let segments = parser.get_memory_map_segments();
+
+        match source {
+            QuerySource::Segments => segments
+                .iter()
+                .map(|segment| {
+                    vec![
+                        Value::Text(segment.get_name().to_string()),
+                        segment.get_address().into(),
+                        segment.get_load_address().into(),
+                        segment.get_size().into(),
+                        Value::Number(segment.get_entries().len() as u64),
+                    ]
+                })
+                .collect(),
+            QuerySource::Entries => segments
+                .iter()
+                .flat_map(|segment| {
+                    segment.get_entries().iter().map(move |entry| {
+                        vec![
+                            Value::Text(entry.get_name().to_string()),
+                            Value::Text(segment.get_name().to_string()),
+                            Value::Number(entry.get_address()),
+                            Value::Number(entry.get_size()),
+                            Value::Number(entry.get_fill_size()),
+                            entry.get_object_name().into(),
+                            entry.get_library_name().into(),
+                        ]
+                    })
+                })
+                .collect(),
+            QuerySource::Objects => {
+                let mut libraries: BTreeMap<&str, &str> = BTreeMap::new();
+                for entry in segments.iter().flat_map(|s| s.get_entries()) {
+                    if let (Some(object_name), Some(library_name)) =
+                        (entry.get_object_name(), entry.get_library_name())
+                    {
+                        libraries.entry(object_name).or_insert(library_name);
+                    }
+                }
+
+                parser
+                    .get_memory_map_objects()
+                    .values()
+                    .map(|object| {
+                        vec![
+                            Value::Text(object.get_name().to_string()),
+                            libraries.get(object.get_name()).copied().into(),
+                            Value::Number(object.get_total_size()),
+                            Value::Number(object.get_all_segments().len() as u64),
+                        ]
+                    })
+                    .collect()
+            }
+            QuerySource::Libraries => {
+                let mut libraries: BTreeMap<&str, (u64, BTreeSet<&str>, u64)> = BTreeMap::new();
+                for entry in segments.iter().flat_map(|s| s.get_entries()) {
+                    if let Some(library_name) = entry.get_library_name() {
+                        let (size, objects, entries) = libraries.entry(library_name).or_default();
+                        *size += entry.get_size();
+                        objects.extend(entry.get_object_name());
+                        *entries += 1;
+                    }
+                }
+
+                libraries
+                    .into_iter()
+                    .map(|(name, (size, objects, entries))| {
+                        vec![
+                            Value::Text(name.to_string()),
+                            Value::Number(size),
+                            Value::Number(objects.len() as u64),
+                            Value::Number(entries),
+                        ]
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Structure containing the rows selected by a query
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct QueryResult {
+    /// Queried items
+    source: QuerySource,
+    /// Field values of each item, in [QuerySource::get_fields] order
+    rows: Vec<Vec<Value>>,
+}
+
+impl QueryResult {
+    /// Get column names
+    pub fn get_columns(&self) -> Vec<&'static str> {
+        self.source
+            .get_fields()
+            .iter()
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    /// Get [rows](#structfield.rows)
+    pub fn get_rows(&self) -> &[Vec<Value>] {
+        &self.rows
+    }
+
+    /// Writes rows as a text table with header, addresses in hexadecimal and missing values as `-`
+    pub fn write_table<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let fields = self.source.get_fields();
+        let cells = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(fields)
+                    .map(|(value, (_, kind))| match (value, kind) {
+                        (Value::Null, _) => "-".to_string(),
+                        (Value::Number(number), FieldKind::Address) => format!("{number:#010x}"),
+                        (Value::Number(number), _) => number.to_string(),
+                        (Value::Text(text), _) => text.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let widths = fields
+            .iter()
+            .enumerate()
+            .map(|(index, (name, _))| {
+                cells
+                    .iter()
+                    .map(|row| row[index].chars().count())
+                    .chain([name.len()])
+                    .max()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let format_line = |values: Vec<&str>| {
+            values
+                .iter()
+                .zip(fields)
+                .zip(&widths)
+                .map(|((value, (_, kind)), width)| match kind {
+                    FieldKind::Text => format!("{value:<width$}"),
+                    _ => format!("{value:>width$}"),
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        writeln!(writer, "{}", format_line(self.get_columns()))?;
+        for row in &cells {
+            writeln!(
+                writer,
+                "{}",
+                format_line(row.iter().map(|c| c.as_str()).collect())
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes rows as a JSON array of objects, with field names as keys and missing values as `null`
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let columns = self.get_columns();
+
+        writeln!(writer, "[")?;
+        for (index, row) in self.rows.iter().enumerate() {
+            let fields = columns
+                .iter()
+                .zip(row)
+                .map(|(name, value)| {
+                    let value = match value {
+                        Value::Null => "null".to_string(),
+                        Value::Number(number) => number.to_string(),
+                        Value::Text(text) => escape_json(text),
+                    };
+                    format!("\"{name}\": {value}")
+                })
+                .collect::<Vec<_>>();
+            let separator = if index + 1 < self.rows.len() { "," } else { "" };
+            writeln!(writer, "  {{{}}}{separator}", fields.join(", "))?;
+        }
+        writeln!(writer, "]")
+    }
+}
+
+/// Quotes `text` as a JSON string
+fn escape_json(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+    result.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
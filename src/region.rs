@@ -0,0 +1,118 @@
+//! Region module
+//!
+//! This module contains the code to process and store memory region information
+
+use crate::{
+    segment::Segment,
+    xmlwriter::{ToXmlWriter, XmlWriter},
+};
+use std::io::Write;
+use xml::writer::XmlEvent;
+
+/// Structure containing memory region information, as found in 'Memory Configuration' section
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Region {
+    /// Region name
+    name: String,
+    /// Start address
+    origin: u64,
+    /// Region length
+    length: u64,
+    /// Attributes (ex: `xr`, `rw`) or [None](Option::None) if missing
+    attributes: Option<String>,
+    /// Sum of all [Segment](crate::segment::Segment) sizes placed (VMA) or loaded (LMA) in the region
+    used_size: u64,
+}
+
+impl Region {
+    /// Creates a new [Region]
+    pub fn new(name: &str, origin: u64, length: u64) -> Self {
+        Self {
+            name: name.to_string(),
+            origin,
+            length,
+            attributes: None,
+            used_size: 0,
+        }
+    }
+
+    /// Set region [attributes](#structfield.attributes)
+    pub fn set_attributes(&mut self, attributes: &str) {
+        self.attributes = Some(attributes.to_string());
+    }
+
+    /// Get region [attributes](#structfield.attributes) or [None](Option::None)
+    pub fn get_attributes(&self) -> Option<&str> {
+        self.attributes.as_deref()
+    }
+
+    /// Get region [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get region [origin](#structfield.origin)
+    pub fn get_origin(&self) -> u64 {
+        self.origin
+    }
+
+    /// Get region [length](#structfield.length)
+    pub fn get_length(&self) -> u64 {
+        self.length
+    }
+
+    /// Update [used_size](#structfield.used_size)
+    pub fn update_used_size(&mut self, size: u64) {
+        self.used_size += size;
+    }
+
+    /// Get region [used_size](#structfield.used_size)
+    pub fn get_used_size(&self) -> u64 {
+        self.used_size
+    }
+
+    /// Returns [true] if `address` is placed inside the region
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.origin && (address - self.origin) < self.length
+    }
+
+    /// Returns [true] if allocated `segment` is placed (VMA) or loaded (LMA) inside the region
+    ///
+    /// Segments without address and size are never contained
+    pub fn contains_segment(&self, segment: &Segment) -> bool {
+        if !segment.is_allocated() || segment.get_size().is_none() {
+            return false;
+        }
+
+        let loaded = segment
+            .get_load_address()
+            .is_some_and(|load_address| self.contains(load_address));
+
+        segment
+            .get_address()
+            .is_some_and(|address| self.contains(address))
+            || loaded
+    }
+}
+
+impl<W: Write> ToXmlWriter<W> for Region {
+    fn to_xml_writer(&self, writer: &mut XmlWriter<W>) {
+        let origin = format!("{:#016x}", self.origin);
+        let length = self.length.to_string();
+        let used_size = self.used_size.to_string();
+
+        let mut element = XmlEvent::start_element("region")
+            .attr("name", self.name.as_str())
+            .attr("origin", &origin)
+            .attr("length", &length)
+            .attr("used_size", &used_size);
+
+        if let Some(attributes) = &self.attributes {
+            element = element.attr("attributes", attributes);
+        }
+
+        writer.start_element(element);
+        writer.end_element();
+    }
+}
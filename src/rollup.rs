@@ -0,0 +1,187 @@
+//! Rollup module
+//!
+//! This module contains the code to build a directory tree from object and library paths and to sum [Entry]
+//! sizes at every level, for a `du`-like view
+
+use crate::{entry::Entry, Parser};
+use std::collections::BTreeMap;
+
+/// Separator between path components in node paths
+const PATH_SEPARATOR: char = '/';
+
+/// Structure containing a directory tree node: a directory, a library or an object
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct RollupNode {
+    /// Last path component, empty for root node
+    name: String,
+    /// Path components joined with `/`, empty for root node
+    path: String,
+    /// Sum of entry sizes of all objects below this node, including `*fill*` padding
+    size: u64,
+    /// Child nodes, sorted by size (largest first), then by name
+    children: Vec<RollupNode>,
+}
+
+impl RollupNode {
+    /// Get node [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get node [path](#structfield.path)
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get node [size](#structfield.size)
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    /// Get node [children](#structfield.children)
+    pub fn get_children(&self) -> &[RollupNode] {
+        &self.children
+    }
+
+    /// Returns [true] if the node has no children (object)
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+/// Tree used while adding sizes, children are kept by name
+#[derive(Default)]
+struct TreeBuilder {
+    size: u64,
+    children: BTreeMap<String, TreeBuilder>,
+}
+
+impl TreeBuilder {
+    fn add(&mut self, components: &[&str], size: u64) {
+        self.size += size;
+        if let Some((first, rest)) = components.split_first() {
+            self.children
+                .entry(first.to_string())
+                .or_default()
+                .add(rest, size);
+        }
+    }
+
+    fn build(self, name: String, path: String) -> RollupNode {
+        let mut children = self
+            .children
+            .into_iter()
+            .map(|(child_name, child)| {
+                let child_path = match path.is_empty() {
+                    true => child_name.clone(),
+                    false => format!("{path}{PATH_SEPARATOR}{child_name}"),
+                };
+                child.build(child_name, child_path)
+            })
+            .collect::<Vec<_>>();
+        children.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+        RollupNode {
+            name,
+            path,
+            size: self.size,
+            children,
+        }
+    }
+}
+
+/// Structure containing the directory tree of object paths and sizes at every level
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct DirectoryRollup {
+    /// Root node, containing the total size
+    root: RollupNode,
+}
+
+impl DirectoryRollup {
+    /// Returns the rollup path of `entry` object: library path followed by object name for library members (ex:
+    /// `drivers/libserial.a(uart.o)` gives `drivers/libserial.a/uart.o`), or [None](Option::None) if there is no
+    /// object
+    pub fn get_entry_path(entry: &Entry) -> Option<String> {
+        let object_name = entry.get_object_name()?;
+        Some(match entry.get_library_name() {
+            Some(library_name) => format!("{library_name}{PATH_SEPARATOR}{object_name}"),
+            None => object_name.to_string(),
+        })
+    }
+
+    /// Splits `path` into components, ignoring empty and `.` components
+    fn split_path(path: &str) -> Vec<&str> {
+        path.split(['/', '\\'])
+            .filter(|c| !c.is_empty() && *c != ".")
+            .collect()
+    }
+
+    /// Creates a new [DirectoryRollup] from pairs of path and size. Sizes of the same path are added
+    pub fn from_sizes<'a, I>(sizes: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, u64)>,
+    {
+        let mut builder = TreeBuilder::default();
+        for (path, size) in sizes {
+            builder.add(&Self::split_path(path), size);
+        }
+
+        Self {
+            root: builder.build(String::new(), String::new()),
+        }
+    }
+
+    /// Creates a new [DirectoryRollup] of object sizes from `parser` results
+    ///
+    /// Only entries from [Parser::get_allocated_segments] are counted. Entries without object are not counted
+    pub fn new(parser: &Parser) -> Self {
+        let mut sizes: BTreeMap<String, u64> = BTreeMap::new();
+
+        for segment in parser.get_allocated_segments() {
+            for entry in segment.get_entries() {
+                if let Some(path) = Self::get_entry_path(entry) {
+                    *sizes.entry(path).or_insert(0) += entry.get_size();
+                }
+            }
+        }
+
+        Self::from_sizes(sizes.iter().map(|(path, size)| (path.as_str(), *size)))
+    }
+
+    /// Get [root](#structfield.root) node
+    pub fn get_root(&self) -> &RollupNode {
+        &self.root
+    }
+
+    /// Get total size of all objects
+    pub fn get_total_size(&self) -> u64 {
+        self.root.size
+    }
+
+    /// Get node at `path` or [None](Option::None)
+    pub fn get_node(&self, path: &str) -> Option<&RollupNode> {
+        let mut node = &self.root;
+        for component in Self::split_path(path) {
+            node = node.children.iter().find(|c| c.name == component)?;
+        }
+        Some(node)
+    }
+
+    /// Get pairs of depth and node, parent first (children sorted by size), down to `max_depth` levels below root
+    /// or all levels if [None](Option::None). Root node is not included
+    pub fn get_nodes(&self, max_depth: Option<usize>) -> Vec<(usize, &RollupNode)> {
+        let mut nodes = vec![];
+        let mut stack = vec![(0, &self.root)];
+
+        while let Some((depth, node)) = stack.pop() {
+            if depth > 0 {
+                nodes.push((depth, node));
+            }
+            if max_depth.is_none_or(|max_depth| depth < max_depth) {
+                stack.extend(node.children.iter().rev().map(|c| (depth + 1, c)));
+            }
+        }
+
+        nodes
+    }
+}
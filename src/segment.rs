@@ -10,8 +10,18 @@ use crate::{
 use std::io::Write;
 use xml::writer::XmlEvent;
 
+/// Name prefixes of segments that are not loaded into target memory
+const NON_ALLOCATED_PREFIXES: [&str; 5] = [
+    ".debug",
+    ".comment",
+    ".stab",
+    ".ARM.attributes",
+    ".gnu.attributes",
+];
+
 /// Structure containing memory map segment information
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Segment {
     /// Name
     name: String,
@@ -19,6 +29,8 @@ pub struct Segment {
     address: Option<u64>,
     /// Size or [None](Option::None) if missing (valid along with [address](#structfield.address))
     size: Option<u64>,
+    /// Load address (LMA) or [None](Option::None) if it is the same as [address](#structfield.address) (VMA)
+    load_address: Option<u64>,
     /// List of entries
     entries: Vec<Entry>,
 }
@@ -30,6 +42,7 @@ impl Segment {
             name: name.to_string(),
             address: None,
             size: None,
+            load_address: None,
             entries: vec![],
         }
     }
@@ -40,6 +53,11 @@ impl Segment {
         self.size = Some(size);
     }
 
+    /// Sets segment [load_address](#structfield.load_address)
+    pub fn set_load_address(&mut self, load_address: u64) {
+        self.load_address = Some(load_address);
+    }
+
     /// Calculates the sum of all entries
     pub fn get_entries_total_size(&self) -> u64 {
         if self.entries.is_empty() {
@@ -91,6 +109,20 @@ impl Segment {
     pub fn get_address(&self) -> Option<u64> {
         self.address
     }
+
+    /// Gets segment [load_address](#structfield.load_address) or [None](Option::None)
+    pub fn get_load_address(&self) -> Option<u64> {
+        self.load_address
+    }
+
+    /// Returns [false] if the segment does not occupy target memory (debug information, comments, etc.)
+    ///
+    /// The mapfile does not contain segment flags, so this is decided by the segment name
+    pub fn is_allocated(&self) -> bool {
+        !NON_ALLOCATED_PREFIXES
+            .iter()
+            .any(|prefix| self.name.starts_with(prefix))
+    }
 }
 
 impl<W: Write> ToXmlWriter<W> for Segment {
@@ -101,7 +133,12 @@ impl<W: Write> ToXmlWriter<W> for Segment {
             let addr = format!("{:#016x}", addr);
             let size = self.size.unwrap().to_string();
             element = element.attr("address", &addr).attr("size", &size);
-            writer.start_element(element);
+            if let Some(load_address) = self.load_address {
+                let load_address = format!("{:#016x}", load_address);
+                writer.start_element(element.attr("load_address", &load_address));
+            } else {
+                writer.start_element(element);
+            }
         } else {
             writer.start_element(element);
         }
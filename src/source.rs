@@ -0,0 +1,308 @@
+//! Source module
+//!
+//! This module contains the code to read compilation units from the DWARF debug information of an ELF image and to
+//! attribute [Entry] and symbol sizes to source files and source directories
+
+use crate::{entry::Entry, Parser};
+use gimli::{AttributeValue, EndianSlice, Operation, RunTimeEndian, SectionId};
+use object::{Object, ObjectSection};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    path::{Component, Path, PathBuf},
+};
+
+/// Name used for the directory of source files without a directory
+const ROOT_DIRECTORY: &str = ".";
+
+/// Enum containing the errors that can occur while reading debug information
+#[derive(Debug)]
+pub enum DebugInfoError {
+    /// Malformed ELF image
+    Object(object::Error),
+    /// Malformed DWARF debug information
+    Dwarf(gimli::Error),
+}
+
+impl Display for DebugInfoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DebugInfoError::Object(e) => write!(f, "{e}"),
+            DebugInfoError::Dwarf(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for DebugInfoError {}
+
+impl From<object::Error> for DebugInfoError {
+    fn from(e: object::Error) -> Self {
+        DebugInfoError::Object(e)
+    }
+}
+
+impl From<gimli::Error> for DebugInfoError {
+    fn from(e: gimli::Error) -> Self {
+        DebugInfoError::Dwarf(e)
+    }
+}
+
+/// Structure containing a DWARF compilation unit
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CompilationUnit {
+    /// Source file name, as given to the compiler (`DW_AT_name`)
+    name: String,
+    /// Compilation directory (`DW_AT_comp_dir`) or [None](Option::None)
+    directory: Option<String>,
+    /// Source file path: name joined with compilation directory, without `.` and `..` components
+    path: String,
+}
+
+impl CompilationUnit {
+    /// Creates a new [CompilationUnit] from source file `name` and compilation `directory`
+    pub fn new(name: &str, directory: Option<&str>) -> Self {
+        let mut path = PathBuf::new();
+        let joined = match directory {
+            Some(directory) => Path::new(directory).join(name),
+            None => PathBuf::from(name),
+        };
+        for component in joined.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => match path.components().next_back() {
+                    Some(Component::Normal(_)) => {
+                        path.pop();
+                    }
+                    Some(Component::RootDir) => {}
+                    _ => path.push(".."),
+                },
+                _ => path.push(component),
+            }
+        }
+
+        Self {
+            name: name.to_string(),
+            directory: directory.map(String::from),
+            path: path.to_string_lossy().into_owned(),
+        }
+    }
+
+    /// Get source file [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get compilation [directory](#structfield.directory) or [None](Option::None)
+    pub fn get_directory(&self) -> Option<&str> {
+        self.directory.as_deref()
+    }
+
+    /// Get source file [path](#structfield.path)
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get source file path relative to `root`, or full path if it is not inside `root`
+    pub fn get_relative_path(&self, root: Option<&str>) -> &str {
+        root.and_then(|root| Path::new(&self.path).strip_prefix(root).ok())
+            .and_then(|path| path.to_str())
+            .unwrap_or(&self.path)
+    }
+}
+
+/// Structure containing the compilation units of an ELF image and the addresses they own
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct DebugInfo {
+    /// List of compilation units, in `.debug_info` order
+    units: Vec<CompilationUnit>,
+    /// Tuples of start address, end address (exclusive) and unit index of code ranges, sorted by start address
+    ranges: Vec<(u64, u64, usize)>,
+    /// Variable addresses (`DW_OP_addr` locations) and their unit index
+    variables: BTreeMap<u64, usize>,
+}
+
+impl DebugInfo {
+    /// Reads compilation units from the DWARF debug information of ELF image `data`
+    ///
+    /// An image without debug information results in an empty [DebugInfo]
+    pub fn parse(data: &[u8]) -> Result<Self, DebugInfoError> {
+        let file = object::File::parse(data)?;
+        let endian = match file.is_little_endian() {
+            true => RunTimeEndian::Little,
+            false => RunTimeEndian::Big,
+        };
+
+        let load_section = |id: SectionId| -> Result<Cow<[u8]>, object::Error> {
+            match file.section_by_name(id.name()) {
+                Some(section) => section.uncompressed_data(),
+                None => Ok(Cow::Borrowed(&[])),
+            }
+        };
+        let sections = gimli::DwarfSections::load(load_section)?;
+        let dwarf = sections.borrow(|section| EndianSlice::new(section, endian));
+
+        let mut debug_info = Self::default();
+        let mut headers = dwarf.units();
+        while let Some(header) = headers.next()? {
+            let unit = dwarf.unit(header)?;
+            let name = match &unit.name {
+                Some(name) => name.to_string_lossy().into_owned(),
+                None => continue,
+            };
+            let directory = unit
+                .comp_dir
+                .as_ref()
+                .map(|directory| directory.to_string_lossy().into_owned());
+            let index = debug_info.units.len();
+            debug_info
+                .units
+                .push(CompilationUnit::new(&name, directory.as_deref()));
+
+            let mut ranges = dwarf.unit_ranges(&unit)?;
+            while let Some(range) = ranges.next()? {
+                // Discarded (garbage collected) code keeps a 0 address
+                if range.begin < range.end && range.begin != 0 {
+                    debug_info.ranges.push((range.begin, range.end, index));
+                }
+            }
+
+            let mut entries = unit.entries();
+            while let Some((_, die)) = entries.next_dfs()? {
+                if die.tag() != gimli::DW_TAG_variable {
+                    continue;
+                }
+                let expression = match die.attr_value(gimli::DW_AT_location)? {
+                    Some(AttributeValue::Exprloc(expression)) => expression,
+                    _ => continue,
+                };
+                let address = match expression.operations(unit.encoding()).next() {
+                    Ok(Some(Operation::Address { address })) => address,
+                    Ok(Some(Operation::AddressIndex { index })) => dwarf.address(&unit, index)?,
+                    _ => continue,
+                };
+                if address != 0 {
+                    debug_info.variables.entry(address).or_insert(index);
+                }
+            }
+        }
+        debug_info.ranges.sort_by_key(|r| r.0);
+
+        Ok(debug_info)
+    }
+
+    /// Reads debug information from ELF image file at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::parse(&data).map_err(std::io::Error::other)
+    }
+
+    /// Get all compilation [units](#structfield.units)
+    pub fn get_units(&self) -> &[CompilationUnit] {
+        &self.units
+    }
+
+    /// Returns [true] if the image has no compilation units
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    /// Get compilation unit whose code or variables are placed in `size` bytes starting at `address`
+    /// or [None](Option::None)
+    pub fn get_unit(&self, address: u64, size: u64) -> Option<&CompilationUnit> {
+        let end = address + size.max(1);
+
+        // Code ranges do not overlap, so the ranges ending after the address are the last ones starting before the
+        // end. The first of them (lowest start) is used
+        let index = self.ranges.partition_point(|r| r.0 < end);
+        let code = self.ranges[..index]
+            .iter()
+            .rev()
+            .take_while(|r| r.1 > address)
+            .last()
+            .map(|r| r.2);
+
+        code.or_else(|| self.variables.range(address..end).next().map(|v| *v.1))
+            .map(|index| &self.units[index])
+    }
+
+    /// Get compilation unit of `entry` or [None](Option::None)
+    pub fn get_entry_unit(&self, entry: &Entry) -> Option<&CompilationUnit> {
+        self.get_unit(entry.get_address(), entry.get_original_size())
+    }
+}
+
+/// Structure containing entry sizes per source file and per source directory
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct SourceSizes {
+    /// Pairs of source file path and size, sorted by size (largest first)
+    files: Vec<(String, u64)>,
+    /// Pairs of source directory path and size (of files inside, at any depth), sorted by size (largest first)
+    directories: Vec<(String, u64)>,
+    /// Size of entries without compilation unit (ex: libraries built without debug information)
+    unattributed_size: u64,
+}
+
+impl SourceSizes {
+    /// Creates a new [SourceSizes] from `parser` results and `debug_info`
+    ///
+    /// Source paths are relative to `root` when inside it. Only entries with a size, from
+    /// [Parser::get_allocated_segments] are counted
+    pub fn new(parser: &Parser, debug_info: &DebugInfo, root: Option<&str>) -> Self {
+        let mut files: HashMap<&str, u64> = HashMap::new();
+        let mut unattributed_size = 0;
+
+        for segment in parser.get_allocated_segments() {
+            for entry in segment.get_entries() {
+                let size = entry.get_original_size();
+                if size == 0 {
+                    continue;
+                }
+                match debug_info.get_entry_unit(entry) {
+                    Some(unit) => *files.entry(unit.get_relative_path(root)).or_insert(0) += size,
+                    None => unattributed_size += size,
+                }
+            }
+        }
+
+        let mut directories: HashMap<String, u64> = HashMap::new();
+        for (file, size) in &files {
+            // File size is added to every ancestor directory, up to the root
+            for ancestor in Path::new(file).ancestors().skip(1) {
+                let directory = match ancestor.as_os_str().is_empty() {
+                    true => ROOT_DIRECTORY.to_string(),
+                    false => ancestor.to_string_lossy().into_owned(),
+                };
+                *directories.entry(directory).or_insert(0) += size;
+            }
+        }
+
+        Self {
+            files: Self::sorted(files.into_iter().map(|(k, v)| (k.to_string(), v))),
+            directories: Self::sorted(directories.into_iter()),
+            unattributed_size,
+        }
+    }
+
+    /// Returns `sizes` sorted by size (largest first), then by name
+    fn sorted(sizes: impl Iterator<Item = (String, u64)>) -> Vec<(String, u64)> {
+        let mut sizes = sizes.collect::<Vec<_>>();
+        sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        sizes
+    }
+
+    /// Get [files](#structfield.files) sizes
+    pub fn get_files(&self) -> &[(String, u64)] {
+        &self.files
+    }
+
+    /// Get [directories](#structfield.directories) sizes
+    pub fn get_directories(&self) -> &[(String, u64)] {
+        &self.directories
+    }
+
+    /// Get [unattributed_size](#structfield.unattributed_size)
+    pub fn get_unattributed_size(&self) -> u64 {
+        self.unattributed_size
+    }
+}
@@ -0,0 +1,115 @@
+//! Symbol module
+//!
+//! This module contains the code to process and store linker symbol information
+
+use crate::{
+    demangle::demangle,
+    xmlwriter::{ToXmlWriter, XmlWriter},
+};
+use std::io::Write;
+use xml::writer::XmlEvent;
+
+/// Enum containing the ways a [LinkerSymbol] can be defined in the memory map
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SymbolKind {
+    /// Linker script assignment (ex: `_sdata = .`)
+    Assignment,
+    /// Linker script `PROVIDE`/`PROVIDE_HIDDEN` statement that was referenced
+    Provide,
+    /// Symbol defined by an input section (ex: function or variable)
+    Definition,
+}
+
+impl SymbolKind {
+    /// Returns kind name as used in outputs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SymbolKind::Assignment => "assignment",
+            SymbolKind::Provide => "provide",
+            SymbolKind::Definition => "definition",
+        }
+    }
+}
+
+/// Structure containing linker symbol information
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinkerSymbol {
+    /// Symbol name
+    name: String,
+    /// Symbol address (value)
+    address: u64,
+    /// How the symbol was defined
+    kind: SymbolKind,
+    /// Linker script expression or [None](Option::None) for [Definition](SymbolKind::Definition)
+    expression: Option<String>,
+}
+
+impl LinkerSymbol {
+    /// Creates a new [LinkerSymbol]
+    pub fn new(name: &str, address: u64, kind: SymbolKind) -> Self {
+        Self {
+            name: name.to_string(),
+            address,
+            kind,
+            expression: None,
+        }
+    }
+
+    /// Set symbol [expression](#structfield.expression)
+    pub fn set_expression(&mut self, expression: &str) {
+        self.expression = Some(expression.to_string());
+    }
+
+    /// Get symbol [expression](#structfield.expression) or [None](Option::None)
+    pub fn get_expression(&self) -> Option<&str> {
+        self.expression.as_deref()
+    }
+
+    /// Get symbol [name](#structfield.name)
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get demangled symbol [name](#structfield.name) or [None](Option::None) if the name is not mangled
+    pub fn get_demangled_name(&self) -> Option<String> {
+        demangle(&self.name)
+    }
+
+    /// Get symbol [address](#structfield.address)
+    pub fn get_address(&self) -> u64 {
+        self.address
+    }
+
+    /// Get symbol [kind](#structfield.kind)
+    pub fn get_kind(&self) -> SymbolKind {
+        self.kind
+    }
+}
+
+impl<W: Write> ToXmlWriter<W> for LinkerSymbol {
+    fn to_xml_writer(&self, writer: &mut XmlWriter<W>) {
+        let addr = format!("{:#016x}", self.address);
+        let demangled_name = match writer.get_demangle() {
+            true => self.get_demangled_name(),
+            false => None,
+        };
+
+        let mut element = XmlEvent::start_element("symbol")
+            .attr("name", self.name.as_str())
+            .attr("address", &addr)
+            .attr("kind", self.kind.as_str());
+
+        if let Some(expression) = &self.expression {
+            element = element.attr("expression", expression);
+        }
+
+        if let Some(demangled_name) = &demangled_name {
+            element = element.attr("demangled", demangled_name);
+        }
+
+        writer.start_element(element);
+        writer.end_element();
+    }
+}
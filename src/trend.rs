@@ -0,0 +1,428 @@
+//! Trend module
+//!
+//! This module contains the code to record parsed sizes of multiple builds into a SQLite database and to query
+//! their history, as CSV or HTML. The database requires the `trend` feature
+
+#[cfg(feature = "trend")]
+use crate::Parser;
+#[cfg(feature = "trend")]
+use rusqlite::{params, Connection, OptionalExtension};
+use std::io::Write;
+#[cfg(feature = "trend")]
+use std::{collections::HashMap, path::Path};
+
+/// Database schema, created if missing
+#[cfg(feature = "trend")]
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS builds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    build_id TEXT NOT NULL UNIQUE,
+    git_commit TEXT,
+    timestamp TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sizes (
+    build INTEGER NOT NULL REFERENCES builds(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    name TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    PRIMARY KEY (build, kind, name)
+);
+";
+
+/// Width of the HTML trend chart
+const CHART_WIDTH: u64 = 800;
+/// Height of the HTML trend chart
+const CHART_HEIGHT: u64 = 200;
+
+/// Enum containing the items whose size history is recorded
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum TrendKind {
+    /// Segment size
+    Segment,
+    /// Region used size
+    Region,
+    /// Sum of all segment sizes of an object
+    Object,
+    /// Sum of all entry sizes of a library
+    Library,
+}
+
+impl TrendKind {
+    /// Returns kind name as stored in database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrendKind::Segment => "segment",
+            TrendKind::Region => "region",
+            TrendKind::Object => "object",
+            TrendKind::Library => "library",
+        }
+    }
+
+    /// Returns [TrendKind] matching `name` or [None](Option::None) if unknown
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "segment" => Some(TrendKind::Segment),
+            "region" => Some(TrendKind::Region),
+            "object" => Some(TrendKind::Object),
+            "library" => Some(TrendKind::Library),
+            _ => None,
+        }
+    }
+}
+
+/// Structure containing the information that identifies a recorded build
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BuildInfo {
+    /// Unique build identifier (ex: CI build number)
+    build_id: String,
+    /// Git commit or [None](Option::None) if unknown
+    commit: Option<String>,
+    /// Build timestamp
+    timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl BuildInfo {
+    /// Creates a new [BuildInfo] with current time as timestamp
+    pub fn new(build_id: &str) -> Self {
+        Self {
+            build_id: build_id.to_string(),
+            commit: None,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// Set git [commit](#structfield.commit)
+    pub fn set_commit(&mut self, commit: &str) {
+        self.commit = Some(commit.to_string());
+    }
+
+    /// Set build [timestamp](#structfield.timestamp)
+    pub fn set_timestamp(&mut self, timestamp: chrono::DateTime<chrono::Utc>) {
+        self.timestamp = timestamp;
+    }
+
+    /// Get [build_id](#structfield.build_id)
+    pub fn get_build_id(&self) -> &str {
+        &self.build_id
+    }
+
+    /// Get git [commit](#structfield.commit) or [None](Option::None)
+    pub fn get_commit(&self) -> Option<&str> {
+        self.commit.as_deref()
+    }
+
+    /// Get build [timestamp](#structfield.timestamp)
+    pub fn get_timestamp(&self) -> chrono::DateTime<chrono::Utc> {
+        self.timestamp
+    }
+}
+
+/// Structure containing the size of an item in a recorded build
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TrendPoint {
+    /// Build identifier
+    build_id: String,
+    /// Git commit or [None](Option::None) if unknown
+    commit: Option<String>,
+    /// Build timestamp, in RFC 3339 format
+    timestamp: String,
+    /// Item size, 0 if the item is missing from the build
+    size: u64,
+    /// Size difference from the previous build (0 for the first one)
+    delta: i64,
+}
+
+impl TrendPoint {
+    /// Get [build_id](#structfield.build_id)
+    pub fn get_build_id(&self) -> &str {
+        &self.build_id
+    }
+
+    /// Get git [commit](#structfield.commit) or [None](Option::None)
+    pub fn get_commit(&self) -> Option<&str> {
+        self.commit.as_deref()
+    }
+
+    /// Get build [timestamp](#structfield.timestamp)
+    pub fn get_timestamp(&self) -> &str {
+        &self.timestamp
+    }
+
+    /// Get item [size](#structfield.size)
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+
+    /// Get size [delta](#structfield.delta)
+    pub fn get_delta(&self) -> i64 {
+        self.delta
+    }
+}
+
+/// Returns pairs of kind and name with their size, for all items of `parser`
+#[cfg(feature = "trend")]
+fn collect_sizes(parser: &Parser) -> HashMap<(TrendKind, &str), u64> {
+    let mut sizes = HashMap::new();
+
+    for segment in parser.get_memory_map_segments() {
+        if let Some(size) = segment.get_size() {
+            *sizes
+                .entry((TrendKind::Segment, segment.get_name()))
+                .or_insert(0) += size;
+        }
+
+        for entry in segment.get_entries() {
+            if let Some(library_name) = entry.get_library_name() {
+                *sizes.entry((TrendKind::Library, library_name)).or_insert(0) += entry.get_size();
+            }
+        }
+    }
+
+    for region in parser.get_memory_regions() {
+        sizes.insert(
+            (TrendKind::Region, region.get_name()),
+            region.get_used_size(),
+        );
+    }
+
+    for object in parser.get_memory_map_objects().values() {
+        sizes.insert(
+            (TrendKind::Object, object.get_name()),
+            object.get_total_size(),
+        );
+    }
+
+    sizes
+}
+
+/// Structure containing the size history database
+#[cfg(feature = "trend")]
+pub struct TrendDatabase {
+    /// SQLite connection
+    connection: Connection,
+}
+
+#[cfg(feature = "trend")]
+impl TrendDatabase {
+    /// Opens (or creates) the database at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> rusqlite::Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Opens a new database in memory
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(connection: Connection) -> rusqlite::Result<Self> {
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Records sizes of all segments, regions, objects and libraries from `parser` under `build`
+    ///
+    /// Fails if a build with the same id was already recorded
+    pub fn record(&mut self, parser: &Parser, build: &BuildInfo) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+
+        transaction.execute(
+            "INSERT INTO builds (build_id, git_commit, timestamp) VALUES (?1, ?2, ?3)",
+            params![
+                build.build_id,
+                build.commit,
+                build
+                    .timestamp
+                    .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+            ],
+        )?;
+        let id = transaction.last_insert_rowid();
+
+        {
+            let mut statement = transaction
+                .prepare("INSERT INTO sizes (build, kind, name, size) VALUES (?1, ?2, ?3, ?4)")?;
+            for ((kind, name), size) in collect_sizes(parser) {
+                statement.execute(params![id, kind.as_str(), name, size as i64])?;
+            }
+        }
+
+        transaction.commit()
+    }
+
+    /// Returns [true] if a build with `build_id` was recorded
+    pub fn contains_build(&self, build_id: &str) -> rusqlite::Result<bool> {
+        self.connection
+            .query_row(
+                "SELECT 1 FROM builds WHERE build_id = ?1",
+                params![build_id],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|r| r.is_some())
+    }
+
+    /// Returns the number of recorded builds
+    pub fn get_build_count(&self) -> rusqlite::Result<usize> {
+        self.connection
+            .query_row("SELECT COUNT(*) FROM builds", [], |row| row.get(0))
+    }
+
+    /// Returns the size history of item `name` of type `kind`, oldest build first
+    ///
+    /// If `limit` is set, only the last `limit` builds are returned
+    pub fn query(
+        &self,
+        kind: TrendKind,
+        name: &str,
+        limit: Option<usize>,
+    ) -> rusqlite::Result<Vec<TrendPoint>> {
+        let mut statement = self.connection.prepare(
+            "SELECT b.build_id, b.git_commit, b.timestamp, COALESCE(s.size, 0)
+             FROM builds b
+             LEFT JOIN sizes s ON s.build = b.id AND s.kind = ?1 AND s.name = ?2
+             ORDER BY b.id DESC
+             LIMIT ?3",
+        )?;
+
+        let limit = limit.map_or(-1, |l| l as i64);
+        let mut points = statement
+            .query_map(params![kind.as_str(), name, limit], |row| {
+                Ok(TrendPoint {
+                    build_id: row.get(0)?,
+                    commit: row.get(1)?,
+                    timestamp: row.get(2)?,
+                    size: row.get::<_, i64>(3)? as u64,
+                    delta: 0,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        points.reverse();
+
+        for i in 1..points.len() {
+            points[i].delta = points[i].size as i64 - points[i - 1].size as i64;
+        }
+
+        Ok(points)
+    }
+}
+
+/// Escapes `text` to be used inside HTML
+fn escape_html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Quotes `text` to be used as a CSV field, if needed
+pub(crate) fn escape_csv(text: &str) -> String {
+    match text.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => text.to_string(),
+    }
+}
+
+/// Writes `points` as CSV, with header
+pub fn write_csv<W: Write>(writer: &mut W, points: &[TrendPoint]) -> std::io::Result<()> {
+    writeln!(writer, "build_id,commit,timestamp,size,delta")?;
+    for point in points {
+        writeln!(
+            writer,
+            "{},{},{},{},{}",
+            escape_csv(&point.build_id),
+            escape_csv(point.commit.as_deref().unwrap_or("")),
+            escape_csv(&point.timestamp),
+            point.size,
+            point.delta
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes `points` as a standalone HTML page containing a line chart and a table, for item `name` of type `kind`
+pub fn write_html<W: Write>(
+    writer: &mut W,
+    kind: TrendKind,
+    name: &str,
+    points: &[TrendPoint],
+) -> std::io::Result<()> {
+    let title = format!("{} {} size trend", kind.as_str(), escape_html(name));
+
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, "<html>")?;
+    writeln!(writer, "<head>")?;
+    writeln!(writer, "<meta charset=\"utf-8\">")?;
+    writeln!(writer, "<title>{title}</title>")?;
+    writeln!(
+        writer,
+        "<style>body{{font-family:sans-serif}}table{{border-collapse:collapse}}td,th{{border:1px solid #ccc;padding:2px 8px}}td.n{{text-align:right}}.up{{color:#c00}}.down{{color:#080}}</style>"
+    )?;
+    writeln!(writer, "</head>")?;
+    writeln!(writer, "<body>")?;
+    writeln!(writer, "<h1>{title}</h1>")?;
+
+    // Line chart, scaled between minimum and maximum size
+    let min = points.iter().map(|p| p.size).min().unwrap_or(0);
+    let max = points.iter().map(|p| p.size).max().unwrap_or(0);
+    let range = (max - min).max(1);
+    let step = match points.len() {
+        0 | 1 => 0,
+        n => CHART_WIDTH / (n as u64 - 1),
+    };
+    let coordinates = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let x = i as u64 * step;
+            let y = CHART_HEIGHT - (p.size - min) * CHART_HEIGHT / range;
+            format!("{x},{y}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    writeln!(
+        writer,
+        "<svg width=\"{}\" height=\"{}\" viewBox=\"-5 -5 {} {}\"><polyline fill=\"none\" stroke=\"#36c\" stroke-width=\"2\" points=\"{}\"/></svg>",
+        CHART_WIDTH + 10,
+        CHART_HEIGHT + 10,
+        CHART_WIDTH + 10,
+        CHART_HEIGHT + 10,
+        coordinates
+    )?;
+    writeln!(writer, "<p>min: {min} bytes, max: {max} bytes</p>")?;
+
+    writeln!(writer, "<table>")?;
+    writeln!(
+        writer,
+        "<tr><th>Build</th><th>Commit</th><th>Timestamp</th><th>Size</th><th>Delta</th></tr>"
+    )?;
+    for point in points {
+        let class = match point.delta {
+            d if d > 0 => " up",
+            d if d < 0 => " down",
+            _ => "",
+        };
+        writeln!(
+            writer,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"n\">{}</td><td class=\"n{}\">{:+}</td></tr>",
+            escape_html(&point.build_id),
+            escape_html(point.commit.as_deref().unwrap_or("")),
+            escape_html(&point.timestamp),
+            point.size,
+            class,
+            point.delta
+        )?;
+    }
+    writeln!(writer, "</table>")?;
+    writeln!(writer, "</body>")?;
+    writeln!(writer, "</html>")?;
+
+    Ok(())
+}
@@ -0,0 +1,261 @@
+//! Validation module
+//!
+//! This module contains the code to find overlapping [Entries](crate::entry::Entry) and [Segments](Segment)
+//! and gaps that are not explained by `*fill*` padding
+
+use crate::{region::Region, segment::Segment, Parser};
+use std::fmt::Display;
+
+/// Enum containing validation issue types
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum IssueKind {
+    /// Two entries of the same segment have overlapping address ranges
+    EntryOverlap,
+    /// Two segments placed (VMA) or loaded (LMA) in the same region have overlapping address ranges
+    SegmentOverlap,
+    /// Address range inside a segment that is not covered by entries or `*fill*`
+    Gap,
+}
+
+impl IssueKind {
+    /// Returns kind name as used in outputs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssueKind::EntryOverlap => "entry overlap",
+            IssueKind::SegmentOverlap => "segment overlap",
+            IssueKind::Gap => "gap",
+        }
+    }
+}
+
+/// Structure containing a validation issue and its location
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Issue {
+    /// Issue type
+    kind: IssueKind,
+    /// Region name for [SegmentOverlap](IssueKind::SegmentOverlap), else segment name
+    location: String,
+    /// Name of the first entry/segment involved, or of the entry placed after a [Gap](IssueKind::Gap)
+    /// ([None](Option::None) if the gap is at the end of the segment)
+    first: Option<String>,
+    /// Name of the second entry/segment involved or [None](Option::None) for [Gap](IssueKind::Gap)
+    second: Option<String>,
+    /// Start address of the overlapping range or of the gap
+    address: u64,
+    /// Size of the overlapping range or of the gap
+    size: u64,
+}
+
+impl Issue {
+    /// Get issue [kind](#structfield.kind)
+    pub fn get_kind(&self) -> IssueKind {
+        self.kind
+    }
+
+    /// Get issue [location](#structfield.location)
+    pub fn get_location(&self) -> &str {
+        &self.location
+    }
+
+    /// Get [first](#structfield.first) name or [None](Option::None)
+    pub fn get_first(&self) -> Option<&str> {
+        self.first.as_deref()
+    }
+
+    /// Get [second](#structfield.second) name or [None](Option::None)
+    pub fn get_second(&self) -> Option<&str> {
+        self.second.as_deref()
+    }
+
+    /// Get issue [address](#structfield.address)
+    pub fn get_address(&self) -> u64 {
+        self.address
+    }
+
+    /// Get issue [size](#structfield.size)
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} in {} at {:#010x} ({} bytes)",
+            self.kind.as_str(),
+            self.location,
+            self.address,
+            self.size
+        )?;
+
+        match (self.kind, &self.first, &self.second) {
+            (IssueKind::Gap, Some(first), _) => write!(f, ": before {first}"),
+            (IssueKind::Gap, None, _) => write!(f, ": at the end"),
+            (_, Some(first), Some(second)) => write!(f, ": {first} and {second}"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Structure containing the results of the validation pass
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Validation {
+    /// List of found issues
+    issues: Vec<Issue>,
+}
+
+impl Validation {
+    /// Creates a new [Validation] from `parser` results
+    pub fn new(parser: &Parser) -> Self {
+        let mut validation = Self::default();
+
+        for segment in parser.get_allocated_segments() {
+            validation.check_entries(segment);
+        }
+
+        for region in parser.get_memory_regions() {
+            validation.check_segments(region, parser.get_memory_map_segments());
+        }
+
+        validation
+    }
+
+    /// Finds overlapping entries and gaps inside `segment`
+    fn check_entries(&mut self, segment: &Segment) {
+        // Segments without entries are usually sized by linker script assignments (ex: stack)
+        if segment.get_entries().is_empty() {
+            return;
+        }
+
+        // Tuples of start address, end address (exclusive, includes fill) and name
+        let mut ranges = segment
+            .get_entries()
+            .iter()
+            .map(|e| {
+                (
+                    e.get_address(),
+                    e.get_address().saturating_add(e.get_size()),
+                    e.get_name(),
+                )
+            })
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|(start, _, _)| *start);
+
+        // Without segment address, gaps can not be computed
+        let mut cursor = segment.get_address();
+        let mut last: Option<(u64, &str)> = None;
+
+        for (start, end, name) in ranges {
+            if let Some(position) = cursor {
+                if start > position {
+                    self.issues.push(Issue {
+                        kind: IssueKind::Gap,
+                        location: segment.get_name().to_string(),
+                        first: Some(name.to_string()),
+                        second: None,
+                        address: position,
+                        size: start - position,
+                    });
+                }
+                cursor = Some(position.max(end));
+            }
+
+            // Entries without size (ex: sharing the address with the next one) can not overlap
+            if start == end {
+                continue;
+            }
+
+            match last {
+                Some((last_end, last_name)) if start < last_end => {
+                    self.issues.push(Issue {
+                        kind: IssueKind::EntryOverlap,
+                        location: segment.get_name().to_string(),
+                        first: Some(last_name.to_string()),
+                        second: Some(name.to_string()),
+                        address: start,
+                        size: last_end.min(end) - start,
+                    });
+                    if end > last_end {
+                        last = Some((end, name));
+                    }
+                }
+                _ => last = Some((end, name)),
+            }
+        }
+
+        if let (Some(position), Some(address), Some(size)) =
+            (cursor, segment.get_address(), segment.get_size())
+        {
+            let segment_end = address.saturating_add(size);
+            if segment_end > position {
+                self.issues.push(Issue {
+                    kind: IssueKind::Gap,
+                    location: segment.get_name().to_string(),
+                    first: None,
+                    second: None,
+                    address: position,
+                    size: segment_end - position,
+                });
+            }
+        }
+    }
+
+    /// Finds overlapping `segments` placed (VMA) or loaded (LMA) inside `region`
+    fn check_segments(&mut self, region: &Region, segments: &[Segment]) {
+        // Tuples of start address, end address (exclusive) and name
+        let mut ranges = vec![];
+        for segment in segments.iter().filter(|s| s.is_allocated()) {
+            let (address, size) = match (segment.get_address(), segment.get_size()) {
+                (Some(address), Some(size)) if size > 0 => (address, size),
+                _ => continue,
+            };
+
+            if region.contains(address) {
+                ranges.push((address, address.saturating_add(size), segment.get_name()));
+            }
+
+            // Segments loaded at their own address are placed once
+            if let Some(load_address) = segment.get_load_address().filter(|&l| l != address) {
+                if region.contains(load_address) {
+                    ranges.push((
+                        load_address,
+                        load_address.saturating_add(size),
+                        segment.get_name(),
+                    ));
+                }
+            }
+        }
+        ranges.sort_by_key(|(start, _, _)| *start);
+
+        let mut last: Option<(u64, &str)> = None;
+        for (start, end, name) in ranges {
+            match last {
+                Some((last_end, last_name)) if start < last_end => {
+                    self.issues.push(Issue {
+                        kind: IssueKind::SegmentOverlap,
+                        location: region.get_name().to_string(),
+                        first: Some(last_name.to_string()),
+                        second: Some(name.to_string()),
+                        address: start,
+                        size: last_end.min(end) - start,
+                    });
+                    if end > last_end {
+                        last = Some((end, name));
+                    }
+                }
+                _ => last = Some((end, name)),
+            }
+        }
+    }
+
+    /// Get all found issues
+    pub fn get_issues(&self) -> &[Issue] {
+        &self.issues
+    }
+
+    /// Returns [true] if no issues were found
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
@@ -0,0 +1,362 @@
+//! XML Reader module
+//!
+//! This module contains the code to rebuild a [Parser] from the XML written by [XmlWriter](crate::xmlwriter::XmlWriter)
+//!
+//! Regions, segments, entries, objects and symbols are read back. Padding and grouping are skipped, since they
+//! are computed from the parsed information
+//!
+//! Objects are rebuilt from the `object` attribute of entries. XML written by older releases does not have it,
+//! in which case objects are read from `<objects>` element
+
+use crate::{
+    entry::Entry,
+    object::Object,
+    region::Region,
+    segment::Segment,
+    symbol::{LinkerSymbol, SymbolKind},
+    Parser,
+};
+use std::{fmt::Display, io::Read};
+use xml::{attribute::OwnedAttribute, reader::XmlEvent, EventReader, ParserConfig};
+
+/// Elements containing computed information, that are not read back
+const SKIPPED_ELEMENTS: [&str; 3] = ["padding", "grouping", "components"];
+
+/// Enum containing the errors that can occur while reading XML
+#[derive(Debug)]
+pub enum XmlReadError {
+    /// Malformed XML document
+    Xml(xml::reader::Error),
+    /// Element is missing a required attribute
+    MissingAttribute {
+        /// Element name
+        element: String,
+        /// Attribute name
+        attribute: String,
+    },
+    /// Attribute value can not be converted
+    InvalidValue {
+        /// Attribute name
+        attribute: String,
+        /// Attribute value
+        value: String,
+    },
+    /// Document root is not a `<mapfile>` element
+    InvalidRoot(String),
+}
+
+impl Display for XmlReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            XmlReadError::Xml(e) => write!(f, "{e}"),
+            XmlReadError::MissingAttribute { element, attribute } => {
+                write!(f, "Missing '{attribute}' attribute in <{element}> element")
+            }
+            XmlReadError::InvalidValue { attribute, value } => {
+                write!(f, "Invalid '{attribute}' attribute value '{value}'")
+            }
+            XmlReadError::InvalidRoot(name) => {
+                write!(f, "Invalid root element <{name}>, expected <mapfile>")
+            }
+        }
+    }
+}
+
+impl std::error::Error for XmlReadError {}
+
+impl From<xml::reader::Error> for XmlReadError {
+    fn from(e: xml::reader::Error) -> Self {
+        XmlReadError::Xml(e)
+    }
+}
+
+/// Wrapper over element attributes used to get and convert values
+struct Attributes<'a> {
+    /// Element name
+    element: &'a str,
+    /// Element attributes
+    attributes: &'a [OwnedAttribute],
+}
+
+impl<'a> Attributes<'a> {
+    /// Get attribute `name` value or [None](Option::None) if missing
+    fn get_optional(&self, name: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|a| a.name.local_name == name)
+            .map(|a| a.value.as_str())
+    }
+
+    /// Get attribute `name` value
+    fn get(&self, name: &str) -> Result<&'a str, XmlReadError> {
+        self.get_optional(name)
+            .ok_or_else(|| XmlReadError::MissingAttribute {
+                element: self.element.to_string(),
+                attribute: name.to_string(),
+            })
+    }
+
+    /// Converts `value` of attribute `name`, written in hex (`0x` prefix) or decimal format, into a number
+    fn to_number(name: &str, value: &str) -> Result<u64, XmlReadError> {
+        let result = match value.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => value.parse::<u64>(),
+        };
+        result.map_err(|_| XmlReadError::InvalidValue {
+            attribute: name.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    /// Get attribute `name` value as number
+    fn get_number(&self, name: &str) -> Result<u64, XmlReadError> {
+        Self::to_number(name, self.get(name)?)
+    }
+
+    /// Get attribute `name` value as number or [None](Option::None) if missing
+    fn get_optional_number(&self, name: &str) -> Result<Option<u64>, XmlReadError> {
+        self.get_optional(name)
+            .map(|value| Self::to_number(name, value))
+            .transpose()
+    }
+
+    /// Get attribute `name` value as boolean
+    fn get_bool(&self, name: &str) -> Result<bool, XmlReadError> {
+        let value = self.get(name)?;
+        value
+            .parse::<bool>()
+            .map_err(|_| XmlReadError::InvalidValue {
+                attribute: name.to_string(),
+                value: value.to_string(),
+            })
+    }
+}
+
+/// XML Reader structure
+pub struct XmlReader<R>
+where
+    R: Read,
+{
+    /// Event Reader
+    reader: EventReader<R>,
+    /// `source` attribute of `<mapfile>` element or [None](Option::None) if not read yet
+    source: Option<String>,
+    /// `datetime` attribute of `<mapfile>` element or [None](Option::None) if not read yet
+    datetime: Option<String>,
+    /// `schema_version` attribute of `<mapfile>` element or [None](Option::None) if not read yet
+    schema_version: Option<String>,
+}
+
+impl<R> XmlReader<R>
+where
+    R: Read,
+{
+    /// Creates a new [XmlReader]
+    pub fn new(source: R) -> Self {
+        Self {
+            reader: ParserConfig::new()
+                .ignore_comments(true)
+                .cdata_to_characters(true)
+                .create_reader(source),
+            source: None,
+            datetime: None,
+            schema_version: None,
+        }
+    }
+
+    /// Get mapfile source, as written in XML, or [None](Option::None) if missing
+    pub fn get_source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Get date and time when the XML was written or [None](Option::None) if missing
+    pub fn get_datetime(&self) -> Option<&str> {
+        self.datetime.as_deref()
+    }
+
+    /// Get schema version of the XML document or [None](Option::None) if missing (written by older releases)
+    pub fn get_schema_version(&self) -> Option<&str> {
+        self.schema_version.as_deref()
+    }
+
+    /// Reads the XML document and rebuilds a [Parser]
+    pub fn read(&mut self) -> Result<Parser, XmlReadError> {
+        let mut parser = Parser::new();
+
+        // Names of all currently opened elements
+        let mut stack: Vec<String> = vec![];
+        // Depth of the skipped element or [None](Option::None) if not inside one
+        let mut skip_depth: Option<usize> = None;
+
+        let mut segment: Option<Segment> = None;
+        let mut entry: Option<Entry> = None;
+        let mut object: Option<Object> = None;
+        // Objects read from `<objects>` element, used only if entries have no object name
+        let mut objects: Vec<Object> = vec![];
+        let mut data = String::new();
+
+        loop {
+            match self.reader.next()? {
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => {
+                    let element = name.local_name;
+                    stack.push(element.clone());
+
+                    if skip_depth.is_some() {
+                        continue;
+                    }
+
+                    let attributes = Attributes {
+                        element: &element,
+                        attributes: &attributes,
+                    };
+
+                    match element.as_str() {
+                        "mapfile" => {
+                            self.source = attributes.get_optional("source").map(String::from);
+                            self.datetime = attributes.get_optional("datetime").map(String::from);
+                            self.schema_version =
+                                attributes.get_optional("schema_version").map(String::from);
+                        }
+                        _ if stack.len() == 1 => return Err(XmlReadError::InvalidRoot(element)),
+                        e if SKIPPED_ELEMENTS.contains(&e) => skip_depth = Some(stack.len()),
+                        "region" => parser.add_region(Self::read_region(&attributes)?),
+                        "symbol" => parser.add_symbol(Self::read_symbol(&attributes)?),
+                        "object" => object = Some(Object::new(attributes.get("name")?)),
+                        "segment" => match object.as_mut() {
+                            Some(object) => object.update_segment_size(
+                                attributes.get("name")?,
+                                attributes.get_number("size")?,
+                            ),
+                            None => segment = Some(Self::read_segment(&attributes)?),
+                        },
+                        "entry" => entry = Some(Self::read_entry(&attributes)?),
+                        "data" => data.clear(),
+                        _ => {}
+                    }
+                }
+                XmlEvent::EndElement { .. } => {
+                    let element = stack.pop().unwrap_or_default();
+
+                    if let Some(depth) = skip_depth {
+                        if stack.len() < depth {
+                            skip_depth = None;
+                        }
+                        continue;
+                    }
+
+                    match element.as_str() {
+                        "object" => {
+                            if let Some(object) = object.take() {
+                                objects.push(object);
+                            }
+                        }
+                        "segment" if object.is_none() => {
+                            if let Some(segment) = segment.take() {
+                                parser.add_segment(segment);
+                            }
+                        }
+                        "entry" => {
+                            if let (Some(entry), Some(segment)) = (entry.take(), segment.as_mut()) {
+                                segment.add_entry(entry);
+                            }
+                        }
+                        "data" => {
+                            if let Some(entry) = entry.as_mut() {
+                                entry.set_data(&data);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                XmlEvent::Characters(text) if stack.last().is_some_and(|e| e == "data") => {
+                    data.push_str(&text);
+                }
+                XmlEvent::EndDocument => break,
+                _ => {}
+            }
+        }
+
+        if parser.get_memory_map_objects().is_empty() {
+            for object in objects {
+                parser.add_object(object);
+            }
+        }
+
+        Ok(parser)
+    }
+}
+
+/// Helper functions for [read](#method.read)
+impl<R> XmlReader<R>
+where
+    R: Read,
+{
+    fn read_region(attributes: &Attributes) -> Result<Region, XmlReadError> {
+        let mut region = Region::new(
+            attributes.get("name")?,
+            attributes.get_number("origin")?,
+            attributes.get_number("length")?,
+        );
+        if let Some(value) = attributes.get_optional("attributes") {
+            region.set_attributes(value);
+        }
+        Ok(region)
+    }
+
+    fn read_symbol(attributes: &Attributes) -> Result<LinkerSymbol, XmlReadError> {
+        let kind = match attributes.get("kind")? {
+            "assignment" => SymbolKind::Assignment,
+            "provide" => SymbolKind::Provide,
+            "definition" => SymbolKind::Definition,
+            value => {
+                return Err(XmlReadError::InvalidValue {
+                    attribute: "kind".to_string(),
+                    value: value.to_string(),
+                })
+            }
+        };
+        let mut symbol = LinkerSymbol::new(
+            attributes.get("name")?,
+            attributes.get_number("address")?,
+            kind,
+        );
+        if let Some(expression) = attributes.get_optional("expression") {
+            symbol.set_expression(expression);
+        }
+        Ok(symbol)
+    }
+
+    fn read_segment(attributes: &Attributes) -> Result<Segment, XmlReadError> {
+        let mut segment = Segment::new(attributes.get("name")?);
+        if let Some(address) = attributes.get_optional_number("address")? {
+            segment.set_size_and_address(attributes.get_number("size")?, address);
+        }
+        if let Some(load_address) = attributes.get_optional_number("load_address")? {
+            segment.set_load_address(load_address);
+        }
+        Ok(segment)
+    }
+
+    fn read_entry(attributes: &Attributes) -> Result<Entry, XmlReadError> {
+        let address = attributes.get_number("address")?;
+        let size = attributes.get_number("size")?;
+        let fill_size = attributes.get_number("fill_size")?;
+        let fill_overlaps = attributes.get_bool("fill_overlaps")?;
+
+        let mut entry = Entry::new(attributes.get("name")?, address, size, "");
+        if let Some(object_name) = attributes.get_optional("object") {
+            entry.set_object_name(object_name);
+        }
+        if let Some(library_name) = attributes.get_optional("library") {
+            entry.set_library_name(library_name);
+        }
+        if fill_overlaps {
+            entry.set_fill(address, fill_size);
+        } else if fill_size > 0 {
+            entry.set_fill(address + size, fill_size);
+        }
+        Ok(entry)
+    }
+}
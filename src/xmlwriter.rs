@@ -1,10 +1,30 @@
 //! XML Writer module
 //!
 //! This module contains the code for XML Writer
+//!
+//! The written document is described by the XSD schema in `schema/mapfile.xsd`, also available as [SCHEMA]
 
+use crate::{component::ComponentRules, SortKey};
+use chrono::format::{Item, StrftimeItems};
 use std::io::Write;
 use xml::{writer::XmlEvent, EmitterConfig, EventWriter};
 
+/// Version of the written XML document structure, as `schema_version` attribute of `<mapfile>` element
+///
+/// Must be updated together with `schema/mapfile.xsd` when the structure changes
+pub const SCHEMA_VERSION: &str = "1.1";
+
+/// XSD schema of the written XML document
+pub const SCHEMA: &str = include_str!("../schema/mapfile.xsd");
+
+/// Default format of `datetime` attribute of `<mapfile>` element
+pub const DEFAULT_DATETIME_FORMAT: &str = "%d/%m/%Y %T";
+
+/// Returns [true] if `format` is a valid [chrono::format::strftime] format
+pub fn is_valid_datetime_format(format: &str) -> bool {
+    StrftimeItems::new(format).all(|item| !matches!(item, Item::Error))
+}
+
 /// This trait must be implemented in order to convert into an xml format for XmlWriter
 pub trait ToXmlWriter<W>
 where
@@ -22,6 +42,16 @@ where
     writer: EventWriter<W>,
     /// If [true], do not include data from [Entry](crate::entry::Entry)
     skip_data: bool,
+    /// If [true], add demangled names next to mangled entry and symbol names
+    demangle: bool,
+    /// Order of written objects
+    sort_key: SortKey,
+    /// Rules used to write sizes per component, [None](Option::None) if not written
+    component_rules: Option<ComponentRules>,
+    /// If [true], write `*fill*` padding sizes and alignment gaps
+    padding: bool,
+    /// If [true], write sizes grouped by logical symbol and category
+    grouping: bool,
     /// If [true], the mapfile elements does not get written. Valid only for [new_empty](#method.new_empty), in UT.
     empty: bool,
 }
@@ -30,8 +60,15 @@ impl<W> XmlWriter<W>
 where
     W: Write,
 {
-    /// Creates a new [XmlWriter]
+    /// Creates a new [XmlWriter], with `datetime` attribute in [DEFAULT_DATETIME_FORMAT]
     pub fn new(sink: W, source: &str) -> Self {
+        Self::with_datetime_format(sink, source, DEFAULT_DATETIME_FORMAT)
+    }
+
+    /// Creates a new [XmlWriter], with `datetime` attribute in `format` ([chrono::format::strftime] syntax)
+    ///
+    /// Panics if `format` is not valid, see [is_valid_datetime_format]
+    pub fn with_datetime_format(sink: W, source: &str, format: &str) -> Self {
         let mut writer = Self {
             writer: EmitterConfig::new()
                 .perform_indent(true)
@@ -39,16 +76,19 @@ where
                 .write_document_declaration(false)
                 .create_writer(sink),
             skip_data: false,
+            demangle: false,
+            sort_key: SortKey::Name,
+            component_rules: None,
+            padding: false,
+            grouping: false,
             empty: false,
         };
         let datetime: chrono::DateTime<chrono::offset::Utc> = std::time::SystemTime::now().into();
         writer.start_element(
             XmlEvent::start_element("mapfile")
-                .attr(
-                    "datetime",
-                    datetime.format("%d/%m/%Y %T").to_string().as_str(),
-                )
-                .attr("source", source),
+                .attr("datetime", datetime.format(format).to_string().as_str())
+                .attr("source", source)
+                .attr("schema_version", SCHEMA_VERSION),
         );
         writer
     }
@@ -62,6 +102,11 @@ where
                 .write_document_declaration(false)
                 .create_writer(sink),
             skip_data: false,
+            demangle: false,
+            sort_key: SortKey::Name,
+            component_rules: None,
+            padding: false,
+            grouping: false,
             empty: true,
         }
     }
@@ -76,6 +121,56 @@ where
         self.skip_data
     }
 
+    /// Set demangle
+    pub fn set_demangle(&mut self, value: bool) {
+        self.demangle = value;
+    }
+
+    /// Get demangle state
+    pub fn get_demangle(&self) -> bool {
+        self.demangle
+    }
+
+    /// Set sort key, used to order objects
+    pub fn set_sort_key(&mut self, value: SortKey) {
+        self.sort_key = value;
+    }
+
+    /// Get sort key
+    pub fn get_sort_key(&self) -> SortKey {
+        self.sort_key
+    }
+
+    /// Set component rules. If set, sizes per component are written
+    pub fn set_component_rules(&mut self, rules: ComponentRules) {
+        self.component_rules = Some(rules);
+    }
+
+    /// Get component rules or [None](Option::None)
+    pub fn get_component_rules(&self) -> Option<&ComponentRules> {
+        self.component_rules.as_ref()
+    }
+
+    /// Set padding. If [true], `*fill*` padding sizes and alignment gaps are written
+    pub fn set_padding(&mut self, value: bool) {
+        self.padding = value;
+    }
+
+    /// Get padding state
+    pub fn get_padding(&self) -> bool {
+        self.padding
+    }
+
+    /// Set grouping. If [true], sizes grouped by logical symbol and category are written
+    pub fn set_grouping(&mut self, value: bool) {
+        self.grouping = value;
+    }
+
+    /// Get grouping state
+    pub fn get_grouping(&self) -> bool {
+        self.grouping
+    }
+
     /// Start a new element with given `event`. Make sure it has an equivalent [end_element](#method.end_element)
     pub fn start_element<'a, E>(&mut self, event: E)
     where
@@ -0,0 +1,188 @@
+use parser::{
+    batch::{self, BatchKind, BatchReport},
+    Parser,
+};
+use std::path::PathBuf;
+
+const NRF52_MAPFILE: &str = "\
+Memory Configuration
+
+Name             Origin             Length             Attributes
+FLASH            0x0000000000000000 0x0000000000080000 xr
+RAM              0x0000000020000000 0x0000000000010000 xrw
+
+Linker script and memory map
+
+.text           0x0000000000000000      0x300
+ .text.main     0x0000000000000000      0x100 main.o
+ .text.printk   0x0000000000000100      0x200 libkernel.a(printk.o)
+
+.data           0x0000000020000000       0x10 load address 0x0000000000000300
+ .data.uart     0x0000000020000000       0x10 libdrivers.a(uart.o)
+
+.comment        0x0000000000000000       0x20
+ .comment       0x0000000000000000       0x20 libkernel.a(printk.o)
+";
+
+const NRF5340_MAPFILE: &str = "\
+Memory Configuration
+
+Name             Origin             Length             Attributes
+FLASH            0x0000000000000000 0x0000000000100000 xr
+RAM              0x0000000020000000 0x0000000000040000 xrw
+
+Linker script and memory map
+
+.text           0x0000000000000000      0x500
+ .text.main     0x0000000000000000      0x100 main.o
+ .text.printk   0x0000000000000100      0x220 libkernel.a(printk.o)
+ .text.ipc      0x0000000000000320      0x1e0 libipc.a(ipc.o)
+
+.data           0x0000000020000000        0x8 load address 0x0000000000000500
+ .data.ipc      0x0000000020000000        0x8 libipc.a(ipc.o)
+";
+
+fn new_report() -> BatchReport {
+    let nrf52 = Parser::parse(NRF52_MAPFILE);
+    let nrf5340 = Parser::parse(NRF5340_MAPFILE);
+    BatchReport::new([("nrf52dk", &nrf52), ("nrf5340dk", &nrf5340)])
+}
+
+#[test]
+fn report() {
+    let report = new_report();
+    assert_eq!(report.get_variants(), ["nrf52dk", "nrf5340dk"]);
+
+    let rows = report
+        .get_rows()
+        .iter()
+        .map(|r| (r.get_kind(), r.get_name(), r.get_sizes().to_vec()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        [
+            (BatchKind::Region, "FLASH", vec![Some(0x310), Some(0x508)]),
+            (BatchKind::Region, "RAM", vec![Some(0x10), Some(0x8)]),
+            (BatchKind::Segment, ".data", vec![Some(0x10), Some(0x8)]),
+            (BatchKind::Segment, ".text", vec![Some(0x300), Some(0x500)]),
+            (BatchKind::Library, "libdrivers.a", vec![Some(0x10), None]),
+            (BatchKind::Library, "libipc.a", vec![None, Some(0x1e8)]),
+            (
+                BatchKind::Library,
+                "libkernel.a",
+                vec![Some(0x200), Some(0x220)]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn shared_rows() {
+    let report = new_report();
+
+    let shared = report
+        .get_rows_of_kind(BatchKind::Library)
+        .iter()
+        .filter(|r| r.is_shared())
+        .map(|r| r.get_name())
+        .collect::<Vec<_>>();
+    assert_eq!(shared, ["libkernel.a"]);
+
+    let row = report.get_row(BatchKind::Library, "libipc.a").unwrap();
+    assert_eq!(row.get_variant_count(), 1);
+    assert!(report.get_row(BatchKind::Segment, ".comment").is_none());
+}
+
+#[test]
+fn write_csv() {
+    let mut output = vec![];
+    new_report().write_csv(&mut output).unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "kind,name,nrf52dk,nrf5340dk");
+    assert_eq!(lines[1], "region,FLASH,784,1288");
+    assert_eq!(lines[5], "library,libdrivers.a,16,");
+    assert_eq!(lines.len(), 8);
+}
+
+#[test]
+fn write_table() {
+    let mut output = vec![];
+    new_report().write_table(&mut output).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "                      nrf52dk  nrf5340dk
+region FLASH              784       1288
+region RAM                 16          8
+segment .data              16          8
+segment .text             768       1280
+library libdrivers.a       16          -
+library libipc.a            -        488
+library libkernel.a       512        544
+"
+    );
+}
+
+#[test]
+fn variant_names() {
+    assert_eq!(
+        batch::get_variant_names(&[
+            "build/nrf52dk/zephyr/zephyr.map",
+            "build/nrf5340dk/zephyr/zephyr.map.gz",
+        ]),
+        ["nrf52dk", "nrf5340dk"]
+    );
+    assert_eq!(
+        batch::get_variant_names(&["maps/app-v1.map.xz", "maps/app-v2.xml"]),
+        ["app-v1", "app-v2"]
+    );
+    // Nested variants keep their differing components
+    assert_eq!(
+        batch::get_variant_names(&["out/a/x/fw.map", "out/b/fw.map"]),
+        ["a/x", "b"]
+    );
+    // Paths where one contains all the others keep at least one component
+    assert_eq!(
+        batch::get_variant_names(&["fw.map", "app/fw.map"]),
+        ["fw", "app/fw"]
+    );
+    assert_eq!(
+        batch::get_variant_names(&["build/zephyr/zephyr.map"]),
+        ["zephyr"]
+    );
+    assert!(batch::get_variant_names::<&str>(&[]).is_empty());
+    // Paths that only differ by extensions keep them
+    assert_eq!(
+        batch::get_variant_names(&["dir/a.map", "dir/a.xml", "dir/b.map"]),
+        ["a.map", "a.xml", "b"]
+    );
+    assert_eq!(
+        batch::get_variant_names(&["dir/a.map", "dir/a.map.gz"]),
+        ["a.map", "a.map.gz"]
+    );
+}
+
+#[test]
+fn find_mapfiles() {
+    let expected = vec![PathBuf::from("tests/data/firmware/firmware.map")];
+
+    assert_eq!(
+        batch::find_mapfiles(&["tests/data/firmware"]).unwrap(),
+        expected
+    );
+    assert_eq!(
+        batch::find_mapfiles(&["tests/data/*/*.map", "tests/data/firmware/firmware.map"]).unwrap(),
+        expected
+    );
+    // Files are used as they are, even without mapfile extension
+    assert_eq!(
+        batch::find_mapfiles(&["tests/data/firmware/build.sh"]).unwrap(),
+        [PathBuf::from("tests/data/firmware/build.sh")]
+    );
+
+    let error = batch::find_mapfiles(&["tests/data/*.none"]).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    assert!(batch::find_mapfiles(&["tests/data/[.map"]).is_err());
+}
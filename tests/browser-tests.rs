@@ -0,0 +1,304 @@
+use parser::{
+    browser::{Browser, NodeKind},
+    diff::Diff,
+    Parser,
+};
+
+const OLD_MAPFILE: &str = "\
+Memory Configuration
+
+Name             Origin             Length             Attributes
+FLASH            0x0000000008000000 0x0000000000010000 xr
+RAM              0x0000000020000000 0x0000000000004000 xrw
+
+Linker script and memory map
+
+.text           0x0000000008000000       0x40
+ .text.main     0x0000000008000000       0x14 main.o
+ .text.foo      0x0000000008000014       0x2c libfoo.a(foo.o)
+
+.data           0x0000000020000000        0x4 load address 0x0000000008000040
+ .data.counter  0x0000000020000000        0x4 main.o
+";
+
+const NEW_MAPFILE: &str = "\
+Memory Configuration
+
+Name             Origin             Length             Attributes
+FLASH            0x0000000008000000 0x0000000000010000 xr
+RAM              0x0000000020000000 0x0000000000004000 xrw
+
+Linker script and memory map
+
+.text           0x0000000008000000       0x50
+ .text.main     0x0000000008000000       0x10 main.o
+ .text.foo      0x0000000008000010       0x30 libfoo.a(foo.o)
+ .text.bar      0x0000000008000040       0x10 libfoo.a(bar.o)
+
+.data           0x0000000020000000        0x4 load address 0x0000000008000050
+ .data.counter  0x0000000020000000        0x4 main.o
+
+.ext            0x0000000060000000        0x8
+ .ext.buffer    0x0000000060000000        0x8 ext.o
+
+.comment        0x0000000000000000       0x20
+ .comment       0x0000000000000000       0x20 main.o
+";
+
+/// Returns names of displayed rows, indented by depth
+fn rows(browser: &Browser) -> Vec<String> {
+    browser
+        .get_rows()
+        .iter()
+        .map(|i| {
+            let node = browser.get_node(*i);
+            format!("{}{}", "  ".repeat(node.get_depth()), node.get_name())
+        })
+        .collect()
+}
+
+/// Expands every displayed node, down to entries
+fn expand_all(browser: &mut Browser) {
+    let mut row = 0;
+    while row < browser.get_rows().len() {
+        browser.move_selection(row as isize - browser.get_selected_row() as isize);
+        browser.expand_selected();
+        row += 1;
+    }
+    browser.select_first();
+}
+
+#[test]
+fn tree() {
+    let parser = Parser::parse(NEW_MAPFILE);
+    let mut browser = Browser::new(&parser);
+
+    assert_eq!(rows(&browser), ["FLASH", "(no region)", "RAM"]);
+
+    expand_all(&mut browser);
+    assert_eq!(
+        rows(&browser),
+        [
+            "FLASH",
+            "  .text",
+            "    libfoo.a(foo.o)",
+            "      .text.foo",
+            "    libfoo.a(bar.o)",
+            "      .text.bar",
+            "    main.o",
+            "      .text.main",
+            "  .data",
+            "    main.o",
+            "      .data.counter",
+            "(no region)",
+            "  .ext",
+            "    ext.o",
+            "      .ext.buffer",
+            "RAM",
+            "  .data",
+            "    main.o",
+            "      .data.counter",
+        ]
+    );
+
+    let sizes = browser
+        .get_rows()
+        .iter()
+        .map(|i| browser.get_node(*i))
+        .filter(|n| n.get_depth() < 2)
+        .map(|n| (n.get_kind(), n.get_size()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sizes,
+        [
+            (NodeKind::Region, 0x54),
+            (NodeKind::Segment, 0x50),
+            (NodeKind::Segment, 0x4),
+            (NodeKind::Region, 0x8),
+            (NodeKind::Segment, 0x8),
+            (NodeKind::Region, 0x4),
+            (NodeKind::Segment, 0x4),
+        ]
+    );
+}
+
+#[test]
+fn navigation() {
+    let parser = Parser::parse(NEW_MAPFILE);
+    let mut browser = Browser::new(&parser);
+
+    browser.move_selection(-1);
+    assert_eq!(browser.get_selected_row(), 0);
+    browser.move_selection(10);
+    assert_eq!(browser.get_selected().unwrap().get_name(), "RAM");
+
+    browser.select_first();
+    browser.toggle_selected();
+    assert!(browser.is_expanded(browser.get_rows()[0]));
+    assert_eq!(browser.get_rows().len(), 5);
+
+    // Collapsing a collapsed node selects its parent
+    browser.move_selection(1);
+    assert_eq!(browser.get_selected().unwrap().get_name(), ".text");
+    browser.collapse_selected();
+    assert_eq!(browser.get_selected().unwrap().get_name(), "FLASH");
+    browser.collapse_selected();
+    assert_eq!(browser.get_rows().len(), 3);
+
+    browser.select_last();
+    assert_eq!(browser.get_selected_row(), 2);
+}
+
+#[test]
+fn entry_data() {
+    let parser = Parser::parse(NEW_MAPFILE);
+    let mut browser = Browser::new(&parser);
+
+    browser.set_search("bar");
+    let node = browser
+        .get_rows()
+        .iter()
+        .map(|i| browser.get_node(*i))
+        .find(|n| n.get_kind() == NodeKind::Entry)
+        .unwrap();
+    assert_eq!(
+        node.get_entry().unwrap().get_data(),
+        " .text.bar      0x0000000008000040       0x10 libfoo.a(bar.o)"
+    );
+}
+
+#[test]
+fn search() {
+    let parser = Parser::parse(NEW_MAPFILE);
+    let mut browser = Browser::new(&parser);
+
+    // Ancestors of matching nodes are expanded
+    browser.set_search("COUNTER");
+    assert_eq!(
+        rows(&browser),
+        [
+            "FLASH",
+            "  .data",
+            "    main.o",
+            "      .data.counter",
+            "RAM",
+            "  .data",
+            "    main.o",
+            "      .data.counter",
+        ]
+    );
+
+    // Matching nodes are not expanded
+    browser.set_search("libfoo");
+    assert_eq!(
+        rows(&browser),
+        [
+            "FLASH",
+            "  .text",
+            "    libfoo.a(foo.o)",
+            "    libfoo.a(bar.o)"
+        ]
+    );
+
+    // Selected node is kept
+    browser.move_selection(3);
+    browser.set_search("");
+    assert_eq!(rows(&browser), ["FLASH", "(no region)", "RAM"]);
+    assert_eq!(browser.get_selected_row(), 0);
+
+    browser.set_search("unknown");
+    assert!(browser.get_rows().is_empty());
+    assert!(browser.get_selected().is_none());
+}
+
+#[test]
+fn diff() {
+    let old = Parser::parse(OLD_MAPFILE);
+    let new = Parser::parse(NEW_MAPFILE);
+    let mut browser = Browser::new(&new);
+    assert!(browser.get_nodes().iter().all(|n| n.get_delta().is_none()));
+
+    browser.set_diff(&Diff::new(&old, &new));
+
+    let deltas = browser
+        .get_nodes()
+        .iter()
+        .filter(|n| n.get_depth() < 2)
+        .map(|n| (n.get_name(), n.get_delta()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        deltas,
+        [
+            ("FLASH", Some(0x10)),
+            (".text", Some(0x10)),
+            (".data", Some(0)),
+            ("RAM", Some(0)),
+            (".data", Some(0)),
+            ("(no region)", Some(0x8)),
+            (".ext", Some(0x8)),
+        ]
+    );
+
+    browser.set_diff_only(true);
+    assert_eq!(
+        rows(&browser),
+        [
+            "FLASH",
+            "  .text",
+            "    libfoo.a(foo.o)",
+            "      .text.foo",
+            "    libfoo.a(bar.o)",
+            "      .text.bar",
+            "    main.o",
+            "      .text.main",
+            "(no region)",
+            "  .ext",
+            "    ext.o",
+            "      .ext.buffer",
+        ]
+    );
+
+    let deltas = browser
+        .get_rows()
+        .iter()
+        .map(|i| browser.get_node(*i))
+        .filter(|n| n.get_kind() == NodeKind::Entry)
+        .map(|n| n.get_delta())
+        .collect::<Vec<_>>();
+    assert_eq!(deltas, [Some(0x4), Some(0x10), Some(-0x4), Some(0x8)]);
+}
+
+#[test]
+fn diff_same_object_in_libraries() {
+    let old = Parser::parse(
+        "\
+Linker script and memory map
+
+.text           0x0000000008000000       0x20
+ .text.init     0x0000000008000000       0x10 liba.a(init.o)
+ .text.init     0x0000000008000010       0x10 libb.a(init.o)
+",
+    );
+    let new = Parser::parse(
+        "\
+Linker script and memory map
+
+.text           0x0000000008000000       0x28
+ .text.init     0x0000000008000000       0x10 liba.a(init.o)
+ .text.init     0x0000000008000010       0x18 libb.a(init.o)
+",
+    );
+    let mut browser = Browser::new(&new);
+    browser.set_diff(&Diff::new(&old, &new));
+
+    let deltas = browser
+        .get_nodes()
+        .iter()
+        .filter(|n| n.get_kind() == NodeKind::Object)
+        .map(|n| (n.get_name(), n.get_delta()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        deltas,
+        [("liba.a(init.o)", Some(0)), ("libb.a(init.o)", Some(0x8))]
+    );
+}
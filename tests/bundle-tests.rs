@@ -0,0 +1,202 @@
+use parser::{
+    bundle::{self, Bundle, BundleError},
+    region::Region,
+    Parser,
+};
+
+const MCUBOOT_MAPFILE: &str = "\
+Memory Configuration
+
+Name             Origin             Length             Attributes
+FLASH            0x0000000000000000 0x000000000000c000 xr
+SRAM             0x0000000020000000 0x0000000000010000 xrw
+
+Linker script and memory map
+
+.text           0x0000000000000000     0x5000
+ .text.main     0x0000000000000000     0x5000 main.o
+
+.data           0x0000000020000000      0x100 load address 0x0000000000005000
+ .data.state    0x0000000020000000      0x100 main.o
+";
+
+const APP_MAPFILE: &str = "\
+Memory Configuration
+
+Name             Origin             Length             Attributes
+FLASH            0x000000000000c000 0x0000000000074000 xr
+SRAM             0x0000000020000000 0x0000000000040000 xrw
+
+Linker script and memory map
+
+.text           0x000000000000c000     0x8000
+ .text.main     0x000000000000c000     0x8000 main.o
+
+.data           0x0000000020000000      0x200 load address 0x0000000000014000
+ .data.buffer   0x0000000020000000      0x200 main.o
+
+.bss            0x0000000020000200      0x400
+ .bss.heap      0x0000000020000200      0x400 main.o
+";
+
+const NET_MAPFILE: &str = "\
+Memory Configuration
+
+Name             Origin             Length             Attributes
+FLASH            0x0000000001000000 0x0000000000040000 xr
+SRAM             0x0000000021000000 0x0000000000010000 xrw
+
+Linker script and memory map
+
+.text           0x0000000001000000     0x3000
+ .text.main     0x0000000001000000     0x3000 main.o
+";
+
+fn new_bundle(mcuboot: &str) -> Bundle {
+    let mut bundle = Bundle::new();
+    bundle.add_image("mcuboot", Parser::parse(mcuboot)).unwrap();
+    bundle.add_image("app", Parser::parse(APP_MAPFILE)).unwrap();
+    bundle.add_image("net", Parser::parse(NET_MAPFILE)).unwrap();
+    bundle
+}
+
+#[test]
+fn is_flash() {
+    let region = |name, attributes: Option<&str>| {
+        let mut region = Region::new(name, 0, 0x1000);
+        if let Some(attributes) = attributes {
+            region.set_attributes(attributes);
+        }
+        region
+    };
+
+    assert!(bundle::is_flash(&region("FLASH", Some("rx"))));
+    assert!(bundle::is_flash(&region("m_text", Some("RX"))));
+    assert!(bundle::is_flash(&region("ram", Some("rx!w"))));
+    assert!(!bundle::is_flash(&region("FLASH", Some("xrw"))));
+    assert!(!bundle::is_flash(&region("RAM", Some("!rx"))));
+    assert!(bundle::is_flash(&region("BOOT_ROM", None)));
+    assert!(bundle::is_flash(&region("flash_app", None)));
+    assert!(!bundle::is_flash(&region("SRAM", None)));
+}
+
+#[test]
+fn images() {
+    let bundle = new_bundle(MCUBOOT_MAPFILE);
+
+    let names = bundle
+        .get_images()
+        .iter()
+        .map(|i| i.get_name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["mcuboot", "app", "net"]);
+
+    let usage = |image| {
+        bundle
+            .get_image(image)
+            .unwrap()
+            .get_regions()
+            .iter()
+            .map(|r| (r.get_name().to_string(), r.get_used_size()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        usage("mcuboot"),
+        [("FLASH".to_string(), 0x5100), ("SRAM".to_string(), 0x100)]
+    );
+    assert_eq!(
+        usage("app"),
+        [("FLASH".to_string(), 0x8200), ("SRAM".to_string(), 0x600)]
+    );
+    assert!(bundle.get_image("bootloader").is_none());
+}
+
+#[test]
+fn duplicate_image() {
+    let mut bundle = new_bundle(MCUBOOT_MAPFILE);
+    let error = bundle
+        .add_image("app", Parser::parse(APP_MAPFILE))
+        .unwrap_err();
+
+    assert_eq!(error, BundleError::DuplicateImage("app".to_string()));
+    assert_eq!(error.to_string(), "Duplicate bundle image 'app'");
+    assert_eq!(bundle.get_images().len(), 3);
+}
+
+#[test]
+fn shared_flashes() {
+    let flashes = new_bundle(MCUBOOT_MAPFILE).get_shared_flashes();
+    assert_eq!(flashes.len(), 2);
+
+    // Bootloader and application partitions are adjacent in the application core flash
+    let flash = &flashes[0];
+    assert_eq!(flash.get_name(), "FLASH");
+    assert_eq!(flash.get_origin(), 0);
+    assert_eq!(flash.get_length(), 0x80000);
+    assert_eq!(
+        flash.get_regions(),
+        [
+            ("mcuboot".to_string(), "FLASH".to_string()),
+            ("app".to_string(), "FLASH".to_string())
+        ]
+    );
+    assert_eq!(
+        flash.get_used_sizes(),
+        [("mcuboot".to_string(), 0x5100), ("app".to_string(), 0x8200)]
+    );
+    assert_eq!(flash.get_used_size(), 0xd300);
+    assert_eq!(flash.get_image_used_size("net"), None);
+
+    // Network core flash is a separate memory
+    let flash = &flashes[1];
+    assert_eq!(flash.get_origin(), 0x0100_0000);
+    assert_eq!(flash.get_length(), 0x40000);
+    assert_eq!(flash.get_used_sizes(), [("net".to_string(), 0x3000)]);
+}
+
+#[test]
+fn shared_flashes_load_address() {
+    // Load address equal to the address is counted once
+    let mapfile = NET_MAPFILE.replace(
+        ".text           0x0000000001000000     0x3000",
+        ".text           0x0000000001000000     0x3000 load address 0x0000000001000000",
+    );
+    let mut bundle = Bundle::new();
+    bundle.add_image("net", Parser::parse(&mapfile)).unwrap();
+
+    let flashes = bundle.get_shared_flashes();
+    assert_eq!(flashes[0].get_used_sizes(), [("net".to_string(), 0x3000)]);
+    assert!(bundle.find_overlaps().is_empty());
+}
+
+#[test]
+fn overlaps() {
+    // RAM is shared by bootloader and application, but is not checked
+    assert!(new_bundle(MCUBOOT_MAPFILE).find_overlaps().is_empty());
+
+    // Bootloader code overflows into the application partition
+    let mapfile = MCUBOOT_MAPFILE
+        .replace(
+            ".text           0x0000000000000000     0x5000",
+            ".text           0x0000000000000000     0xc100",
+        )
+        .replace(
+            "load address 0x0000000000005000",
+            "load address 0x000000000000c100",
+        );
+    let overlaps = new_bundle(&mapfile).find_overlaps();
+
+    assert_eq!(overlaps.len(), 2);
+    assert_eq!(overlaps[0].get_flash(), "FLASH");
+    assert_eq!(overlaps[0].get_first(), ("mcuboot", ".text"));
+    assert_eq!(overlaps[0].get_second(), ("app", ".text"));
+    assert_eq!(overlaps[0].get_address(), 0xc000);
+    assert_eq!(overlaps[0].get_size(), 0x100);
+    assert_eq!(overlaps[1].get_first(), ("app", ".text"));
+    assert_eq!(overlaps[1].get_second(), ("mcuboot", ".data"));
+    assert_eq!(overlaps[1].get_address(), 0xc100);
+    assert_eq!(
+        overlaps[1].to_string(),
+        "image overlap in FLASH at 0x0000c100 (256 bytes): app .text and mcuboot .data"
+    );
+}
@@ -0,0 +1,266 @@
+use parser::{
+    component::{ComponentRule, ComponentRules, Components, RuleError, RuleTarget},
+    xmlreader::XmlReader,
+    xmlwriter::{ToXmlWriter, XmlWriter},
+    Parser,
+};
+
+mod uthelper;
+use uthelper::*;
+use xml::{reader::XmlEvent, ParserConfig};
+
+const RULES: &str = "\
+# Team ownership
+drivers/wifi/** -> connectivity
+library: libbt*.a -> connectivity
+
+entry:.text.crypto_* -> security
+/app/*.o -> application
+";
+
+fn new_parser() -> Parser {
+    UTParserBuilder::new()
+        .segment(".text", 0x0800_0000, 0x100)
+        .entry(
+            UTEntryBuilder::new(".text.scan", 0x0800_0000, 0x40)
+                .object("build/drivers/wifi/scan.o"),
+        )
+        .entry(
+            UTEntryBuilder::new(".text.hci_send", 0x0800_0040, 0x30)
+                .object("hci.o")
+                .library("out/libbt_host.a"),
+        )
+        .entry(
+            UTEntryBuilder::new(".text.crypto_sha256", 0x0800_0070, 0x20)
+                .object("sha256.o")
+                .library("libcrypto.a"),
+        )
+        .entry(UTEntryBuilder::new(".text.main", 0x0800_0090, 0x10).object("/app/main.o"))
+        .entry(
+            UTEntryBuilder::new(".text.memcpy", 0x0800_00a0, 0x8)
+                .object("memcpy.o")
+                .library("libc.a"),
+        )
+        // Not allocated, not counted
+        .segment(".debug_info", 0x0, 0x1000)
+        .entry(UTEntryBuilder::new(".debug_info", 0x0, 0x1000).object("build/drivers/wifi/scan.o"))
+        .build()
+}
+
+#[test]
+fn rule_target() {
+    for target in [RuleTarget::Object, RuleTarget::Library, RuleTarget::Entry] {
+        assert_eq!(RuleTarget::from_name(target.as_str()), Some(target));
+    }
+    assert_eq!(RuleTarget::from_name("segment"), None);
+}
+
+#[test]
+fn parse_rules() {
+    let rules = ComponentRules::parse(RULES).unwrap();
+
+    let rules = rules
+        .get_rules()
+        .iter()
+        .map(|r| (r.get_target(), r.get_pattern(), r.get_component()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rules,
+        [
+            (RuleTarget::Object, "drivers/wifi/**", "connectivity"),
+            (RuleTarget::Library, "libbt*.a", "connectivity"),
+            (RuleTarget::Entry, ".text.crypto_*", "security"),
+            (RuleTarget::Object, "/app/*.o", "application")
+        ]
+    );
+}
+
+#[test]
+fn parse_invalid_rules() {
+    assert_eq!(
+        ComponentRules::parse("# rules\ndrivers/** connectivity").unwrap_err(),
+        RuleError::InvalidLine {
+            line: 2,
+            text: "drivers/** connectivity".to_string()
+        }
+    );
+    assert!(matches!(
+        ComponentRules::parse("drivers/** -> ").unwrap_err(),
+        RuleError::InvalidLine { line: 1, .. }
+    ));
+    assert_eq!(
+        ComponentRules::parse("segment:.text -> app").unwrap_err(),
+        RuleError::InvalidTarget {
+            line: 1,
+            target: "segment".to_string()
+        }
+    );
+    assert!(ComponentRules::parse("").unwrap().get_rules().is_empty());
+}
+
+#[test]
+fn glob_patterns() {
+    let entry = UTEntryBuilder::new(".text", 0x0, 0x4)
+        .object("build/drivers/wifi/scan.o")
+        .build();
+    let is_match = |pattern| ComponentRule::new(RuleTarget::Object, pattern, "c").is_match(&entry);
+
+    assert!(is_match("drivers/wifi/**"));
+    assert!(is_match("wifi/*.o"));
+    assert!(is_match("scan.o"));
+    assert!(is_match("**/scan.?"));
+    assert!(is_match("build/**/scan.o"));
+    assert!(is_match("build/**"));
+    assert!(!is_match("drivers/*.o"));
+    assert!(!is_match("/drivers/**"));
+    assert!(!is_match("can.o"));
+    assert!(!is_match("scan.obj"));
+
+    // Regex characters are literals
+    let entry = UTEntryBuilder::new(".text", 0x0, 0x4)
+        .object("lib+(x).o")
+        .build();
+    assert!(ComponentRule::new(RuleTarget::Object, "lib+(x).o", "c").is_match(&entry));
+
+    // Missing library does not match
+    assert!(!ComponentRule::new(RuleTarget::Library, "**", "c").is_match(&entry));
+}
+
+#[test]
+fn components() {
+    let rules = ComponentRules::parse(RULES).unwrap();
+    let components = Components::new(&new_parser(), &rules);
+
+    let sizes = components
+        .get_components()
+        .iter()
+        .map(|c| (c.get_name(), c.get_size(), c.get_entry_count()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sizes,
+        [
+            ("connectivity", 0x70, 2),
+            ("security", 0x20, 1),
+            ("application", 0x10, 1)
+        ]
+    );
+    assert_eq!(components.get_size("security"), Some(0x20));
+    assert_eq!(components.get_size("unknown"), None);
+    assert_eq!(components.get_unassigned_size(), 0x8);
+}
+
+#[test]
+fn first_rule_wins() {
+    let mut rules = ComponentRules::new();
+    rules.add_rule(ComponentRule::new(RuleTarget::Object, "**", "all"));
+    rules.add_rule(ComponentRule::new(RuleTarget::Object, "scan.o", "wifi"));
+
+    let components = Components::new(&new_parser(), &rules);
+
+    assert_eq!(components.get_size("all"), Some(0xa8));
+    // Rules without matching entries are reported
+    assert_eq!(components.get_size("wifi"), Some(0));
+    assert_eq!(components.get_unassigned_size(), 0);
+}
+
+#[test]
+fn xml_writer() {
+    let rules = ComponentRules::parse(RULES).unwrap();
+    let components = Components::new(&new_parser(), &rules);
+
+    let sink = UTSinkSource::new();
+    let mut writer = XmlWriter::new_empty(sink.clone());
+
+    components.to_xml_writer(&mut writer);
+
+    drop(writer);
+
+    let parser = ParserConfig::default()
+        .ignore_root_level_whitespace(true)
+        .trim_whitespace(true)
+        .create_reader(sink);
+
+    let mut names = vec![];
+    for event in parser {
+        if let XmlEvent::StartElement {
+            name, attributes, ..
+        } = event.unwrap()
+        {
+            match name.local_name.as_str() {
+                "components" => {
+                    assert_eq!(attributes[0].name.local_name, "count");
+                    assert_eq!(attributes[0].value, "3");
+                    assert_eq!(attributes[1].name.local_name, "unassigned_size");
+                    assert_eq!(attributes[1].value, "8");
+                }
+                "component" => {
+                    assert_eq!(attributes[0].name.local_name, "name");
+                    assert_eq!(attributes[1].name.local_name, "size");
+                    assert_eq!(attributes[2].name.local_name, "entries");
+                    names.push(attributes[0].value.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    assert_eq!(names, ["connectivity", "security", "application"]);
+}
+
+/// Writes `parser` into XML, with sizes per component if `rules` are set
+fn write_xml(parser: &Parser, rules: Option<ComponentRules>) -> UTSinkSource {
+    let sink = UTSinkSource::new();
+    let mut writer = XmlWriter::new(sink.clone(), "source");
+    if let Some(rules) = rules {
+        writer.set_component_rules(rules);
+    }
+    parser.to_xml_writer(&mut writer);
+    drop(writer);
+    sink
+}
+
+/// Returns names of the elements placed inside `<section name="MemoryMap">`
+fn memory_map_elements(sink: UTSinkSource) -> Vec<String> {
+    let reader = ParserConfig::default()
+        .ignore_root_level_whitespace(true)
+        .trim_whitespace(true)
+        .create_reader(sink);
+
+    let mut depth = 0;
+    let mut names = vec![];
+    for event in reader {
+        match event.unwrap() {
+            XmlEvent::StartElement { name, .. } => {
+                depth += 1;
+                // mapfile, section, element
+                if depth == 3 {
+                    names.push(name.local_name);
+                }
+            }
+            XmlEvent::EndElement { .. } => depth -= 1,
+            _ => {}
+        }
+    }
+    names
+}
+
+#[test]
+fn parser_xml_writer() {
+    let parser = new_parser();
+    let rules = ComponentRules::parse(RULES).unwrap();
+
+    let names = memory_map_elements(write_xml(&parser, None));
+    assert!(!names.contains(&"components".to_string()));
+
+    let names = memory_map_elements(write_xml(&parser, Some(rules.clone())));
+    assert_eq!(names.last().map(|n| n.as_str()), Some("components"));
+
+    // Components are computed, not read back
+    let read = XmlReader::new(write_xml(&parser, Some(rules)))
+        .read()
+        .unwrap();
+    assert_eq!(
+        read.get_memory_map_segments().len(),
+        parser.get_memory_map_segments().len()
+    );
+}
@@ -0,0 +1,13 @@
+#!/bin/sh
+# Builds the ELF images (with and without DWARF debug information) and mapfile used by the ELF tests
+# (x86_64 gcc/binutils)
+set -e
+cd "$(dirname "$0")"
+mkdir -p build
+CFLAGS="-g -fdebug-prefix-map=$PWD=. -Os -fno-pie -fno-asynchronous-unwind-tables -ffunction-sections -fdata-sections"
+gcc $CFLAGS -c src/app/main.c -o build/main.o
+gcc $CFLAGS -c src/drivers/led.c -o build/led.o
+gcc -static -nostdlib -no-pie -Wl,--build-id=none -Wl,-z,norelro -Wl,-z,max-page-size=0x1000 \
+    -Wl,-z,noseparate-code -Wl,-Map=firmware.map -o firmware.elf build/main.o build/led.o
+rm -r build
+strip --strip-debug -o firmware-nodebug.elf firmware.elf
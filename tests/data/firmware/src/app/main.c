@@ -0,0 +1,15 @@
+int counter = 1;
+static const char banner[] = "firmware";
+int led_toggle(int);
+
+int main(void)
+{
+    return led_toggle(counter) + banner[0];
+}
+
+void _start(void)
+{
+    counter = main();
+    for (;;) {
+    }
+}
@@ -0,0 +1,7 @@
+int led_state;
+
+int led_toggle(int value)
+{
+    led_state ^= value;
+    return led_state;
+}
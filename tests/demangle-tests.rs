@@ -0,0 +1,127 @@
+use parser::{
+    demangle::demangle,
+    entry::Entry,
+    symbol::{LinkerSymbol, SymbolKind},
+    xmlwriter::{ToXmlWriter, XmlWriter},
+};
+
+mod uthelper;
+use uthelper::*;
+use xml::{reader::XmlEvent, ParserConfig};
+
+#[test]
+fn demangle_cpp() {
+    assert_eq!(demangle("_ZN3foo3barEv"), Some("foo::bar()".to_string()));
+    assert_eq!(
+        demangle("_ZNK6Sensor4readEPhj"),
+        Some("Sensor::read(unsigned char*, unsigned int) const".to_string())
+    );
+}
+
+#[test]
+fn demangle_rust_legacy() {
+    assert_eq!(
+        demangle("_ZN4core3fmt5write17h0123456789abcdefE"),
+        Some("core::fmt::write".to_string())
+    );
+}
+
+#[test]
+fn demangle_rust_v0() {
+    assert_eq!(
+        demangle("_RNvCs1234_7mycrate3foo"),
+        Some("mycrate::foo".to_string())
+    );
+}
+
+#[test]
+fn demangle_section_prefix() {
+    assert_eq!(
+        demangle(".text._ZN3foo3barEv"),
+        Some(".text.foo::bar()".to_string())
+    );
+    assert_eq!(
+        demangle(".rodata._ZN4core3fmt5write17h0123456789abcdefE"),
+        Some(".rodata.core::fmt::write".to_string())
+    );
+}
+
+#[test]
+fn demangle_not_mangled() {
+    assert_eq!(demangle("main"), None);
+    assert_eq!(demangle(".text.main"), None);
+    assert_eq!(demangle(".text"), None);
+    assert_eq!(demangle("_Zinvalid"), None);
+}
+
+#[test]
+fn get_demangled_name() {
+    let entry = Entry::new(".text._ZN3foo3barEv", 0x1000, 0x10, "");
+    assert_eq!(
+        entry.get_demangled_name(),
+        Some(".text.foo::bar()".to_string())
+    );
+
+    let symbol = LinkerSymbol::new("_ZN3foo3barEv", 0x1000, SymbolKind::Definition);
+    assert_eq!(symbol.get_demangled_name(), Some("foo::bar()".to_string()));
+}
+
+/// Returns value of attribute `name` from the first element written by `item`
+fn get_xml_attribute<T: ToXmlWriter<UTSinkSource>>(
+    item: &T,
+    demangle: bool,
+    name: &str,
+) -> Option<String> {
+    let sink = UTSinkSource::new();
+    let mut writer = XmlWriter::new_empty(sink.clone());
+    writer.set_skip_data(true);
+    writer.set_demangle(demangle);
+
+    item.to_xml_writer(&mut writer);
+
+    drop(writer);
+
+    let mut parser = ParserConfig::default()
+        .ignore_root_level_whitespace(true)
+        .trim_whitespace(true)
+        .create_reader(sink);
+
+    check_start_document_event(parser.next().unwrap());
+
+    match parser.next().unwrap() {
+        XmlEvent::StartElement { attributes, .. } => attributes
+            .into_iter()
+            .find(|a| a.name.local_name == name)
+            .map(|a| a.value),
+        _ => panic!("Unexpected XML event!"),
+    }
+}
+
+#[test]
+fn xml_writer_entry() {
+    let entry = Entry::new(".text._ZN3foo3barEv", 0x1000, 0x10, "");
+
+    assert_eq!(get_xml_attribute(&entry, false, "demangled"), None);
+    assert_eq!(
+        get_xml_attribute(&entry, true, "demangled"),
+        Some(".text.foo::bar()".to_string())
+    );
+    assert_eq!(
+        get_xml_attribute(&entry, true, "name"),
+        Some(".text._ZN3foo3barEv".to_string())
+    );
+
+    let entry = Entry::new(".text.main", 0x1000, 0x10, "");
+    assert_eq!(get_xml_attribute(&entry, true, "demangled"), None);
+}
+
+#[test]
+fn xml_writer_symbol() {
+    let symbol = LinkerSymbol::new("_ZN3foo3barEv", 0x1000, SymbolKind::Definition);
+
+    assert_eq!(get_xml_attribute(&symbol, false, "demangled"), None);
+    assert_eq!(
+        get_xml_attribute(&symbol, true, "demangled"),
+        Some("foo::bar()".to_string())
+    );
+}
@@ -0,0 +1,179 @@
+use parser::{
+    diff::{Diff, DiffKind},
+    Parser,
+};
+
+const OLD_MAPFILE: &str = "\
+Linker script and memory map
+
+.text           0x0000000008000000       0x40
+ .text.main     0x0000000008000000       0x14 main.o
+ .text.foo      0x0000000008000014       0x2c libfoo.a(foo.o)
+
+.data           0x0000000020000000        0x8
+ .data.counter  0x0000000020000000        0x4 main.o
+ .data.old      0x0000000020000004        0x4 libold.a(old.o)
+";
+
+const NEW_MAPFILE: &str = "\
+Linker script and memory map
+
+.text           0x0000000008000000       0x50
+ .text.main     0x0000000008000000       0x10 main.o
+ .text.foo      0x0000000008000010       0x30 libfoo.a(foo.o)
+ .text.bar      0x0000000008000040       0x10 libfoo.a(bar.o)
+
+.data           0x0000000020000000        0x4
+ .data.counter  0x0000000020000000        0x4 main.o
+";
+
+fn new_diff() -> Diff {
+    Diff::new(&Parser::parse(OLD_MAPFILE), &Parser::parse(NEW_MAPFILE))
+}
+
+fn sizes(diff: &Diff, kind: DiffKind) -> Vec<(&str, Option<u64>, Option<u64>, i64)> {
+    diff.get_items_of_kind(kind)
+        .iter()
+        .map(|i| {
+            (
+                i.get_name(),
+                i.get_old_size(),
+                i.get_new_size(),
+                i.get_delta(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn segments() {
+    let diff = new_diff();
+
+    assert_eq!(
+        sizes(&diff, DiffKind::Segment),
+        [
+            (".data", Some(0x8), Some(0x4), -0x4),
+            (".text", Some(0x40), Some(0x50), 0x10)
+        ]
+    );
+    assert_eq!(diff.get_total_delta(DiffKind::Segment), 0xc);
+}
+
+#[test]
+fn objects_and_libraries() {
+    let diff = new_diff();
+
+    assert_eq!(
+        sizes(&diff, DiffKind::Object),
+        [
+            ("bar.o", None, Some(0x10), 0x10),
+            ("foo.o", Some(0x2c), Some(0x30), 0x4),
+            ("main.o", Some(0x18), Some(0x14), -0x4),
+            ("old.o", Some(0x4), None, -0x4)
+        ]
+    );
+    assert_eq!(
+        sizes(&diff, DiffKind::Library),
+        [
+            ("libfoo.a", Some(0x2c), Some(0x40), 0x14),
+            ("libold.a", Some(0x4), None, -0x4)
+        ]
+    );
+}
+
+#[test]
+fn entries() {
+    let diff = new_diff();
+    let entries = diff.get_items_of_kind(DiffKind::Entry);
+
+    assert_eq!(entries.len(), 5);
+    let main = entries
+        .iter()
+        .find(|i| i.get_name() == ".text.main")
+        .unwrap();
+    assert_eq!(main.get_segment_name(), Some(".text"));
+    assert_eq!(main.get_object_name(), Some("main.o"));
+    assert_eq!(main.get_delta(), -0x4);
+    assert_eq!(main.get_percent_change(), Some(-20.0));
+}
+
+#[test]
+fn added_and_removed() {
+    let diff = new_diff();
+
+    let added = diff
+        .get_added()
+        .iter()
+        .map(|i| (i.get_kind(), i.get_name()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        added,
+        [(DiffKind::Object, "bar.o"), (DiffKind::Entry, ".text.bar")]
+    );
+    assert!(diff.get_added().iter().all(|i| i.is_added()));
+    assert_eq!(diff.get_added()[0].get_percent_change(), None);
+
+    let removed = diff
+        .get_removed()
+        .iter()
+        .map(|i| (i.get_kind(), i.get_name()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        removed,
+        [
+            (DiffKind::Object, "old.o"),
+            (DiffKind::Library, "libold.a"),
+            (DiffKind::Entry, ".data.old")
+        ]
+    );
+    assert!(diff.get_removed().iter().all(|i| i.is_removed()));
+}
+
+#[test]
+fn same_mapfile() {
+    let parser = Parser::parse(OLD_MAPFILE);
+    let diff = Diff::new(&parser, &parser);
+
+    assert!(diff.get_added().is_empty());
+    assert!(diff.get_removed().is_empty());
+    assert!(diff.get_items().iter().all(|i| i.get_delta() == 0));
+    for kind in DiffKind::ALL {
+        assert_eq!(diff.get_total_delta(kind), 0);
+    }
+}
+
+#[test]
+fn same_object_in_libraries() {
+    let old = Parser::parse(
+        "\
+Linker script and memory map
+
+.text           0x0000000008000000       0x20
+ .text.init     0x0000000008000000       0x10 liba.a(init.o)
+ .text.init     0x0000000008000010       0x10 libb.a(init.o)
+",
+    );
+    let new = Parser::parse(
+        "\
+Linker script and memory map
+
+.text           0x0000000008000000       0x28
+ .text.init     0x0000000008000000       0x10 liba.a(init.o)
+ .text.init     0x0000000008000010       0x18 libb.a(init.o)
+",
+    );
+    let diff = Diff::new(&old, &new);
+
+    let entries = diff
+        .get_items_of_kind(DiffKind::Entry)
+        .iter()
+        .map(|i| (i.get_library_name(), i.get_object_name(), i.get_delta()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        [
+            (Some("liba.a"), Some("init.o"), 0),
+            (Some("libb.a"), Some("init.o"), 0x8)
+        ]
+    );
+}
@@ -0,0 +1,161 @@
+use parser::{
+    elf::{ElfCheck, ElfImage, ElfIssueKind},
+    symbol::{LinkerSymbol, SymbolKind},
+    Parser,
+};
+
+mod uthelper;
+use uthelper::*;
+
+/// ELF image and mapfile built by `data/firmware/build.sh`
+const ELF_IMAGE: &[u8] = include_bytes!("data/firmware/firmware.elf");
+const MAPFILE: &str = include_str!("data/firmware/firmware.map");
+
+fn new_image() -> ElfImage {
+    ElfImage::parse(ELF_IMAGE).unwrap()
+}
+
+#[test]
+fn sections() {
+    let image = new_image();
+
+    let sections = image
+        .get_sections()
+        .iter()
+        .filter(|s| s.is_allocated())
+        .map(|s| (s.get_name(), s.get_address(), s.get_size()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sections,
+        [
+            (".text", 0x4000e8, 0x2e),
+            (".data", 0x401118, 0x4),
+            (".bss", 0x40111c, 0x4)
+        ]
+    );
+
+    let comment = image.get_section(".comment").unwrap();
+    assert!(!comment.is_allocated());
+    assert!(image.get_section(".rodata").is_none());
+}
+
+#[test]
+fn symbols() {
+    let image = new_image();
+
+    let main = image.get_symbol("main").unwrap();
+    assert_eq!(main.get_address(), 0x4000e8);
+    assert_eq!(main.get_size(), 17);
+    assert_eq!(main.get_section_name(), Some(".text"));
+
+    let edata = image.get_symbol("_edata").unwrap();
+    assert_eq!(edata.get_size(), 0);
+
+    // Sorted by address
+    assert!(image
+        .get_symbols()
+        .windows(2)
+        .all(|s| s[0].get_address() <= s[1].get_address()));
+    // File symbols are skipped
+    assert!(image.get_symbol("main.c").is_none());
+}
+
+#[test]
+fn entry_symbols() {
+    let image = new_image();
+    let entry = UTEntryBuilder::new(".text._start", 0x4000f9, 0x1e).build();
+
+    let symbols = image
+        .get_entry_symbols(&entry)
+        .iter()
+        .map(|s| (s.get_name(), s.get_size()))
+        .collect::<Vec<_>>();
+    assert_eq!(symbols, [("_start", 14), ("led_toggle", 15)]);
+}
+
+#[test]
+fn invalid_image() {
+    assert!(ElfImage::parse(MAPFILE.as_bytes()).is_err());
+    assert!(ElfImage::open("data/firmware/missing.elf").is_err());
+}
+
+#[test]
+fn check_mapfile() {
+    let check = ElfCheck::new(&Parser::parse(MAPFILE), &new_image());
+
+    assert!(check.is_valid(), "{:?}", check.get_issues());
+}
+
+#[test]
+fn check_issues() {
+    let parser = UTParserBuilder::new()
+        .segment(".text", 0x4000e8, 0x30)
+        .entry(UTEntryBuilder::new(".text.main", 0x4000e8, 0x11))
+        .entry(UTEntryBuilder::new(".text.extra", 0x400110, 0x10))
+        .segment(".data", 0x401000, 0x4)
+        .segment(".rodata", 0x400120, 0x8)
+        // Not allocated, not checked
+        .segment(".comment", 0, 0x10)
+        .symbol(LinkerSymbol::new("main", 0x4000e8, SymbolKind::Definition))
+        .symbol(LinkerSymbol::new(
+            "led_toggle",
+            0x400100,
+            SymbolKind::Definition,
+        ))
+        .symbol(LinkerSymbol::new("_unused", 0x0, SymbolKind::Provide))
+        .build();
+
+    let check = ElfCheck::new(&parser, &new_image());
+    let issues = check
+        .get_issues()
+        .iter()
+        .map(|i| {
+            (
+                i.get_kind(),
+                i.get_name(),
+                i.get_map_value(),
+                i.get_elf_value(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        issues,
+        [
+            (ElfIssueKind::SizeMismatch, ".text", Some(0x30), Some(0x2e)),
+            (
+                ElfIssueKind::EntryOutOfSection,
+                ".text.extra",
+                Some(0x400110),
+                Some(0x4000e8)
+            ),
+            (
+                ElfIssueKind::AddressMismatch,
+                ".data",
+                Some(0x401000),
+                Some(0x401118)
+            ),
+            (ElfIssueKind::MissingSection, ".rodata", Some(0x8), None),
+            (ElfIssueKind::MissingSegment, ".bss", None, Some(0x4)),
+            (
+                ElfIssueKind::SymbolMismatch,
+                "led_toggle",
+                Some(0x400100),
+                Some(0x400107)
+            )
+        ]
+    );
+    assert!(!check.is_valid());
+
+    assert_eq!(
+        check.get_issues()[0].to_string(),
+        "size mismatch .text: 48 bytes in mapfile, 46 bytes in ELF"
+    );
+    assert_eq!(
+        check.get_issues()[4].to_string(),
+        "missing segment .bss: missing in mapfile, 4 bytes in ELF"
+    );
+    assert_eq!(
+        check.get_issues()[5].to_string(),
+        "symbol mismatch led_toggle: 0x00400100 in mapfile, 0x00400107 in ELF"
+    );
+}
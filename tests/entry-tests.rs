@@ -208,3 +208,20 @@ fn xml_writer_skip_data() {
 
     test_xml_output(&entry, true);
 }
+
+#[test]
+fn xml_writer_object_and_library_name() {
+    let entry_name = get_random_string(RAND_NAME_STRING_LEN);
+    let entry_data = get_random_string(RAND_DATA_STRING_LEN);
+    let object_name = get_random_string(RAND_NAME_STRING_LEN);
+    let library_name = get_random_string(RAND_NAME_STRING_LEN);
+    let entry_address = get_random_number(RAND_ADDRESS_MAX);
+    let entry_size = get_random_number(RAND_SIZE_MAX);
+
+    let mut entry = Entry::new(&entry_name, entry_address, entry_size, &entry_data);
+    entry.set_object_name(&object_name);
+    test_xml_output(&entry, true);
+
+    entry.set_library_name(&library_name);
+    test_xml_output(&entry, true);
+}
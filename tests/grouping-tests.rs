@@ -0,0 +1,230 @@
+use parser::{
+    grouping::{Category, Grouping},
+    xmlwriter::{ToXmlWriter, XmlWriter},
+    Parser,
+};
+
+mod uthelper;
+use uthelper::*;
+use xml::{reader::XmlEvent, ParserConfig};
+
+fn new_parser() -> Parser {
+    UTParserBuilder::new()
+        .segment(".text", 0x0800_0000, 0x48)
+        .entry(UTEntryBuilder::new(".text.main", 0x0800_0000, 0x20))
+        .entry(UTEntryBuilder::new(
+            ".text.unlikely.init_hw",
+            0x0800_0020,
+            0x10,
+        ))
+        .entry(UTEntryBuilder::new(".text.startup.main", 0x0800_0030, 0x8))
+        .entry(UTEntryBuilder::new(".text", 0x0800_0038, 0x4))
+        .entry(UTEntryBuilder::new(".text._ZN3foo3barEv", 0x0800_003c, 0xc))
+        .segment(".rodata", 0x0800_0100, 0x5a)
+        .entry(UTEntryBuilder::new(
+            ".rodata.lookup_table",
+            0x0800_0100,
+            0x40,
+        ))
+        .entry(UTEntryBuilder::new(".rodata.str1.1", 0x0800_0140, 0x12))
+        .entry(UTEntryBuilder::new(".data.rel.ro.vtable", 0x0800_0152, 0x8))
+        .segment(".data", 0x2000_0000, 0x14)
+        .entry(UTEntryBuilder::new(".data.counter", 0x2000_0000, 0x4))
+        .entry(UTEntryBuilder::new(
+            ".data.rel.local.handlers",
+            0x2000_0004,
+            0x10,
+        ))
+        .segment(".bss", 0x2000_0100, 0x124)
+        .entry(UTEntryBuilder::new(".bss.buffer", 0x2000_0100, 0x100))
+        .entry(UTEntryBuilder::new("COMMON", 0x2000_0200, 0x20))
+        .entry(UTEntryBuilder::new(".bss.counter", 0x2000_0220, 0x4))
+        .segment(".init_array", 0x0800_0200, 0x4)
+        .entry(UTEntryBuilder::new(".init_array", 0x0800_0200, 0x4))
+        .segment(".debug_info", 0x0, 0x1000)
+        .entry(UTEntryBuilder::new(".debug_info", 0x0, 0x1000))
+        .build()
+}
+
+fn find(grouping: &Grouping, name: &str, category: Category) -> Option<(u64, usize)> {
+    grouping
+        .get_groups()
+        .iter()
+        .find(|g| g.get_name() == name && g.get_category() == category)
+        .map(|g| (g.get_size(), g.get_entry_count()))
+}
+
+#[test]
+fn empty() {
+    let grouping = Grouping::new(&Parser::new());
+
+    assert!(grouping.get_groups().is_empty());
+    assert_eq!(grouping.get_categories().len(), Category::ALL.len());
+    for category in Category::ALL {
+        assert_eq!(grouping.get_category_size(category), 0);
+    }
+}
+
+#[test]
+fn groups() {
+    let grouping = Grouping::new(&new_parser());
+
+    assert_eq!(find(&grouping, "main", Category::Code), Some((0x28, 2)));
+    assert_eq!(find(&grouping, "init_hw", Category::Code), Some((0x10, 1)));
+    assert_eq!(find(&grouping, ".text", Category::Code), Some((0x4, 1)));
+    assert_eq!(
+        find(&grouping, "_ZN3foo3barEv", Category::Code),
+        Some((0xc, 1))
+    );
+    assert_eq!(
+        find(&grouping, "lookup_table", Category::ConstData),
+        Some((0x40, 1))
+    );
+    assert_eq!(
+        find(&grouping, ".rodata.str1.1", Category::ConstData),
+        Some((0x12, 1))
+    );
+    assert_eq!(
+        find(&grouping, "vtable", Category::ConstData),
+        Some((0x8, 1))
+    );
+    assert_eq!(
+        find(&grouping, "counter", Category::InitData),
+        Some((0x4, 1))
+    );
+    assert_eq!(
+        find(&grouping, "handlers", Category::InitData),
+        Some((0x10, 1))
+    );
+    assert_eq!(
+        find(&grouping, "buffer", Category::ZeroData),
+        Some((0x100, 1))
+    );
+    assert_eq!(
+        find(&grouping, "COMMON", Category::ZeroData),
+        Some((0x20, 1))
+    );
+    assert_eq!(
+        find(&grouping, "counter", Category::ZeroData),
+        Some((0x4, 1))
+    );
+    assert_eq!(
+        find(&grouping, ".init_array", Category::Other),
+        Some((0x4, 1))
+    );
+
+    // Non allocated segments are not grouped
+    assert_eq!(grouping.get_groups().len(), 13);
+
+    // Largest first
+    let sizes = grouping
+        .get_groups()
+        .iter()
+        .map(|g| g.get_size())
+        .collect::<Vec<_>>();
+    assert!(sizes.windows(2).all(|w| w[0] >= w[1]));
+}
+
+#[test]
+fn categories() {
+    let grouping = Grouping::new(&new_parser());
+
+    assert_eq!(grouping.get_category_size(Category::Code), 0x48);
+    assert_eq!(grouping.get_category_size(Category::ConstData), 0x5a);
+    assert_eq!(grouping.get_category_size(Category::InitData), 0x14);
+    assert_eq!(grouping.get_category_size(Category::ZeroData), 0x124);
+    assert_eq!(grouping.get_category_size(Category::Other), 0x4);
+
+    let categories = grouping
+        .get_categories()
+        .iter()
+        .map(|(c, _)| *c)
+        .collect::<Vec<_>>();
+    assert_eq!(categories, Category::ALL);
+}
+
+#[test]
+fn category_from_segment() {
+    let parser = UTParserBuilder::new()
+        .segment(".text", 0x0800_0000, 0x14)
+        .entry(UTEntryBuilder::new(".glue_7", 0x0800_0000, 0x4))
+        .entry(UTEntryBuilder::new(".text.main", 0x0800_0004, 0x10))
+        .build();
+
+    let grouping = Grouping::new(&parser);
+
+    assert_eq!(find(&grouping, ".glue_7", Category::Code), Some((0x4, 1)));
+    assert_eq!(find(&grouping, "main", Category::Code), Some((0x10, 1)));
+}
+
+#[test]
+fn xml_writer() {
+    let grouping = Grouping::new(&new_parser());
+
+    let sink = UTSinkSource::new();
+    let mut writer = XmlWriter::new_empty(sink.clone());
+    writer.set_demangle(true);
+
+    grouping.to_xml_writer(&mut writer);
+
+    drop(writer);
+
+    let parser = ParserConfig::default()
+        .ignore_root_level_whitespace(true)
+        .trim_whitespace(true)
+        .create_reader(sink);
+
+    let mut categories = 0;
+    let mut groups = 0;
+    let mut demangled = vec![];
+    for event in parser {
+        if let XmlEvent::StartElement {
+            name, attributes, ..
+        } = event.unwrap()
+        {
+            match name.local_name.as_str() {
+                "category" => categories += 1,
+                "group" => {
+                    groups += 1;
+                    assert_eq!(attributes[0].name.local_name, "name");
+                    assert_eq!(attributes[1].name.local_name, "category");
+                    assert_eq!(attributes[2].name.local_name, "size");
+                    assert_eq!(attributes[3].name.local_name, "entries");
+                    if let Some(attr) = attributes.get(4) {
+                        assert_eq!(attr.name.local_name, "demangled");
+                        demangled.push(attr.value.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    assert_eq!(categories, Category::ALL.len());
+    assert_eq!(groups, grouping.get_groups().len());
+    assert_eq!(demangled, vec!["foo::bar()".to_string()]);
+}
+
+#[test]
+fn parser_xml_writer() {
+    let has_grouping = |grouping: bool| {
+        let sink = UTSinkSource::new();
+        let mut writer = XmlWriter::new_empty(sink.clone());
+        writer.set_grouping(grouping);
+
+        new_parser().to_xml_writer(&mut writer);
+
+        drop(writer);
+
+        ParserConfig::default()
+            .create_reader(sink)
+            .into_iter()
+            .any(|event| {
+                matches!(event.unwrap(), XmlEvent::StartElement { name, .. } if name.local_name == "grouping")
+            })
+    };
+
+    // Grouping is optional, to keep default output small
+    assert!(!has_grouping(false));
+    assert!(has_grouping(true));
+}
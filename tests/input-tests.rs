@@ -0,0 +1,153 @@
+use parser::{
+    input::{Compression, Input, InputError},
+    Parser,
+};
+use std::io::Write;
+
+const MAPFILE: &str = "\
+Linker script and memory map
+
+.text           0x0000000008000000       0x40
+ .text.main     0x0000000008000000       0x40 main.o
+";
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[cfg(feature = "xz")]
+fn xz(data: &[u8]) -> Vec<u8> {
+    let mut encoder = xz2::write::XzEncoder::new(vec![], 6);
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[cfg(feature = "zstd")]
+fn zstd(data: &[u8]) -> Vec<u8> {
+    zstd::encode_all(data, 0).unwrap()
+}
+
+fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+    for (name, data) in files {
+        writer
+            .start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(data).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn detect() {
+    assert_eq!(Compression::detect(MAPFILE.as_bytes()), Compression::None);
+    assert_eq!(Compression::detect(&gzip(b"")), Compression::Gzip);
+    #[cfg(feature = "xz")]
+    assert_eq!(Compression::detect(&xz(b"")), Compression::Xz);
+    #[cfg(feature = "zstd")]
+    assert_eq!(Compression::detect(&zstd(b"")), Compression::Zstd);
+    assert_eq!(Compression::detect(&zip(&[])), Compression::Zip);
+    assert_eq!(Compression::detect(b""), Compression::None);
+}
+
+#[test]
+fn decompress() {
+    for (name, data, compression) in [
+        ("app.map", MAPFILE.as_bytes().to_vec(), Compression::None),
+        ("app.map.gz", gzip(MAPFILE.as_bytes()), Compression::Gzip),
+        #[cfg(feature = "xz")]
+        ("app.map.xz", xz(MAPFILE.as_bytes()), Compression::Xz),
+        #[cfg(feature = "zstd")]
+        ("app.map.zst", zstd(MAPFILE.as_bytes()), Compression::Zstd),
+        (
+            "app.zip",
+            zip(&[("out/app.map", MAPFILE.as_bytes())]),
+            Compression::Zip,
+        ),
+    ] {
+        let input = Input::parse(name, data).unwrap();
+        assert_eq!(input.get_compression(), compression);
+        assert_eq!(input.get_text(), MAPFILE);
+        assert!(!input.is_xml());
+
+        let parser = Parser::parse(input.get_text());
+        assert_eq!(parser.get_memory_map_segments()[0].get_size(), Some(0x40));
+    }
+}
+
+#[test]
+fn input_name() {
+    let name = |name, data| Input::parse(name, data).unwrap().get_name().to_string();
+
+    assert_eq!(name("app.map.gz", gzip(b"")), "app.map");
+    #[cfg(feature = "zstd")]
+    assert_eq!(name("build/app.xml.ZST", zstd(b"")), "build/app.xml");
+    // Extension is kept if it does not match the compression
+    assert_eq!(name("app.map.xz", gzip(b"")), "app.map.xz");
+    #[cfg(feature = "xz")]
+    assert_eq!(name("-", xz(b"")), "-");
+    assert_eq!(
+        name("maps.zip", zip(&[("app.map", b""), ("README.txt", b"")])),
+        "app.map"
+    );
+}
+
+#[test]
+fn xml_detection() {
+    let input = Input::parse("app.xml.gz", gzip(b"<?xml version=\"1.0\"?>")).unwrap();
+    assert!(input.is_xml());
+
+    // Standard input has no extension, so the content is checked
+    assert!(Input::parse("-", b"\n<?xml version=\"1.0\"?>".to_vec())
+        .unwrap()
+        .is_xml());
+    assert!(!Input::parse("-", MAPFILE.as_bytes().to_vec())
+        .unwrap()
+        .is_xml());
+    assert!(!Input::parse("app.map", b"<".to_vec()).unwrap().is_xml());
+}
+
+#[test]
+fn errors() {
+    assert!(matches!(
+        Input::parse("app.map", vec![0xff, 0xfe, 0x00]),
+        Err(InputError::NotText)
+    ));
+
+    let mut data = gzip(MAPFILE.as_bytes());
+    data.truncate(data.len() / 2);
+    assert!(matches!(
+        Input::parse("app.map.gz", data),
+        Err(InputError::Io(_))
+    ));
+
+    let error = Input::parse("maps.zip", zip(&[("a.map", b""), ("b.map", b"")])).unwrap_err();
+    assert!(matches!(&error, InputError::ZipContent { names } if names == &["a.map", "b.map"]));
+    assert!(matches!(
+        Input::parse("maps.zip", zip(&[])),
+        Err(InputError::ZipContent { names }) if names.is_empty()
+    ));
+    assert_eq!(
+        error.to_string(),
+        "Zip archive must contain a single file or a single .map/.xml file, found [a.map, b.map]"
+    );
+
+    assert!(matches!(
+        Input::open("missing.map.gz"),
+        Err(InputError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound
+    ));
+}
+
+#[test]
+#[cfg(not(feature = "xz"))]
+fn unsupported() {
+    // xz magic number
+    let error = Input::parse("app.map.xz", vec![0xfd, b'7', b'z', b'X', b'Z', 0x00]).unwrap_err();
+    assert!(matches!(error, InputError::Unsupported(Compression::Xz)));
+    assert_eq!(
+        error.to_string(),
+        "xz input is not supported, enable 'xz' feature"
+    );
+}
@@ -0,0 +1,108 @@
+use parser::{
+    lookup::AddressIndex,
+    symbol::{LinkerSymbol, SymbolKind},
+    Parser,
+};
+
+mod uthelper;
+use uthelper::*;
+
+fn new_parser() -> Parser {
+    let mut symbol = LinkerSymbol::new("_stext", 0x1000, SymbolKind::Assignment);
+    symbol.set_expression(".");
+
+    UTParserBuilder::new()
+        .segment(".text", 0x1000, 0x100)
+        .entry(
+            UTEntryBuilder::new(".text.a", 0x1000, 0x40)
+                .object(".text.a.o")
+                .library("lib.a"),
+        )
+        .entry(
+            UTEntryBuilder::new(".text.b", 0x1040, 0x0)
+                .object(".text.b.o")
+                .library("lib.a"),
+        )
+        .entry(
+            UTEntryBuilder::new(".text.c", 0x1040, 0xc0)
+                .object(".text.c.o")
+                .library("lib.a"),
+        )
+        // Overlaps .text.c
+        .entry(
+            UTEntryBuilder::new(".text.d", 0x1080, 0x10)
+                .object(".text.d.o")
+                .library("lib.a"),
+        )
+        .segment(".debug_info", 0x1000, 0x100)
+        .entry(
+            UTEntryBuilder::new(".debug_info", 0x1000, 0x100)
+                .object(".debug_info.o")
+                .library("lib.a"),
+        )
+        .symbol(symbol)
+        .symbol(LinkerSymbol::new("a", 0x1000, SymbolKind::Definition))
+        .symbol(LinkerSymbol::new(
+            "a_helper",
+            0x1020,
+            SymbolKind::Definition,
+        ))
+        .symbol(LinkerSymbol::new("c", 0x1040, SymbolKind::Definition))
+        .build()
+}
+
+#[test]
+fn empty() {
+    let parser = Parser::new();
+    let index = AddressIndex::new(&parser);
+
+    assert!(index.is_empty());
+    assert_eq!(index.lookup(0), None);
+}
+
+#[test]
+fn index() {
+    let parser = new_parser();
+    let index = AddressIndex::new(&parser);
+
+    // Zero sized entries and not allocated segments are not indexed
+    assert_eq!(index.len(), 3);
+}
+
+#[test]
+fn lookup() {
+    let parser = new_parser();
+    let index = AddressIndex::new(&parser);
+
+    // Outside of all entries
+    assert_eq!(index.lookup(0xfff), None);
+    assert_eq!(index.lookup(0x1100), None);
+
+    // Prefers symbol definitions over assignments at the same address
+    let info = index.lookup(0x1000).unwrap();
+    assert_eq!(info.get_address(), 0x1000);
+    assert_eq!(info.get_segment().get_name(), ".text");
+    assert_eq!(info.get_entry().get_name(), ".text.a");
+    assert_eq!(info.get_entry().get_object_name(), Some(".text.a.o"));
+    assert_eq!(info.get_entry().get_library_name(), Some("lib.a"));
+    assert_eq!(info.get_symbol().unwrap().get_name(), "a");
+    assert_eq!(info.get_offset(), 0);
+
+    // Nearest symbol before address
+    let info = index.lookup(0x1024).unwrap();
+    assert_eq!(info.get_entry().get_name(), ".text.a");
+    assert_eq!(info.get_symbol().unwrap().get_name(), "a_helper");
+    assert_eq!(info.get_offset(), 4);
+
+    // Smallest entry that contains the address, symbol must be inside entry
+    let info = index.lookup(0x1084).unwrap();
+    assert_eq!(info.get_entry().get_name(), ".text.d");
+    assert_eq!(info.get_symbol(), None);
+    assert_eq!(info.get_offset(), 4);
+
+    // Overlapping entry that starts before a smaller one
+    let info = index.lookup(0x10a0).unwrap();
+    assert_eq!(info.get_entry().get_name(), ".text.c");
+    assert_eq!(info.get_symbol().unwrap().get_name(), "c");
+    assert_eq!(info.get_offset(), 0x60);
+}
@@ -0,0 +1,161 @@
+use parser::{
+    padding::Padding,
+    xmlwriter::{ToXmlWriter, XmlWriter},
+    Parser,
+};
+
+mod uthelper;
+use uthelper::*;
+use xml::{reader::XmlEvent, ParserConfig};
+
+fn new_parser() -> Parser {
+    UTParserBuilder::new()
+        .region("FLASH", 0x0800_0000, 0x1_0000)
+        .region("RAM", 0x2000_0000, 0x4000)
+        .segment(".text", 0x0800_0000, 0x40)
+        .entry(
+            UTEntryBuilder::new(".text.a", 0x0800_0000, 0x12)
+                .object("a.o")
+                .fill(0x0800_0012, 0x2),
+        )
+        .entry(
+            UTEntryBuilder::new(".text.b", 0x0800_0014, 0x24)
+                .object("b.o")
+                .fill(0x0800_0038, 0x8),
+        )
+        .entry(UTEntryBuilder::new(".text.c", 0x0800_0040, 0x0).object("a.o"))
+        .segment(".data", 0x2000_0000, 0x10)
+        .load_address(0x0800_0040)
+        .entry(
+            UTEntryBuilder::new(".data.a", 0x2000_0000, 0xc)
+                .object("a.o")
+                .fill(0x2000_000c, 0x4),
+        )
+        .segment(".bss", 0x2000_0010, 0x10)
+        .entry(
+            UTEntryBuilder::new(".bss", 0x2000_0010, 0x0)
+                .object("c.o")
+                .fill(0x2000_0010, 0x10),
+        )
+        .build()
+}
+
+#[test]
+fn empty() {
+    let padding = Padding::new(&Parser::new());
+
+    assert_eq!(padding.get_total_size(), 0);
+    assert!(padding.get_segments().is_empty());
+    assert!(padding.get_objects().is_empty());
+    assert!(padding.get_regions().is_empty());
+    assert!(padding.get_gaps().is_empty());
+}
+
+#[test]
+fn totals() {
+    let padding = Padding::new(&new_parser());
+
+    assert_eq!(padding.get_total_size(), 0x2 + 0x8 + 0x4 + 0x10);
+    assert_eq!(
+        padding.get_segments(),
+        [
+            (".text".to_string(), 0xa),
+            (".data".to_string(), 0x4),
+            (".bss".to_string(), 0x10)
+        ]
+    );
+    assert_eq!(
+        padding.get_objects(),
+        [
+            ("c.o".to_string(), 0x10),
+            ("b.o".to_string(), 0x8),
+            ("a.o".to_string(), 0x6)
+        ]
+    );
+    // .data is counted in FLASH (LMA) and RAM (VMA)
+    assert_eq!(
+        padding.get_regions(),
+        [("FLASH".to_string(), 0xe), ("RAM".to_string(), 0x14)]
+    );
+}
+
+#[test]
+fn gaps() {
+    let padding = Padding::new(&new_parser());
+    let gaps = padding.get_gaps();
+
+    assert_eq!(gaps.len(), 4);
+
+    let sizes = gaps.iter().map(|g| g.get_size()).collect::<Vec<_>>();
+    assert_eq!(sizes, [0x10, 0x8, 0x4, 0x2]);
+
+    // Overlapping fill starts at entry address
+    assert_eq!(gaps[0].get_entry_name(), ".bss");
+    assert_eq!(gaps[0].get_address(), 0x2000_0010);
+    assert_eq!(gaps[0].get_next_entry_name(), None);
+
+    // Fill starts after entry
+    assert_eq!(gaps[1].get_segment_name(), ".text");
+    assert_eq!(gaps[1].get_entry_name(), ".text.b");
+    assert_eq!(gaps[1].get_next_entry_name(), Some(".text.c"));
+    assert_eq!(gaps[1].get_object_name(), Some("b.o"));
+    assert_eq!(gaps[1].get_address(), 0x0800_0038);
+}
+
+#[test]
+fn xml_writer() {
+    let padding = Padding::new(&new_parser());
+
+    let sink = UTSinkSource::new();
+    let mut writer = XmlWriter::new_empty(sink.clone());
+
+    padding.to_xml_writer(&mut writer);
+
+    drop(writer);
+
+    let parser = ParserConfig::default()
+        .ignore_root_level_whitespace(true)
+        .trim_whitespace(true)
+        .create_reader(sink);
+
+    let mut gaps = 0;
+    for event in parser {
+        if let XmlEvent::StartElement {
+            name, attributes, ..
+        } = event.unwrap()
+        {
+            if name.local_name == "padding" {
+                assert_eq!(attributes[0].name.local_name, "size");
+                assert_eq!(attributes[0].value, padding.get_total_size().to_string());
+            } else if name.local_name == "gap" {
+                gaps += 1;
+            }
+        }
+    }
+
+    assert_eq!(gaps, padding.get_gaps().len());
+}
+
+#[test]
+fn parser_xml_writer() {
+    let has_padding = |padding: bool| {
+        let sink = UTSinkSource::new();
+        let mut writer = XmlWriter::new_empty(sink.clone());
+        writer.set_padding(padding);
+
+        new_parser().to_xml_writer(&mut writer);
+
+        drop(writer);
+
+        ParserConfig::default()
+            .create_reader(sink)
+            .into_iter()
+            .any(|event| {
+                matches!(event.unwrap(), XmlEvent::StartElement { name, .. } if name.local_name == "padding")
+            })
+    };
+
+    // Padding is optional, to keep default output small
+    assert!(!has_padding(false));
+    assert!(has_padding(true));
+}
@@ -1,8 +1,10 @@
 use parser::{
     entry::Entry,
+    region::Region,
     segment::Segment,
+    symbol::SymbolKind,
     xmlwriter::{ToXmlWriter, XmlWriter},
-    Parser, Section,
+    Parser, Section, SortKey,
 };
 
 mod uthelper;
@@ -215,6 +217,271 @@ fn parse_segment_info_fn() {
     segment_sub_test(true, true);
 }
 
+#[test]
+fn parse_segment_load_address_fn() {
+    let segment_name = get_random_string(14);
+    let segment_address = get_random_number(0xFFFFFFFFFFFFFFFF);
+    let segment_size = get_random_number(0xFFFFFFFF);
+    let segment_load_address = get_random_number(0xFFFFFFFFFFFFFFFF);
+    let segment_size_str = format!("{:#x}", segment_size);
+
+    let single_line = format!(
+        "{:15} {:#016x} {:>10} load address {:#016x}",
+        &segment_name, segment_address, segment_size_str, segment_load_address
+    );
+    let multi_line = format!(
+        "{}\n                {:#016x} {:>10} load address {:#016x}",
+        &segment_name, segment_address, segment_size_str, segment_load_address
+    );
+
+    for segment_str in [single_line, multi_line] {
+        let segment = Parser::parse_segment_info(&segment_str).unwrap();
+        assert_eq!(segment.get_name(), &segment_name);
+        assert_eq!(segment.get_address(), Some(segment_address));
+        assert_eq!(segment.get_size(), Some(segment_size));
+        assert_eq!(segment.get_load_address(), Some(segment_load_address));
+    }
+
+    // Segments loaded at their own address have no load address
+    let segment_str = format!(
+        "{:15} {:#016x} {:>10}",
+        &segment_name,
+        segment_address,
+        format!("{:#x}", segment_size)
+    );
+    let segment = Parser::parse_segment_info(&segment_str).unwrap();
+    assert_eq!(segment.get_load_address(), None);
+}
+
+#[test]
+fn parse_region_info_fn() {
+    // Random string, should return None
+    let line = get_random_string(RAND_DATA_STRING_LEN);
+    assert_eq!(Parser::parse_region_info(&line), None);
+
+    // Header line, should return None
+    let header = "Name             Origin             Length             Attributes";
+    assert_eq!(Parser::parse_region_info(header), None);
+
+    // Catch-all region, should return None
+    let default = "*default*        0x0000000000000000 0xffffffffffffffff";
+    assert_eq!(Parser::parse_region_info(default), None);
+
+    let name = get_random_string(10);
+    let origin = get_random_number(RAND_ADDRESS_MAX);
+    let length = get_random_number(RAND_SIZE_MAX);
+
+    let line = format!("{:16} {:#018x} {:#018x}", name, origin, length);
+    let region = Parser::parse_region_info(&line).unwrap();
+    assert_eq!(region.get_name(), name);
+    assert_eq!(region.get_origin(), origin);
+    assert_eq!(region.get_length(), length);
+    assert_eq!(region.get_attributes(), None);
+
+    let line = format!("{:16} {:#018x} {:#018x} xr", name, origin, length);
+    let region = Parser::parse_region_info(&line).unwrap();
+    assert_eq!(region.get_name(), name);
+    assert_eq!(region.get_origin(), origin);
+    assert_eq!(region.get_length(), length);
+    assert_eq!(region.get_attributes(), Some("xr"));
+}
+
+#[test]
+fn parse_load_address_fn() {
+    let data = "\
+Memory Configuration
+
+Name             Origin             Length             Attributes
+FLASH            0x0000000008000000 0x0000000000010000 xr
+RAM              0x0000000020000000 0x0000000000004000 xrw
+*default*        0x0000000000000000 0xffffffffffffffff
+
+Linker script and memory map
+
+.text           0x0000000008000000      0x100
+ .text          0x0000000008000000      0x100 main.o
+
+.data           0x0000000020000000       0x20 load address 0x0000000008000100
+ .data          0x0000000020000000       0x20 main.o
+
+.bss            0x0000000020000020       0x40
+ .bss           0x0000000020000020       0x40 main.o
+
+.debug_info     0x0000000000000000      0x200
+ .debug_info    0x0000000000000000      0x200 main.o
+";
+
+    let parser = Parser::parse(data);
+
+    let regions = parser.get_memory_regions();
+    assert_eq!(regions.len(), 2);
+
+    assert_eq!(regions[0].get_name(), "FLASH");
+    assert_eq!(regions[0].get_attributes(), Some("xr"));
+    // .text + .data (LMA)
+    assert_eq!(regions[0].get_used_size(), 0x120);
+
+    assert_eq!(regions[1].get_name(), "RAM");
+    assert_eq!(regions[1].get_attributes(), Some("xrw"));
+    // .data (VMA) + .bss
+    assert_eq!(regions[1].get_used_size(), 0x60);
+
+    let segments = parser.get_memory_map_segments();
+    assert_eq!(segments.len(), 4);
+    assert_eq!(segments[0].get_load_address(), None);
+    assert_eq!(segments[1].get_load_address(), Some(0x8000100));
+    assert_eq!(segments[2].get_load_address(), None);
+}
+
+#[test]
+fn parse_symbols_fn() {
+    // Random string, should be empty
+    let line = get_random_string(RAND_DATA_STRING_LEN);
+    assert!(Parser::parse_symbols(&line).is_empty());
+
+    // Location counter, assertion, not referenced PROVIDE and data statements are not symbols
+    assert_eq!(
+        Parser::parse_symbol_info(
+            "                0x0000000020000400                . = ALIGN (0x4)"
+        ),
+        None
+    );
+    assert_eq!(
+        Parser::parse_symbol_info(
+            "                0x0000000000000001                ASSERT ((_Min_Stack_Size > 0x100), \"error\")"
+        ),
+        None
+    );
+    assert_eq!(
+        Parser::parse_symbol_info(
+            "                [!provide]                        PROVIDE (end = .)"
+        ),
+        None
+    );
+    assert_eq!(
+        Parser::parse_symbol_info("                0x0000000008000000        0x4 LONG 0x0"),
+        None
+    );
+
+    let symbol =
+        Parser::parse_symbol_info("                0x0000000020000000                _sdata = .")
+            .unwrap();
+    assert_eq!(symbol.get_name(), "_sdata");
+    assert_eq!(symbol.get_address(), 0x20000000);
+    assert_eq!(symbol.get_kind(), SymbolKind::Assignment);
+    assert_eq!(symbol.get_expression(), Some("."));
+
+    let symbol = Parser::parse_symbol_info(
+        "                0x0000000020004000                PROVIDE (_estack = (ORIGIN (RAM) + LENGTH (RAM)))",
+    )
+    .unwrap();
+    assert_eq!(symbol.get_name(), "_estack");
+    assert_eq!(symbol.get_address(), 0x20004000);
+    assert_eq!(symbol.get_kind(), SymbolKind::Provide);
+    assert_eq!(
+        symbol.get_expression(),
+        Some("(ORIGIN (RAM) + LENGTH (RAM))")
+    );
+
+    let name = get_random_string(RAND_NAME_STRING_LEN);
+    let address = get_random_number(RAND_ADDRESS_MAX);
+    let line = format!("                {:#018x}                {}", address, name);
+    let symbol = Parser::parse_symbol_info(&line).unwrap();
+    assert_eq!(symbol.get_name(), name);
+    assert_eq!(symbol.get_address(), address);
+    assert_eq!(symbol.get_kind(), SymbolKind::Definition);
+    assert_eq!(symbol.get_expression(), None);
+}
+
+#[test]
+fn parse_symbols_in_memory_map_fn() {
+    let data = "\
+Linker script and memory map
+
+                0x0000000000000400                _Min_Stack_Size = 0x400
+
+.text           0x0000000008000000      0x100
+ .text          0x0000000008000000      0x100 main.o
+                0x0000000008000000                main
+                0x0000000008000080                foo
+
+.bss            0x0000000020000000      0x100
+                0x0000000020000000                __heap_start = .
+ .bss           0x0000000020000000      0x100 main.o
+                0x0000000020000100                __heap_end = .
+                0x0000000020004000                PROVIDE (_estack = (ORIGIN (RAM) + LENGTH (RAM)))
+";
+
+    let parser = Parser::parse(data);
+
+    assert_eq!(parser.get_memory_map_segments().len(), 2);
+
+    let names = parser
+        .get_memory_map_symbols()
+        .iter()
+        .map(|s| s.get_name())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "_Min_Stack_Size",
+            "main",
+            "foo",
+            "__heap_start",
+            "__heap_end",
+            "_estack"
+        ]
+    );
+
+    assert_eq!(
+        parser
+            .get_memory_map_symbol("_estack")
+            .unwrap()
+            .get_address(),
+        0x20004000
+    );
+    assert_eq!(parser.get_memory_map_symbol("missing"), None);
+    assert_eq!(
+        parser.get_memory_map_symbols_distance("__heap_start", "__heap_end"),
+        Some(0x100)
+    );
+    assert_eq!(
+        parser.get_memory_map_symbols_distance("__heap_end", "__heap_start"),
+        None
+    );
+    assert_eq!(
+        parser.get_memory_map_symbols_distance("__heap_start", "missing"),
+        None
+    );
+}
+
+#[test]
+fn add_region_fn() {
+    let mut parser = Parser::new();
+
+    let mut segment = Segment::new(".text");
+    segment.set_size_and_address(0x100, 0x1000);
+    parser.add_segment(segment);
+
+    // Region added after segment should still account it
+    parser.add_region(Region::new("FLASH", 0x1000, 0x1000));
+    assert_eq!(parser.get_memory_regions()[0].get_used_size(), 0x100);
+
+    let mut segment = Segment::new(".rodata");
+    segment.set_size_and_address(0x10, 0x1100);
+    parser.add_segment(segment);
+    assert_eq!(parser.get_memory_regions()[0].get_used_size(), 0x110);
+
+    // Outside of region
+    let mut segment = Segment::new(".bss");
+    segment.set_size_and_address(0x10, 0x2000);
+    parser.add_segment(segment);
+    assert_eq!(parser.get_memory_regions()[0].get_used_size(), 0x110);
+
+    parser.clear();
+    assert!(parser.get_memory_regions().is_empty());
+}
+
 #[test]
 fn split_segment_fn() {
     let mut pos: usize = 0;
@@ -293,7 +560,6 @@ fn split_segment_fn() {
     test_str += &format!(" *(SORT_BY_ALIGNMENT({}))\n", get_random_string(15));
 
     split_vec.push((pos, test_str.len() - 1));
-    pos = test_str.len();
 
     assert_eq!(Parser::split_segment(&test_str), split_vec);
 }
@@ -365,6 +631,45 @@ fn add_segment_fn() {
     );
 }
 
+#[test]
+fn sorted_objects() {
+    let mut parser = Parser::new();
+
+    let mut text = Segment::new(".text");
+    let mut position = 0x0800_0000;
+    for (object, size) in [("c.o", 0x10), ("a.o", 0x4), ("b.o", 0x20)] {
+        let mut entry = Entry::new(".text", position, size, "");
+        entry.set_object_name(object);
+        text.add_entry(entry);
+        position += size;
+    }
+    text.set_size_and_address(position - 0x0800_0000, 0x0800_0000);
+    parser.add_segment(text);
+
+    // Not allocated, only counted in size
+    let mut debug = Segment::new(".debug_info");
+    let mut entry = Entry::new(".debug_info", 0, 0x100, "");
+    entry.set_object_name("d.o");
+    debug.add_entry(entry);
+    parser.add_segment(debug);
+
+    let names = |key| {
+        parser
+            .get_sorted_objects(key)
+            .iter()
+            .map(|o| o.get_name())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(SortKey::Name), ["a.o", "b.o", "c.o", "d.o"]);
+    assert_eq!(names(SortKey::Size), ["d.o", "b.o", "c.o", "a.o"]);
+    assert_eq!(names(SortKey::Address), ["c.o", "a.o", "b.o", "d.o"]);
+
+    for key in [SortKey::Name, SortKey::Size, SortKey::Address] {
+        assert_eq!(SortKey::from_name(key.as_str()), Some(key));
+    }
+    assert_eq!(SortKey::from_name("entries"), None);
+}
+
 #[test]
 fn test_xml_output() {
     let sink = UTSinkSource::new();
@@ -407,3 +712,18 @@ fn test_xml_output() {
 
     check_end_document_event(parser.next().unwrap());
 }
+
+#[test]
+fn allocated_segments() {
+    let parser = UTParserBuilder::new()
+        .segment(".text", 0x1000, 0x10)
+        .segment(".debug_info", 0x0, 0x20)
+        .segment(".data", 0x2000, 0x8)
+        .build();
+
+    let names = parser
+        .get_allocated_segments()
+        .map(|s| s.get_name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, [".text", ".data"]);
+}
@@ -0,0 +1,252 @@
+use parser::{
+    query::{Query, QueryError, QuerySource, Value},
+    Parser,
+};
+
+const MAPFILE: &str = "\
+Linker script and memory map
+
+.text           0x0000000008000000      0x900
+ .text.main     0x0000000008000000       0x80 main.o
+ .text.memcpy   0x0000000008000080      0x500 libc.a(memcpy.o)
+ .text.printf   0x0000000008000580      0x400 libc.a(printf.o)
+ .text.sin      0x0000000008000980       0x10 libm.a(sin.o)
+
+.data           0x0000000020000000       0x10 load address 0x0000000008000990
+ .data.counter  0x0000000020000000        0x4 main.o
+ .data.errno    0x0000000020000004        0xc libc.a(errno.o)
+";
+
+/// Returns first column (name) of query `text` rows
+fn names(text: &str) -> Vec<String> {
+    Query::parse(text)
+        .unwrap()
+        .run(&Parser::parse(MAPFILE))
+        .get_rows()
+        .iter()
+        .map(|row| match &row[0] {
+            Value::Text(name) => name.clone(),
+            value => panic!("Unexpected name {:?}", value),
+        })
+        .collect()
+}
+
+#[test]
+fn query_source() {
+    for source in [
+        QuerySource::Segments,
+        QuerySource::Entries,
+        QuerySource::Objects,
+        QuerySource::Libraries,
+    ] {
+        assert_eq!(QuerySource::from_name(source.as_str()), Some(source));
+        assert_eq!(source.get_fields()[0].0, "name");
+    }
+    assert_eq!(QuerySource::from_name("regions"), None);
+}
+
+#[test]
+fn filter() {
+    assert_eq!(
+        names(
+            r#"entries where segment =~ "^\.text" and size > 1024 and library = "libc.a" order by size desc limit 20"#
+        ),
+        [".text.memcpy"]
+    );
+    assert_eq!(
+        names("entries where library != \"libc.a\" and not (size >= 0x80)"),
+        [".text.sin", ".data.counter"]
+    );
+    assert_eq!(
+        names("entries where name =~ \"main|sin\" or address < 0x8000080"),
+        [".text.main", ".text.sin"]
+    );
+    // Missing library only matches != and !~
+    assert_eq!(
+        names("entries where library !~ \"^lib\""),
+        [".text.main", ".data.counter"]
+    );
+    assert!(names("entries where library < \"zzz\" and object = \"main.o\"").is_empty());
+}
+
+#[test]
+fn order_and_limit() {
+    assert_eq!(
+        names("ENTRIES ORDER BY size LIMIT 2"),
+        [".data.counter", ".data.errno"]
+    );
+    assert_eq!(
+        names("entries where segment = \".text\" order by size desc"),
+        [".text.memcpy", ".text.printf", ".text.main", ".text.sin"]
+    );
+    assert_eq!(names("segments order by name asc"), [".data", ".text"]);
+    assert!(names("segments limit 0").is_empty());
+}
+
+#[test]
+fn sources() {
+    let parser = Parser::parse(MAPFILE);
+    let run = |text| Query::parse(text).unwrap().run(&parser);
+
+    let result = run("segments where name = \".data\"");
+    assert_eq!(
+        result.get_columns(),
+        ["name", "address", "load_address", "size", "entries"]
+    );
+    assert_eq!(
+        result.get_rows(),
+        [vec![
+            Value::Text(".data".to_string()),
+            Value::Number(0x2000_0000),
+            Value::Number(0x0800_0990),
+            Value::Number(0x10),
+            Value::Number(2)
+        ]]
+    );
+
+    let result = run("objects where name = \"memcpy.o\"");
+    assert_eq!(
+        result.get_columns(),
+        ["name", "library", "size", "segments"]
+    );
+    assert_eq!(
+        result.get_rows()[0],
+        [
+            Value::Text("memcpy.o".to_string()),
+            Value::Text("libc.a".to_string()),
+            Value::Number(0x500),
+            Value::Number(1)
+        ]
+    );
+    assert_eq!(run("objects where library = \"\"").get_rows().len(), 0);
+    assert_eq!(
+        run("objects where library != \"libc.a\"").get_rows().len(),
+        2
+    );
+
+    let result = run("libraries order by size desc");
+    assert_eq!(result.get_columns(), ["name", "size", "objects", "entries"]);
+    assert_eq!(
+        result.get_rows(),
+        [
+            vec![
+                Value::Text("libc.a".to_string()),
+                Value::Number(0x90c),
+                Value::Number(3),
+                Value::Number(3)
+            ],
+            vec![
+                Value::Text("libm.a".to_string()),
+                Value::Number(0x10),
+                Value::Number(1),
+                Value::Number(1)
+            ]
+        ]
+    );
+}
+
+#[test]
+fn parse_errors() {
+    let error = |text| Query::parse(text).unwrap_err();
+
+    assert_eq!(
+        error("symbols"),
+        QueryError::UnknownSource("symbols".to_string())
+    );
+    assert_eq!(
+        error("entries where segmnt = \".text\""),
+        QueryError::UnknownField {
+            source: "entries".to_string(),
+            field: "segmnt".to_string()
+        }
+    );
+    assert_eq!(
+        error("entries where size =~ \"1\""),
+        QueryError::TypeMismatch {
+            field: "size".to_string(),
+            op: "=~".to_string()
+        }
+    );
+    assert_eq!(
+        error("entries where name = 1"),
+        QueryError::TypeMismatch {
+            field: "name".to_string(),
+            op: "=".to_string()
+        }
+    );
+    assert!(matches!(
+        error("entries where name =~ \"(\""),
+        QueryError::InvalidRegex { .. }
+    ));
+    assert!(matches!(
+        error("entries where name = \"main"),
+        QueryError::Syntax { position: 21, .. }
+    ));
+    assert!(matches!(
+        error("entries where (size > 1"),
+        QueryError::Syntax { position: 23, .. }
+    ));
+    assert!(matches!(
+        error("entries order size"),
+        QueryError::Syntax { position: 14, .. }
+    ));
+    assert!(matches!(
+        error("entries limit 10 where size > 1"),
+        QueryError::Syntax { position: 17, .. }
+    ));
+    assert!(matches!(
+        error("entries where size > 0xz"),
+        QueryError::Syntax { position: 21, .. }
+    ));
+    assert!(matches!(error(""), QueryError::Syntax { position: 0, .. }));
+}
+
+#[test]
+fn write_table() {
+    let result = Query::parse("entries where segment = \".data\"")
+        .unwrap()
+        .run(&Parser::parse(MAPFILE));
+
+    let mut output = vec![];
+    result.write_table(&mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "\
+name           segment     address  size  fill  object   library
+.data.counter  .data    0x20000000     4     0  main.o   -
+.data.errno    .data    0x20000004    12     0  errno.o  libc.a
+"
+    );
+}
+
+#[test]
+fn write_json() {
+    let result = Query::parse("libraries where name = \"libm.a\"")
+        .unwrap()
+        .run(&Parser::parse(MAPFILE));
+
+    let mut output = vec![];
+    result.write_json(&mut output).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([{"name": "libm.a", "size": 16, "objects": 1, "entries": 1}])
+    );
+
+    let result = Query::parse("entries where name = \".text.main\"")
+        .unwrap()
+        .run(&Parser::parse(MAPFILE));
+    let mut output = vec![];
+    result.write_json(&mut output).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json[0]["library"], serde_json::Value::Null);
+    assert_eq!(json[0]["address"], 0x0800_0000);
+
+    let mut output = vec![];
+    Query::parse("entries limit 0")
+        .unwrap()
+        .run(&Parser::parse(MAPFILE))
+        .write_json(&mut output)
+        .unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "[\n]\n");
+}
@@ -0,0 +1,112 @@
+use parser::{
+    region::Region,
+    xmlwriter::{ToXmlWriter, XmlWriter},
+};
+
+mod uthelper;
+use uthelper::*;
+use xml::ParserConfig;
+
+const RAND_NAME_STRING_LEN: usize = 20;
+const ENTRIES_COUNT: usize = 10;
+
+#[test]
+fn new() {
+    let region_name = get_random_string(RAND_NAME_STRING_LEN);
+    let region_origin = get_random_number(RAND_ADDRESS_MAX);
+    let region_length = get_random_number(RAND_SIZE_MAX);
+
+    let region = Region::new(&region_name, region_origin, region_length);
+
+    assert_eq!(region.get_name(), region_name);
+    assert_eq!(region.get_origin(), region_origin);
+    assert_eq!(region.get_length(), region_length);
+    assert_eq!(region.get_attributes(), None);
+    assert_eq!(region.get_used_size(), 0);
+}
+
+#[test]
+fn attributes() {
+    let region_name = get_random_string(RAND_NAME_STRING_LEN);
+    let mut region = Region::new(&region_name, 0, 0);
+
+    region.set_attributes("xr");
+
+    assert_eq!(region.get_attributes(), Some("xr"));
+}
+
+#[test]
+fn contains() {
+    let region_name = get_random_string(RAND_NAME_STRING_LEN);
+    let region_origin = get_random_number(RAND_ADDRESS_MAX) + 1;
+    let region_length = get_random_number(RAND_SIZE_MAX) + 1;
+
+    let region = Region::new(&region_name, region_origin, region_length);
+
+    assert!(!region.contains(region_origin - 1));
+    assert!(region.contains(region_origin));
+    assert!(region.contains(region_origin + region_length - 1));
+    assert!(!region.contains(region_origin + region_length));
+}
+
+#[test]
+fn used_size() {
+    let region_name = get_random_string(RAND_NAME_STRING_LEN);
+    let mut region = Region::new(&region_name, 0, 0);
+
+    let mut sum = 0;
+    for _ in 0..ENTRIES_COUNT {
+        let size = get_random_number(RAND_SIZE_MAX);
+        sum += size;
+
+        region.update_used_size(size);
+    }
+
+    assert_eq!(region.get_used_size(), sum);
+}
+
+fn test_xml_output(region: &Region) {
+    let sink = UTSinkSource::new();
+    let mut writer = XmlWriter::new_empty(sink.clone());
+
+    region.to_xml_writer(&mut writer);
+
+    drop(writer);
+
+    let mut parser = ParserConfig::default()
+        .ignore_root_level_whitespace(true)
+        .trim_whitespace(true)
+        .create_reader(sink);
+
+    check_start_document_event(parser.next().unwrap());
+
+    check_region_start_element_event(parser.next().unwrap(), region);
+
+    check_end_element_event(parser.next().unwrap(), "region");
+
+    check_end_document_event(parser.next().unwrap());
+}
+
+#[test]
+fn xml_writer_no_attributes() {
+    let region_name = get_random_string(RAND_NAME_STRING_LEN);
+    let region_origin = get_random_number(RAND_ADDRESS_MAX);
+    let region_length = get_random_number(RAND_SIZE_MAX);
+
+    let region = Region::new(&region_name, region_origin, region_length);
+
+    test_xml_output(&region);
+}
+
+#[test]
+fn xml_writer_with_attributes() {
+    let region_name = get_random_string(RAND_NAME_STRING_LEN);
+    let region_origin = get_random_number(RAND_ADDRESS_MAX);
+    let region_length = get_random_number(RAND_SIZE_MAX);
+
+    let mut region = Region::new(&region_name, region_origin, region_length);
+    region.set_attributes("rw");
+    region.update_used_size(get_random_number(RAND_SIZE_MAX));
+
+    test_xml_output(&region);
+}
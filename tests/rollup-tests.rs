@@ -0,0 +1,137 @@
+use parser::{rollup::DirectoryRollup, Parser};
+
+mod uthelper;
+use uthelper::*;
+
+fn new_parser() -> Parser {
+    UTParserBuilder::new()
+        .segment(".text", 0x0800_0000, 0x100)
+        .entry(
+            UTEntryBuilder::new(".text", 0x0800_0000, 0x40)
+                .object("uart_ns16550.c.obj")
+                .library("zephyr/drivers/serial/libdrivers__serial.a"),
+        )
+        .entry(
+            UTEntryBuilder::new(".text", 0x0800_0040, 0x20)
+                .object("uart_console.c.obj")
+                .library("zephyr/drivers/console/libdrivers__console.a"),
+        )
+        .entry(
+            UTEntryBuilder::new(".text", 0x0800_0060, 0x10)
+                .object("./app/CMakeFiles/app.dir/src/main.c.obj"),
+        )
+        .entry(UTEntryBuilder::new(".text", 0x0800_0070, 0x8).object("crt0.o"))
+        // Without object, not counted
+        .entry(UTEntryBuilder::new(".text", 0x0800_0078, 0x8))
+        .segment(".data", 0x2000_0000, 0x10)
+        .entry(
+            UTEntryBuilder::new(".text", 0x2000_0000, 0x10)
+                .object("uart_ns16550.c.obj")
+                .library("zephyr/drivers/serial/libdrivers__serial.a"),
+        )
+        // Not allocated, not counted
+        .segment(".debug_info", 0x0, 0x1000)
+        .entry(UTEntryBuilder::new(".text", 0x0, 0x1000).object("crt0.o"))
+        .build()
+}
+
+#[test]
+fn entry_path() {
+    let entry = UTEntryBuilder::new(".text", 0x0, 0x4)
+        .object("uart.o")
+        .library("drivers/libserial.a")
+        .build();
+    assert_eq!(
+        DirectoryRollup::get_entry_path(&entry).as_deref(),
+        Some("drivers/libserial.a/uart.o")
+    );
+
+    let entry = UTEntryBuilder::new(".text", 0x0, 0x4)
+        .object("build/main.o")
+        .build();
+    assert_eq!(
+        DirectoryRollup::get_entry_path(&entry).as_deref(),
+        Some("build/main.o")
+    );
+
+    let entry = UTEntryBuilder::new(".text", 0x0, 0x4).build();
+    assert_eq!(DirectoryRollup::get_entry_path(&entry), None);
+}
+
+#[test]
+fn rollup() {
+    let rollup = DirectoryRollup::new(&new_parser());
+
+    assert_eq!(rollup.get_total_size(), 0x40 + 0x20 + 0x10 + 0x8 + 0x10);
+    let size = |path| rollup.get_node(path).map(|n| n.get_size());
+    assert_eq!(size("zephyr"), Some(0x70));
+    assert_eq!(size("zephyr/drivers"), Some(0x70));
+    assert_eq!(size("zephyr/drivers/serial"), Some(0x50));
+    assert_eq!(
+        size("zephyr/drivers/serial/libdrivers__serial.a/uart_ns16550.c.obj"),
+        Some(0x50)
+    );
+    // "." components are ignored
+    assert_eq!(size("app/CMakeFiles/app.dir/src"), Some(0x10));
+    assert_eq!(size("crt0.o"), Some(0x8));
+    assert_eq!(size("zephyr/kernel"), None);
+    assert_eq!(size(""), Some(rollup.get_total_size()));
+
+    let node = rollup.get_node("zephyr/drivers/console").unwrap();
+    assert_eq!(node.get_name(), "console");
+    assert_eq!(node.get_path(), "zephyr/drivers/console");
+    assert!(!node.is_leaf());
+    assert!(rollup.get_node("crt0.o").unwrap().is_leaf());
+
+    // Largest first
+    let children = rollup
+        .get_root()
+        .get_children()
+        .iter()
+        .map(|c| c.get_name())
+        .collect::<Vec<_>>();
+    assert_eq!(children, ["zephyr", "app", "crt0.o"]);
+}
+
+#[test]
+fn nodes_depth() {
+    let rollup = DirectoryRollup::new(&new_parser());
+
+    let nodes = rollup
+        .get_nodes(Some(2))
+        .into_iter()
+        .map(|(depth, node)| (depth, node.get_path(), node.get_size()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        nodes,
+        [
+            (1, "zephyr", 0x70),
+            (2, "zephyr/drivers", 0x70),
+            (1, "app", 0x10),
+            (2, "app/CMakeFiles", 0x10),
+            (1, "crt0.o", 0x8)
+        ]
+    );
+
+    assert_eq!(rollup.get_nodes(Some(0)).len(), 0);
+    let all = rollup.get_nodes(None);
+    assert_eq!(all.len(), 14);
+    assert_eq!(all.last().map(|(_, node)| node.get_path()), Some("crt0.o"));
+}
+
+#[test]
+fn from_sizes() {
+    let rollup = DirectoryRollup::from_sizes([
+        ("src/app/main.c", 0x10),
+        ("src\\app\\util.c", 0x8),
+        ("/src/drivers/led.c", 0x4),
+        ("src/app/main.c", 0x2),
+    ]);
+
+    assert_eq!(rollup.get_total_size(), 0x1e);
+    assert_eq!(rollup.get_node("src/app").unwrap().get_size(), 0x1a);
+    assert_eq!(rollup.get_node("src/app/main.c").unwrap().get_size(), 0x12);
+    assert_eq!(rollup.get_node("src/drivers").unwrap().get_size(), 0x4);
+
+    assert!(DirectoryRollup::from_sizes([]).get_nodes(None).is_empty());
+}
@@ -116,3 +116,38 @@ fn xml_writer_with_size_and_address() {
 
     test_xml_output(&segment, false);
 }
+
+#[test]
+fn load_address() {
+    let segment_name = get_random_string(RAND_NAME_STRING_LEN);
+    let segment_load_address = get_random_number(RAND_ADDRESS_MAX);
+
+    let mut segment = Segment::new(&segment_name);
+    assert_eq!(segment.get_load_address(), None);
+
+    segment.set_load_address(segment_load_address);
+    assert_eq!(segment.get_load_address(), Some(segment_load_address));
+}
+
+#[test]
+fn is_allocated() {
+    assert!(Segment::new(".text").is_allocated());
+    assert!(Segment::new(".data").is_allocated());
+    assert!(!Segment::new(".debug_info").is_allocated());
+    assert!(!Segment::new(".comment").is_allocated());
+    assert!(!Segment::new(".ARM.attributes").is_allocated());
+}
+
+#[test]
+fn xml_writer_with_load_address() {
+    let segment_name = get_random_string(RAND_NAME_STRING_LEN);
+    let segment_size = get_random_number(RAND_ADDRESS_MAX);
+    let segment_address: u64 = get_random_number(RAND_ADDRESS_MAX);
+    let segment_load_address: u64 = get_random_number(RAND_ADDRESS_MAX);
+
+    let mut segment = Segment::new(&segment_name);
+    segment.set_size_and_address(segment_size, segment_address);
+    segment.set_load_address(segment_load_address);
+
+    test_xml_output(&segment, false);
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "serde")]
+
+use parser::{
+    entry::Entry,
+    object::Object,
+    segment::Segment,
+    symbol::{LinkerSymbol, SymbolKind},
+    Parser, Section,
+};
+
+const MAPFILE: &str = "\
+Memory Configuration
+
+Name             Origin             Length             Attributes
+FLASH            0x0000000008000000 0x0000000000010000 xr
+RAM              0x0000000020000000 0x0000000000004000 xrw
+
+Linker script and memory map
+
+.text           0x0000000008000000       0x40
+ .text.main     0x0000000008000000       0x12 main.o
+                0x0000000008000000                main
+ *fill*         0x0000000008000012        0x2
+ .text.foo      0x0000000008000014       0x2c libfoo.a(foo.o)
+
+.data           0x0000000020000000        0x4 load address 0x0000000008000040
+                0x0000000020000000                _sdata = .
+ .data.counter  0x0000000020000000        0x4 main.o
+";
+
+#[test]
+fn entry() {
+    let mut entry = Entry::new(".text.main", 0x0800_0000, 0x12, " .text.main ...");
+    entry.set_fill(0x0800_0012, 0x2);
+    entry.set_object_name("main.o");
+    entry.set_library_name("libmain.a");
+
+    let json = serde_json::to_string(&entry).unwrap();
+    let result: Entry = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(result, entry);
+}
+
+#[test]
+fn segment() {
+    let mut segment = Segment::new(".data");
+    segment.set_size_and_address(0x4, 0x2000_0000);
+    segment.set_load_address(0x0800_0040);
+    segment.add_entry(Entry::new(".data.counter", 0x2000_0000, 0x4, ""));
+
+    let json = serde_json::to_string(&segment).unwrap();
+    let result: Segment = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(result, segment);
+}
+
+#[test]
+fn object() {
+    let mut object = Object::new("main.o");
+    object.update_segment_size(".text", 0x14);
+    object.update_segment_size(".data", 0x4);
+
+    let json = serde_json::to_string(&object).unwrap();
+    let result: Object = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(result.get_name(), "main.o");
+    assert_eq!(result.get_segment_size(".text"), Some(0x14));
+    assert_eq!(result.get_segment_size(".data"), Some(0x4));
+}
+
+#[test]
+fn section() {
+    let json = serde_json::to_string(&Section::MemoryMap).unwrap();
+    let result: Section = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(result, Section::MemoryMap);
+}
+
+#[test]
+fn symbol() {
+    let mut symbol = LinkerSymbol::new("_sdata", 0x2000_0000, SymbolKind::Assignment);
+    symbol.set_expression(".");
+
+    let json = serde_json::to_string(&symbol).unwrap();
+    let result: LinkerSymbol = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(result, symbol);
+}
+
+#[test]
+fn parser() {
+    let parser = Parser::parse(MAPFILE);
+
+    let value = serde_json::to_value(&parser).unwrap();
+    let result: Parser = serde_json::from_value(value.clone()).unwrap();
+
+    assert_eq!(result.get_memory_regions(), parser.get_memory_regions());
+    assert_eq!(
+        result.get_memory_map_segments(),
+        parser.get_memory_map_segments()
+    );
+    assert_eq!(
+        result.get_memory_map_symbols(),
+        parser.get_memory_map_symbols()
+    );
+    assert_eq!(serde_json::to_value(&result).unwrap(), value);
+}
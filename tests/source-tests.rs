@@ -0,0 +1,129 @@
+use parser::{
+    entry::Entry,
+    segment::Segment,
+    source::{CompilationUnit, DebugInfo, SourceSizes},
+    Parser,
+};
+
+/// ELF image with debug information and mapfile built by `data/firmware/build.sh`
+const ELF_IMAGE: &[u8] = include_bytes!("data/firmware/firmware.elf");
+const MAPFILE: &str = include_str!("data/firmware/firmware.map");
+
+fn new_debug_info() -> DebugInfo {
+    DebugInfo::parse(ELF_IMAGE).unwrap()
+}
+
+#[test]
+fn compilation_unit_path() {
+    let unit = CompilationUnit::new("src/app/main.c", Some("."));
+    assert_eq!(unit.get_name(), "src/app/main.c");
+    assert_eq!(unit.get_directory(), Some("."));
+    assert_eq!(unit.get_path(), "src/app/main.c");
+
+    let unit = CompilationUnit::new("../../src/wifi/./scan.c", Some("/work/fw/build/wifi"));
+    assert_eq!(unit.get_path(), "/work/fw/src/wifi/scan.c");
+    assert_eq!(unit.get_relative_path(Some("/work/fw")), "src/wifi/scan.c");
+    assert_eq!(
+        unit.get_relative_path(Some("/other")),
+        "/work/fw/src/wifi/scan.c"
+    );
+
+    let unit = CompilationUnit::new("../main.c", None);
+    assert_eq!(unit.get_path(), "../main.c");
+    assert_eq!(unit.get_relative_path(None), "../main.c");
+}
+
+#[test]
+fn units() {
+    let debug_info = new_debug_info();
+
+    let paths = debug_info
+        .get_units()
+        .iter()
+        .map(|u| u.get_path())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, ["src/app/main.c", "src/drivers/led.c"]);
+    assert!(!debug_info.is_empty());
+}
+
+#[test]
+fn unit_lookup() {
+    let debug_info = new_debug_info();
+    let path = |address, size| debug_info.get_unit(address, size).map(|u| u.get_path());
+
+    // main, _start and led_toggle functions
+    assert_eq!(path(0x4000e8, 0x11), Some("src/app/main.c"));
+    assert_eq!(path(0x4000f9, 0), Some("src/app/main.c"));
+    assert_eq!(path(0x400107, 0xf), Some("src/drivers/led.c"));
+    // counter and led_state variables
+    assert_eq!(path(0x401118, 0x4), Some("src/app/main.c"));
+    assert_eq!(path(0x40111c, 0), Some("src/drivers/led.c"));
+    // Entry containing a variable after its start
+    assert_eq!(path(0x401110, 0x10), Some("src/app/main.c"));
+
+    assert_eq!(path(0x400116, 0x2), None);
+    assert_eq!(path(0x0, 0x10), None);
+
+    let entry = Entry::new(".text.led_toggle", 0x400107, 0xf, "");
+    assert_eq!(
+        debug_info.get_entry_unit(&entry).map(|u| u.get_name()),
+        Some("src/drivers/led.c")
+    );
+}
+
+#[test]
+fn no_debug_info() {
+    let debug_info =
+        DebugInfo::parse(include_bytes!("data/firmware/firmware-nodebug.elf")).unwrap();
+    assert!(debug_info.is_empty());
+
+    assert!(DebugInfo::parse(MAPFILE.as_bytes()).is_err());
+    assert!(DebugInfo::default().get_unit(0x4000e8, 0x10).is_none());
+}
+
+#[test]
+fn source_sizes() {
+    let sizes = SourceSizes::new(&Parser::parse(MAPFILE), &new_debug_info(), None);
+
+    assert_eq!(
+        sizes.get_files(),
+        [
+            ("src/app/main.c".to_string(), 0x11 + 0xe + 0x4),
+            ("src/drivers/led.c".to_string(), 0xf + 0x4)
+        ]
+    );
+    assert_eq!(
+        sizes.get_directories(),
+        [
+            (".".to_string(), 0x11 + 0xe + 0x4 + 0xf + 0x4),
+            ("src".to_string(), 0x11 + 0xe + 0x4 + 0xf + 0x4),
+            ("src/app".to_string(), 0x11 + 0xe + 0x4),
+            ("src/drivers".to_string(), 0xf + 0x4)
+        ]
+    );
+    assert_eq!(sizes.get_unattributed_size(), 0);
+}
+
+#[test]
+fn source_sizes_root() {
+    let mut parser = Parser::parse(MAPFILE);
+    let mut rodata = Segment::new(".rodata");
+    rodata.set_size_and_address(0x20, 0x400200);
+    rodata.add_entry(Entry::new(".rodata", 0x400200, 0x20, ""));
+    parser.add_segment(rodata);
+
+    let sizes = SourceSizes::new(&parser, &new_debug_info(), Some("src"));
+
+    let directories = sizes
+        .get_directories()
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(directories, [".", "app", "drivers"]);
+    assert_eq!(sizes.get_files()[1].0, "drivers/led.c");
+    assert_eq!(sizes.get_unattributed_size(), 0x20);
+
+    let sizes = SourceSizes::new(&parser, &DebugInfo::default(), None);
+    assert!(sizes.get_files().is_empty());
+    assert_eq!(sizes.get_unattributed_size(), 0x20 + 0x11 + 0xe + 0xf + 0x8);
+}
@@ -0,0 +1,78 @@
+use parser::{
+    symbol::{LinkerSymbol, SymbolKind},
+    xmlwriter::{ToXmlWriter, XmlWriter},
+};
+
+mod uthelper;
+use uthelper::*;
+use xml::ParserConfig;
+
+const RAND_NAME_STRING_LEN: usize = 20;
+
+#[test]
+fn new() {
+    let symbol_name = get_random_string(RAND_NAME_STRING_LEN);
+    let symbol_address = get_random_number(RAND_ADDRESS_MAX);
+
+    let symbol = LinkerSymbol::new(&symbol_name, symbol_address, SymbolKind::Definition);
+
+    assert_eq!(symbol.get_name(), symbol_name);
+    assert_eq!(symbol.get_address(), symbol_address);
+    assert_eq!(symbol.get_kind(), SymbolKind::Definition);
+    assert_eq!(symbol.get_expression(), None);
+}
+
+#[test]
+fn expression() {
+    let symbol_name = get_random_string(RAND_NAME_STRING_LEN);
+    let symbol_address = get_random_number(RAND_ADDRESS_MAX);
+
+    let mut symbol = LinkerSymbol::new(&symbol_name, symbol_address, SymbolKind::Assignment);
+    symbol.set_expression(".");
+
+    assert_eq!(symbol.get_kind(), SymbolKind::Assignment);
+    assert_eq!(symbol.get_expression(), Some("."));
+}
+
+fn test_xml_output(symbol: &LinkerSymbol) {
+    let sink = UTSinkSource::new();
+    let mut writer = XmlWriter::new_empty(sink.clone());
+
+    symbol.to_xml_writer(&mut writer);
+
+    drop(writer);
+
+    let mut parser = ParserConfig::default()
+        .ignore_root_level_whitespace(true)
+        .trim_whitespace(true)
+        .create_reader(sink);
+
+    check_start_document_event(parser.next().unwrap());
+
+    check_symbol_start_element_event(parser.next().unwrap(), symbol);
+
+    check_end_element_event(parser.next().unwrap(), "symbol");
+
+    check_end_document_event(parser.next().unwrap());
+}
+
+#[test]
+fn xml_writer_no_expression() {
+    let symbol_name = get_random_string(RAND_NAME_STRING_LEN);
+    let symbol_address = get_random_number(RAND_ADDRESS_MAX);
+
+    let symbol = LinkerSymbol::new(&symbol_name, symbol_address, SymbolKind::Definition);
+
+    test_xml_output(&symbol);
+}
+
+#[test]
+fn xml_writer_with_expression() {
+    let symbol_name = get_random_string(RAND_NAME_STRING_LEN);
+    let symbol_address = get_random_number(RAND_ADDRESS_MAX);
+
+    let mut symbol = LinkerSymbol::new(&symbol_name, symbol_address, SymbolKind::Provide);
+    symbol.set_expression("(ORIGIN (RAM) + LENGTH (RAM))");
+
+    test_xml_output(&symbol);
+}
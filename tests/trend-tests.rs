@@ -0,0 +1,157 @@
+#![cfg(feature = "trend")]
+
+use parser::{
+    trend::{self, BuildInfo, TrendDatabase, TrendKind},
+    Parser,
+};
+
+mod uthelper;
+use uthelper::*;
+
+fn new_parser(text_size: u64, with_lib: bool) -> Parser {
+    let mut entry = UTEntryBuilder::new(".text.main", 0x0800_0000, text_size).object("main.o");
+    if with_lib {
+        entry = entry.library("libmain.a");
+    }
+
+    UTParserBuilder::new()
+        .region("FLASH", 0x0800_0000, 0x1_0000)
+        .segment(".text", 0x0800_0000, text_size)
+        .entry(entry)
+        .build()
+}
+
+fn new_build(build_id: &str, commit: &str) -> BuildInfo {
+    let mut build = BuildInfo::new(build_id);
+    build.set_commit(commit);
+    build
+}
+
+fn new_database() -> TrendDatabase {
+    let mut database = TrendDatabase::open_in_memory().unwrap();
+    database
+        .record(&new_parser(0x100, true), &new_build("1", "aaa"))
+        .unwrap();
+    database
+        .record(&new_parser(0x140, true), &new_build("2", "bbb"))
+        .unwrap();
+    database
+        .record(&new_parser(0x120, false), &new_build("3", "ccc"))
+        .unwrap();
+    database
+}
+
+#[test]
+fn trend_kind() {
+    for kind in [
+        TrendKind::Segment,
+        TrendKind::Region,
+        TrendKind::Object,
+        TrendKind::Library,
+    ] {
+        assert_eq!(TrendKind::from_name(kind.as_str()), Some(kind));
+    }
+    assert_eq!(TrendKind::from_name("entry"), None);
+}
+
+#[test]
+fn record() {
+    let mut database = new_database();
+
+    assert_eq!(database.get_build_count().unwrap(), 3);
+    assert!(database.contains_build("2").unwrap());
+    assert!(!database.contains_build("4").unwrap());
+
+    // Build ids are unique
+    assert!(database
+        .record(&new_parser(0x100, true), &new_build("2", "ddd"))
+        .is_err());
+    assert_eq!(database.get_build_count().unwrap(), 3);
+}
+
+#[test]
+fn query() {
+    let database = new_database();
+
+    let points = database.query(TrendKind::Segment, ".text", None).unwrap();
+    let values = points
+        .iter()
+        .map(|p| {
+            (
+                p.get_build_id(),
+                p.get_commit(),
+                p.get_size(),
+                p.get_delta(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        [
+            ("1", Some("aaa"), 0x100, 0),
+            ("2", Some("bbb"), 0x140, 0x40),
+            ("3", Some("ccc"), 0x120, -0x20)
+        ]
+    );
+
+    let sizes = |kind, name| {
+        database
+            .query(kind, name, None)
+            .unwrap()
+            .iter()
+            .map(|p| p.get_size())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(sizes(TrendKind::Region, "FLASH"), [0x100, 0x140, 0x120]);
+    assert_eq!(sizes(TrendKind::Object, "main.o"), [0x100, 0x140, 0x120]);
+    // Missing from a build
+    assert_eq!(sizes(TrendKind::Library, "libmain.a"), [0x100, 0x140, 0]);
+    assert_eq!(sizes(TrendKind::Segment, ".data"), [0, 0, 0]);
+}
+
+#[test]
+fn query_limit() {
+    let database = new_database();
+
+    let points = database
+        .query(TrendKind::Segment, ".text", Some(2))
+        .unwrap();
+    let builds = points.iter().map(|p| p.get_build_id()).collect::<Vec<_>>();
+    assert_eq!(builds, ["2", "3"]);
+    // First returned build has no previous build
+    assert_eq!(points[0].get_delta(), 0);
+}
+
+#[test]
+fn write_csv() {
+    let database = new_database();
+    let points = database.query(TrendKind::Segment, ".text", None).unwrap();
+
+    let mut output = vec![];
+    trend::write_csv(&mut output, &points).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "build_id,commit,timestamp,size,delta");
+    assert!(lines[2].starts_with("2,bbb,"));
+    assert!(lines[2].ends_with(",320,64"));
+    assert!(lines[3].ends_with(",288,-32"));
+}
+
+#[test]
+fn write_html() {
+    let database = new_database();
+    let points = database.query(TrendKind::Segment, ".text", None).unwrap();
+
+    let mut output = vec![];
+    trend::write_html(&mut output, TrendKind::Segment, "<.text>", &points).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.starts_with("<!DOCTYPE html>"));
+    assert!(output.contains("<title>segment &lt;.text&gt; size trend</title>"));
+    assert!(output.contains("<polyline"));
+    assert_eq!(output.matches("<tr>").count(), 4);
+    assert!(output.contains("<td class=\"n up\">+64</td>"));
+    assert!(output.contains("<td class=\"n down\">-32</td>"));
+}
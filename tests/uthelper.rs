@@ -1,11 +1,15 @@
 use std::{
     cell::RefCell,
+    fmt::Display,
     io::{Read, Write},
     ops::Deref,
     rc::Rc,
 };
 
-use parser::{entry::Entry, object::Object, segment::Segment};
+use parser::{
+    entry::Entry, object::Object, region::Region, segment::Segment, symbol::LinkerSymbol,
+    xmlwriter::SCHEMA_VERSION, Parser,
+};
 use rand::{distributions::Alphanumeric, Rng};
 use xml::{common::XmlVersion, reader::XmlEvent};
 
@@ -27,9 +31,19 @@ impl UTSinkSource {
     }
 }
 
-impl ToString for UTSinkSource {
-    fn to_string(&self) -> String {
-        String::from_utf8_lossy(self.buffer.deref().borrow().as_slice()).to_string()
+impl Default for UTSinkSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for UTSinkSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            String::from_utf8_lossy(self.buffer.deref().borrow().as_slice())
+        )
     }
 }
 
@@ -67,6 +81,112 @@ impl Read for UTSinkSource {
     }
 }
 
+/// Builder of test [Entry] fixtures
+pub struct UTEntryBuilder {
+    entry: Entry,
+}
+
+#[allow(dead_code)]
+impl UTEntryBuilder {
+    pub fn new(name: &str, address: u64, size: u64) -> Self {
+        Self {
+            entry: Entry::new(name, address, size, ""),
+        }
+    }
+
+    pub fn object(mut self, object_name: &str) -> Self {
+        self.entry.set_object_name(object_name);
+        self
+    }
+
+    pub fn library(mut self, library_name: &str) -> Self {
+        self.entry.set_library_name(library_name);
+        self
+    }
+
+    pub fn fill(mut self, address: u64, size: u64) -> Self {
+        self.entry.set_fill(address, size);
+        self
+    }
+
+    pub fn build(self) -> Entry {
+        self.entry
+    }
+}
+
+impl From<UTEntryBuilder> for Entry {
+    fn from(value: UTEntryBuilder) -> Self {
+        value.build()
+    }
+}
+
+/// Builder of test [Parser] fixtures. Entries are added into the last started segment
+pub struct UTParserBuilder {
+    parser: Parser,
+    segment: Option<Segment>,
+}
+
+#[allow(dead_code)]
+impl UTParserBuilder {
+    pub fn new() -> Self {
+        Self {
+            parser: Parser::new(),
+            segment: None,
+        }
+    }
+
+    pub fn region(mut self, name: &str, origin: u64, length: u64) -> Self {
+        self.parser.add_region(Region::new(name, origin, length));
+        self
+    }
+
+    pub fn segment(mut self, name: &str, address: u64, size: u64) -> Self {
+        self.add_segment();
+        let mut segment = Segment::new(name);
+        segment.set_size_and_address(size, address);
+        self.segment = Some(segment);
+        self
+    }
+
+    pub fn load_address(mut self, load_address: u64) -> Self {
+        self.segment
+            .as_mut()
+            .expect("No segment started")
+            .set_load_address(load_address);
+        self
+    }
+
+    pub fn entry<E: Into<Entry>>(mut self, entry: E) -> Self {
+        self.segment
+            .as_mut()
+            .expect("No segment started")
+            .add_entry(entry.into());
+        self
+    }
+
+    pub fn symbol(mut self, symbol: LinkerSymbol) -> Self {
+        self.parser.add_symbol(symbol);
+        self
+    }
+
+    pub fn build(mut self) -> Parser {
+        self.add_segment();
+        self.parser
+    }
+
+    fn add_segment(&mut self) {
+        if let Some(segment) = self.segment.take() {
+            self.parser.add_segment(segment);
+        }
+    }
+}
+
+impl Default for UTParserBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[allow(dead_code)]
 pub fn check_start_document_event(event: XmlEvent) {
     if let XmlEvent::StartDocument {
@@ -131,7 +251,7 @@ pub fn check_segment_start_element_event(event: XmlEvent, segment: &Segment) {
         assert_eq!(name.local_name, "segment");
         assert_eq!(name.namespace, Option::None);
         assert_eq!(name.prefix, Option::None);
-        assert!(matches!(attributes.len(), 1 | 3));
+        assert!(matches!(attributes.len(), 1 | 3 | 4));
 
         let attr = &attributes[0];
         assert_eq!(attr.name.local_name, "name");
@@ -154,12 +274,103 @@ pub fn check_segment_start_element_event(event: XmlEvent, segment: &Segment) {
             let size = format!("{}", segment.get_size().unwrap());
             assert_eq!(attr.value, size);
         }
+
+        if attributes.len() == 4 {
+            let attr = &attributes[3];
+            assert_eq!(attr.name.local_name, "load_address");
+            assert_eq!(attr.name.namespace, Option::None);
+            assert_eq!(attr.name.prefix, Option::None);
+            let load_address = format!("{:#016x}", segment.get_load_address().unwrap());
+            assert_eq!(attr.value, load_address);
+        } else {
+            assert_eq!(segment.get_load_address(), None);
+        }
         // TODO(calin) check namespace ?
     } else {
         panic!("Expected XmlEvent::StartElement!")
     }
 }
 
+#[allow(dead_code)]
+pub fn check_region_start_element_event(event: XmlEvent, region: &Region) {
+    if let XmlEvent::StartElement {
+        name,
+        attributes,
+        namespace: _,
+    } = event
+    {
+        assert_eq!(name.local_name, "region");
+        assert_eq!(name.namespace, Option::None);
+        assert_eq!(name.prefix, Option::None);
+        assert!(matches!(attributes.len(), 4 | 5));
+
+        let attr = &attributes[0];
+        assert_eq!(attr.name.local_name, "name");
+        assert_eq!(attr.value, region.get_name());
+
+        let attr = &attributes[1];
+        assert_eq!(attr.name.local_name, "origin");
+        let origin = format!("{:#016x}", region.get_origin());
+        assert_eq!(attr.value, origin);
+
+        let attr = &attributes[2];
+        assert_eq!(attr.name.local_name, "length");
+        assert_eq!(attr.value, region.get_length().to_string());
+
+        let attr = &attributes[3];
+        assert_eq!(attr.name.local_name, "used_size");
+        assert_eq!(attr.value, region.get_used_size().to_string());
+
+        if attributes.len() == 5 {
+            let attr = &attributes[4];
+            assert_eq!(attr.name.local_name, "attributes");
+            assert_eq!(Some(attr.value.as_str()), region.get_attributes());
+        } else {
+            assert_eq!(region.get_attributes(), None);
+        }
+    } else {
+        panic!("Expected XmlEvent::StartElement!")
+    }
+}
+
+#[allow(dead_code)]
+pub fn check_symbol_start_element_event(event: XmlEvent, symbol: &LinkerSymbol) {
+    if let XmlEvent::StartElement {
+        name,
+        attributes,
+        namespace: _,
+    } = event
+    {
+        assert_eq!(name.local_name, "symbol");
+        assert_eq!(name.namespace, Option::None);
+        assert_eq!(name.prefix, Option::None);
+        assert!(matches!(attributes.len(), 3 | 4));
+
+        let attr = &attributes[0];
+        assert_eq!(attr.name.local_name, "name");
+        assert_eq!(attr.value, symbol.get_name());
+
+        let attr = &attributes[1];
+        assert_eq!(attr.name.local_name, "address");
+        let address = format!("{:#016x}", symbol.get_address());
+        assert_eq!(attr.value, address);
+
+        let attr = &attributes[2];
+        assert_eq!(attr.name.local_name, "kind");
+        assert_eq!(attr.value, symbol.get_kind().as_str());
+
+        if attributes.len() == 4 {
+            let attr = &attributes[3];
+            assert_eq!(attr.name.local_name, "expression");
+            assert_eq!(Some(attr.value.as_str()), symbol.get_expression());
+        } else {
+            assert_eq!(symbol.get_expression(), None);
+        }
+    } else {
+        panic!("Expected XmlEvent::StartElement!")
+    }
+}
+
 #[allow(dead_code)]
 pub fn check_mapfile_start_element_event(event: XmlEvent, mapfile_source: &str) {
     if let XmlEvent::StartElement {
@@ -185,6 +396,12 @@ pub fn check_mapfile_start_element_event(event: XmlEvent, mapfile_source: &str)
         assert_eq!(attr.name.prefix, Option::None);
         assert_eq!(attr.value, mapfile_source);
 
+        let attr = &attributes[2];
+        assert_eq!(attr.name.local_name, "schema_version");
+        assert_eq!(attr.name.namespace, Option::None);
+        assert_eq!(attr.name.prefix, Option::None);
+        assert_eq!(attr.value, SCHEMA_VERSION);
+
         // TODO(calin) check namespace ?
     } else {
         panic!("Expected XmlEvent::StartElement!")
@@ -237,6 +454,23 @@ pub fn check_entry_start_element_event(event: XmlEvent, entry: &Entry) {
         let fill_overlaps = format!("{}", entry.get_fill_overlaps());
         assert_eq!(attr.value, fill_overlaps);
 
+        let mut index = 5;
+        if let Some(object_name) = entry.get_object_name() {
+            let attr = &attributes[index];
+            assert_eq!(attr.name.local_name, "object");
+            assert_eq!(attr.value, object_name);
+            index += 1;
+        }
+
+        if let Some(library_name) = entry.get_library_name() {
+            let attr = &attributes[index];
+            assert_eq!(attr.name.local_name, "library");
+            assert_eq!(attr.value, library_name);
+            index += 1;
+        }
+
+        assert_eq!(attributes.len(), index);
+
         // TODO(calin) check namespace ?
     } else {
         panic!("Expected XmlEvent::StartElement!")
@@ -0,0 +1,143 @@
+use parser::validation::{IssueKind, Validation};
+
+mod uthelper;
+use uthelper::*;
+
+#[test]
+fn valid() {
+    let parser = UTParserBuilder::new()
+        .region("FLASH", 0x1000, 0x1000)
+        .segment(".text", 0x1000, 0x40)
+        // Fill covers the space up to next entry
+        .entry(UTEntryBuilder::new(".text.a", 0x1000, 0x1e).fill(0x101e, 0x2))
+        // Same address entries without size
+        .entry(UTEntryBuilder::new(".text.b", 0x1020, 0x0))
+        .entry(UTEntryBuilder::new(".text.c", 0x1020, 0x20))
+        .segment(".rodata", 0x1040, 0x10)
+        .entry(UTEntryBuilder::new(".rodata", 0x1040, 0x10))
+        // Not allocated segments are ignored in regions
+        .segment(".debug_info", 0x1000, 0x10)
+        .entry(UTEntryBuilder::new(".debug_info", 0x1000, 0x10))
+        .build();
+
+    let validation = Validation::new(&parser);
+    assert!(validation.is_valid());
+    assert!(validation.get_issues().is_empty());
+}
+
+#[test]
+fn entry_overlap() {
+    let parser = UTParserBuilder::new()
+        .segment(".text", 0x1000, 0x40)
+        .entry(UTEntryBuilder::new(".text.a", 0x1000, 0x30))
+        .entry(UTEntryBuilder::new(".text.b", 0x1020, 0x20))
+        .build();
+
+    let validation = Validation::new(&parser);
+    let issues = validation.get_issues();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].get_kind(), IssueKind::EntryOverlap);
+    assert_eq!(issues[0].get_location(), ".text");
+    assert_eq!(issues[0].get_first(), Some(".text.a"));
+    assert_eq!(issues[0].get_second(), Some(".text.b"));
+    assert_eq!(issues[0].get_address(), 0x1020);
+    assert_eq!(issues[0].get_size(), 0x10);
+}
+
+#[test]
+fn gaps() {
+    let parser = UTParserBuilder::new()
+        .segment(".data", 0x2000, 0x40)
+        .entry(UTEntryBuilder::new(".data.a", 0x2004, 0x10))
+        .entry(UTEntryBuilder::new(".data.b", 0x2020, 0x10))
+        .build();
+
+    let validation = Validation::new(&parser);
+    let issues = validation.get_issues();
+    assert_eq!(issues.len(), 3);
+
+    assert!(issues.iter().all(|i| i.get_kind() == IssueKind::Gap));
+    assert!(issues.iter().all(|i| i.get_location() == ".data"));
+
+    // Before first entry
+    assert_eq!(issues[0].get_first(), Some(".data.a"));
+    assert_eq!(issues[0].get_address(), 0x2000);
+    assert_eq!(issues[0].get_size(), 0x4);
+
+    // Between entries
+    assert_eq!(issues[1].get_first(), Some(".data.b"));
+    assert_eq!(issues[1].get_address(), 0x2014);
+    assert_eq!(issues[1].get_size(), 0xc);
+
+    // At the end of segment
+    assert_eq!(issues[2].get_first(), None);
+    assert_eq!(issues[2].get_address(), 0x2030);
+    assert_eq!(issues[2].get_size(), 0x10);
+}
+
+#[test]
+fn segment_overlap() {
+    let parser = UTParserBuilder::new()
+        .region("FLASH", 0x1000, 0x1000)
+        .region("RAM", 0x2000, 0x1000)
+        .segment(".text", 0x1000, 0x40)
+        .entry(UTEntryBuilder::new(".text", 0x1000, 0x40))
+        // Load address overlaps .text in FLASH
+        .segment(".data", 0x2000, 0x10)
+        .load_address(0x1038)
+        .entry(UTEntryBuilder::new(".data", 0x2000, 0x10))
+        .build();
+
+    let validation = Validation::new(&parser);
+    let issues = validation.get_issues();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].get_kind(), IssueKind::SegmentOverlap);
+    assert_eq!(issues[0].get_location(), "FLASH");
+    assert_eq!(issues[0].get_first(), Some(".text"));
+    assert_eq!(issues[0].get_second(), Some(".data"));
+    assert_eq!(issues[0].get_address(), 0x1038);
+    assert_eq!(issues[0].get_size(), 0x8);
+    assert_eq!(
+        issues[0].to_string(),
+        "segment overlap in FLASH at 0x00001038 (8 bytes): .text and .data"
+    );
+}
+
+#[test]
+fn segment_load_address() {
+    let parser = UTParserBuilder::new()
+        .region("FLASH", 0x1000, 0x1000)
+        // Load address equal to the address does not overlap the segment itself
+        .segment(".text", 0x1000, 0x40)
+        .load_address(0x1000)
+        .entry(UTEntryBuilder::new(".text", 0x1000, 0x40))
+        .build();
+
+    assert!(Validation::new(&parser).is_valid());
+}
+
+#[test]
+fn ignored_segments() {
+    let parser = UTParserBuilder::new()
+        // Sized by linker script assignments
+        .segment("._user_heap_stack", 0x2000, 0x600)
+        // Not allocated
+        .segment(".comment", 0x0, 0x40)
+        .entry(UTEntryBuilder::new(".comment", 0x0, 0x40))
+        .entry(UTEntryBuilder::new(".comment", 0x0, 0x20))
+        .build();
+
+    assert!(Validation::new(&parser).is_valid());
+}
+
+#[test]
+fn address_overflow() {
+    let parser = UTParserBuilder::new()
+        .region("FLASH", 0xffff_ffff_ffff_f000, 0x1000)
+        // End address is past the address space
+        .segment(".big", 0xffff_ffff_ffff_fff0, 0x20)
+        .entry(UTEntryBuilder::new(".big", 0xffff_ffff_ffff_fff0, 0x20))
+        .build();
+
+    assert!(Validation::new(&parser).is_valid());
+}
@@ -0,0 +1,171 @@
+use parser::{
+    entry::Entry,
+    xmlreader::{XmlReadError, XmlReader},
+    xmlwriter::{ToXmlWriter, XmlWriter, SCHEMA_VERSION},
+    Parser,
+};
+
+mod uthelper;
+use uthelper::*;
+
+const MAPFILE: &str = "\
+Memory Configuration
+
+Name             Origin             Length             Attributes
+FLASH            0x0000000008000000 0x0000000000010000 xr
+RAM              0x0000000020000000 0x0000000000004000 xrw
+*default*        0x0000000000000000 0xffffffffffffffff
+
+Linker script and memory map
+
+.text           0x0000000008000000       0x40
+ .text.main     0x0000000008000000       0x12 main.o
+                0x0000000008000000                main
+ *fill*         0x0000000008000012        0x2
+ .text.foo      0x0000000008000014       0x2c libfoo.a(foo.o)
+                0x0000000008000014                foo
+
+.data           0x0000000020000000       0x10 load address 0x0000000008000040
+                0x0000000020000000                _sdata = .
+ .data.counter  0x0000000020000000        0x4 main.o
+ .data.table    0x0000000020000004        0xc libfoo.a(foo.o)
+                0x0000000020004000                PROVIDE (_estack = (ORIGIN (RAM) + LENGTH (RAM)))
+";
+
+/// Writes `parser` into XML and reads it back
+fn round_trip(parser: &Parser, skip_data: bool) -> Parser {
+    let sink = UTSinkSource::new();
+    let mut writer = XmlWriter::new(sink.clone(), "test.map");
+    writer.set_skip_data(skip_data);
+    parser.to_xml_writer(&mut writer);
+    drop(writer);
+
+    let mut reader = XmlReader::new(sink);
+    let result = reader.read().unwrap();
+    assert_eq!(reader.get_source(), Some("test.map"));
+    assert!(reader.get_datetime().is_some());
+    assert_eq!(reader.get_schema_version(), Some(SCHEMA_VERSION));
+    result
+}
+
+fn check_entry(actual: &Entry, expected: &Entry, skip_data: bool) {
+    assert_eq!(actual.get_name(), expected.get_name());
+    assert_eq!(actual.get_address(), expected.get_address());
+    assert_eq!(actual.get_original_size(), expected.get_original_size());
+    assert_eq!(actual.get_fill_size(), expected.get_fill_size());
+    assert_eq!(actual.get_fill_overlaps(), expected.get_fill_overlaps());
+    assert_eq!(actual.get_object_name(), expected.get_object_name());
+    assert_eq!(actual.get_library_name(), expected.get_library_name());
+    match skip_data {
+        true => assert_eq!(actual.get_data(), ""),
+        false => assert_eq!(actual.get_data(), expected.get_data()),
+    }
+}
+
+fn check_parser(actual: &Parser, expected: &Parser, skip_data: bool) {
+    assert_eq!(actual.get_memory_regions(), expected.get_memory_regions());
+    assert_eq!(
+        actual.get_memory_map_symbols(),
+        expected.get_memory_map_symbols()
+    );
+
+    let actual_segments = actual.get_memory_map_segments();
+    let expected_segments = expected.get_memory_map_segments();
+    assert_eq!(actual_segments.len(), expected_segments.len());
+    for (a, e) in actual_segments.iter().zip(expected_segments) {
+        assert_eq!(a.get_name(), e.get_name());
+        assert_eq!(a.get_address(), e.get_address());
+        assert_eq!(a.get_size(), e.get_size());
+        assert_eq!(a.get_load_address(), e.get_load_address());
+        assert_eq!(a.get_entries().len(), e.get_entries().len());
+        for (a, e) in a.get_entries().iter().zip(e.get_entries()) {
+            check_entry(a, e, skip_data);
+        }
+    }
+
+    let actual_objects = actual.get_memory_map_objects();
+    let expected_objects = expected.get_memory_map_objects();
+    assert_eq!(actual_objects.len(), expected_objects.len());
+    for (name, e) in expected_objects {
+        let a = actual_objects.get(name).unwrap();
+        let mut segments = e.get_all_segments();
+        segments.sort();
+        let mut actual_segments = a.get_all_segments();
+        actual_segments.sort();
+        assert_eq!(actual_segments, segments);
+        for segment in segments {
+            assert_eq!(a.get_segment_size(segment), e.get_segment_size(segment));
+        }
+    }
+}
+
+#[test]
+fn round_trip_with_data() {
+    let parser = Parser::parse(MAPFILE);
+
+    check_parser(&round_trip(&parser, false), &parser, false);
+}
+
+#[test]
+fn round_trip_without_data() {
+    let parser = Parser::parse(MAPFILE);
+
+    check_parser(&round_trip(&parser, true), &parser, true);
+}
+
+#[test]
+fn round_trip_empty() {
+    let parser = round_trip(&Parser::new(), true);
+
+    assert!(parser.get_memory_regions().is_empty());
+    assert!(parser.get_memory_map_segments().is_empty());
+    assert!(parser.get_memory_map_objects().is_empty());
+    assert!(parser.get_memory_map_symbols().is_empty());
+}
+
+#[test]
+fn invalid_root() {
+    let result = XmlReader::new("<report></report>".as_bytes()).read();
+
+    assert!(matches!(result, Err(XmlReadError::InvalidRoot(name)) if name == "report"));
+}
+
+#[test]
+fn missing_attribute() {
+    let data = r#"<mapfile><section name="MemoryMap"><segments count="1"><segment address="0x0" size="4" /></segments></section></mapfile>"#;
+    let result = XmlReader::new(data.as_bytes()).read();
+
+    assert!(matches!(
+        result,
+        Err(XmlReadError::MissingAttribute { element, attribute }) if element == "segment" && attribute == "name"
+    ));
+}
+
+#[test]
+fn invalid_value() {
+    let data = r#"<mapfile><section name="MemoryConfiguration"><regions count="1"><region name="RAM" origin="0xzz" length="4" used_size="0" /></regions></section></mapfile>"#;
+    let result = XmlReader::new(data.as_bytes()).read();
+
+    assert!(matches!(
+        result,
+        Err(XmlReadError::InvalidValue { attribute, value }) if attribute == "origin" && value == "0xzz"
+    ));
+}
+
+#[test]
+fn malformed() {
+    let result = XmlReader::new("<mapfile><section></mapfile>".as_bytes()).read();
+
+    assert!(matches!(result, Err(XmlReadError::Xml(_))));
+}
+
+#[test]
+fn objects_without_entry_object_names() {
+    // XML written by older releases, without entry `object` attribute
+    let data = r#"<mapfile><section name="MemoryMap"><segments count="1"><segment name=".text" address="0x0" size="4"><entries count="1"><entry name=".text.main" address="0x0" size="4" fill_size="0" fill_overlaps="false" /></entries></segment></segments><objects count="1"><object name="main.o"><segments count="1"><segment name=".text" size="4" /></segments></object></objects></section></mapfile>"#;
+    let parser = XmlReader::new(data.as_bytes()).read().unwrap();
+
+    let objects = parser.get_memory_map_objects();
+    assert_eq!(objects.len(), 1);
+    assert_eq!(objects["main.o"].get_segment_size(".text"), Some(4));
+}
@@ -1,7 +1,7 @@
 mod uthelper;
-use parser::xmlwriter::XmlWriter;
+use parser::xmlwriter::{self, XmlWriter, SCHEMA, SCHEMA_VERSION};
 use uthelper::*;
-use xml::ParserConfig;
+use xml::{reader::XmlEvent, ParserConfig};
 
 #[test]
 fn xml_writer_mapfile_event() {
@@ -23,3 +23,54 @@ fn xml_writer_mapfile_event() {
 
     check_end_document_event(parser.next().unwrap());
 }
+
+#[test]
+fn xml_writer_datetime_format() {
+    let sink = UTSinkSource::new();
+    let writer = XmlWriter::with_datetime_format(sink.clone(), "source", "%Y-%m-%dT%H:%M:%SZ");
+    drop(writer);
+
+    let mut parser = ParserConfig::default()
+        .ignore_root_level_whitespace(true)
+        .trim_whitespace(true)
+        .create_reader(sink);
+
+    check_start_document_event(parser.next().unwrap());
+    match parser.next().unwrap() {
+        XmlEvent::StartElement { attributes, .. } => {
+            let datetime = &attributes[0].value;
+            assert!(chrono::NaiveDateTime::parse_from_str(datetime, "%Y-%m-%dT%H:%M:%SZ").is_ok());
+        }
+        _ => panic!("Expected XmlEvent::StartElement!"),
+    }
+}
+
+#[test]
+fn datetime_format_validation() {
+    assert!(xmlwriter::is_valid_datetime_format(
+        xmlwriter::DEFAULT_DATETIME_FORMAT
+    ));
+    assert!(xmlwriter::is_valid_datetime_format("%s"));
+    assert!(!xmlwriter::is_valid_datetime_format("%Q"));
+}
+
+#[test]
+fn schema_version() {
+    // Version fixed in the schema must match the written one
+    let mut fixed = None;
+    for event in ParserConfig::default().create_reader(SCHEMA.as_bytes()) {
+        if let XmlEvent::StartElement { attributes, .. } = event.unwrap() {
+            let get = |name: &str| {
+                attributes
+                    .iter()
+                    .find(|a| a.name.local_name == name)
+                    .map(|a| a.value.clone())
+            };
+            if get("name").as_deref() == Some("schema_version") {
+                fixed = get("fixed");
+            }
+        }
+    }
+
+    assert_eq!(fixed.as_deref(), Some(SCHEMA_VERSION));
+}